@@ -0,0 +1,116 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+    sync::Arc,
+};
+
+use alloy::primitives::{TxHash, B256};
+use parking_lot::Mutex;
+use serde::Serialize;
+use tracing::{error, info};
+
+use cb_common::utils::utcnow_ms;
+
+/// The outcome of verifying an unblinded payload against the constraints of its slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadVerdict {
+    /// The payload matches the signed header and contains all constrained transactions.
+    Valid,
+    /// The block hash of the payload doesn't match the one in the signed header.
+    BlockHashMismatch,
+    /// The transactions root of the payload doesn't match the one in the signed header.
+    RootMismatch,
+    /// The payload is missing one or more constrained transactions.
+    MissingTransactions,
+    /// The payload could not be verified.
+    Error,
+}
+
+impl PayloadVerdict {
+    /// Returns the label used for this verdict in metrics.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Valid => "valid",
+            Self::BlockHashMismatch => "block_hash_mismatch",
+            Self::RootMismatch => "root_mismatch",
+            Self::MissingTransactions => "missing_transactions",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// A single entry in the payload audit log.
+#[derive(Debug, Clone, Serialize)]
+pub struct PayloadAuditRecord {
+    /// The time at which the payload was verified, in milliseconds since the UNIX epoch.
+    pub timestamp_ms: u64,
+    pub slot: u64,
+    pub block_hash: B256,
+    /// The transactions root of the header signed by the proposer.
+    pub header_transactions_root: B256,
+    /// The number of constrained transactions for the slot.
+    pub constrained_transactions: usize,
+    /// The constrained transactions that were not found in the payload.
+    pub missing_transactions: Vec<TxHash>,
+    pub verdict: PayloadVerdict,
+    /// A description of the failure, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl PayloadAuditRecord {
+    pub fn new(slot: u64, block_hash: B256, header_transactions_root: B256) -> Self {
+        Self {
+            timestamp_ms: utcnow_ms(),
+            slot,
+            block_hash,
+            header_transactions_root,
+            constrained_transactions: 0,
+            missing_transactions: Vec::new(),
+            verdict: PayloadVerdict::Valid,
+            error: None,
+        }
+    }
+}
+
+/// An append-only audit log of payload verifications. Every record is emitted as a tracing event
+/// with the `audit` target, and written as a JSON line to the configured file, if any.
+#[derive(Debug, Clone, Default)]
+pub struct AuditLog {
+    file: Option<Arc<Mutex<File>>>,
+}
+
+impl AuditLog {
+    /// Creates a new audit log. If `path` is provided, records are also appended to that file.
+    pub fn new(path: Option<&Path>) -> eyre::Result<Self> {
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        info!(path = %path.display(), "Writing payload audit log to file");
+
+        Ok(Self { file: Some(Arc::new(Mutex::new(file))) })
+    }
+
+    /// Records a payload verification.
+    pub fn record(&self, record: &PayloadAuditRecord) {
+        let line = match serde_json::to_string(record) {
+            Ok(line) => line,
+            Err(e) => {
+                error!(?e, "Failed to serialize payload audit record");
+                return;
+            }
+        };
+
+        info!(target: "audit", slot = record.slot, verdict = record.verdict.as_str(), "{line}");
+
+        if let Some(ref file) = self.file {
+            if let Err(e) = writeln!(file.lock(), "{line}") {
+                error!(?e, "Failed to write payload audit record");
+            }
+        }
+    }
+}
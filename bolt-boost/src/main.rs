@@ -4,6 +4,7 @@ use tracing_subscriber::EnvFilter;
 use cb_common::config::load_pbs_custom_config;
 use cb_pbs::{PbsService, PbsState};
 
+mod audit;
 mod constraints;
 mod error;
 mod metrics;
@@ -31,7 +32,7 @@ async fn main() -> Result<()> {
         tracing::info!("ID: {} - URI: {}", relay.id, relay.config.entry.url);
     }
 
-    let custom_state = BuilderState::from_config(extra);
+    let custom_state = BuilderState::from_config(extra)?;
     let state = PbsState::new(pbs_config).with_data(custom_state);
 
     metrics::init_metrics()?;
@@ -15,6 +15,7 @@ pub(crate) fn init_metrics() -> eyre::Result<()> {
     PbsService::register_metric(Box::new(RELAY_STATUS_CODE.clone()));
     PbsService::register_metric(Box::new(RELAY_INVALID_BIDS.clone()));
     PbsService::register_metric(Box::new(CONSTRAINTS_CACHE_SIZE.clone()));
+    PbsService::register_metric(Box::new(PAYLOAD_VERIFICATIONS.clone()));
 
     PbsService::init_metrics()
 }
@@ -58,4 +59,12 @@ lazy_static! {
     )
     .unwrap();
 
+    /// Results of verifying unblinded payloads against the constraints for their slot
+    pub static ref PAYLOAD_VERIFICATIONS: IntCounterVec = register_int_counter_vec_with_registry!(
+        "payload_verifications",
+        "Results of verifying unblinded payloads against constraints",
+        &["result"],
+        BOLT_BOOST_METRICS
+    )
+    .unwrap();
 }
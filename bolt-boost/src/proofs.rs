@@ -1,7 +1,16 @@
-use alloy::primitives::{TxHash, B256};
+use std::collections::HashSet;
+
+use alloy::primitives::{keccak256, TxHash, B256};
+use ssz_rs::{HashTreeRoot, List};
 
 use super::types::{ConstraintsWithProofData, InclusionProofs};
 
+/// The maximum number of bytes in a single transaction, as defined in the consensus specs.
+const MAX_BYTES_PER_TRANSACTION: usize = 1_073_741_824;
+
+/// The maximum number of transactions in an execution payload, as defined in the consensus specs.
+const MAX_TRANSACTIONS_PER_PAYLOAD: usize = 1_048_576;
+
 #[derive(Debug, thiserror::Error)]
 pub enum ProofError {
     #[error("Leaves and indices length mismatch")]
@@ -14,6 +23,16 @@ pub enum ProofError {
     VerificationFailed,
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum PayloadError {
+    #[error("Failed to compute the transactions root of the payload")]
+    Merkleization,
+    #[error("Transactions root mismatch: expected {expected}, got {got}")]
+    RootMismatch { expected: B256, got: B256 },
+    #[error("Constrained transactions missing from the payload: {0:?}")]
+    MissingTransactions(Vec<TxHash>),
+}
+
 /// Returns the length of the leaves that need to be proven (i.e. all transactions).
 fn total_leaves(constraints: &[ConstraintsWithProofData]) -> usize {
     constraints.iter().map(|c| c.proof_data.len()).sum()
@@ -76,6 +95,58 @@ pub fn verify_multiproofs(
     Ok(())
 }
 
+/// Verifies an unblinded execution payload against the constraints for its slot.
+///
+/// The transactions root is recomputed from the payload transactions and checked against
+/// `expected_root`, which should be the root of the header the proposer signed. Then every
+/// constrained transaction is looked up by hash in the payload.
+///
+/// Returns the recomputed transactions root on success.
+pub fn verify_payload_transactions<T: AsRef<[u8]>>(
+    constraints: &[ConstraintsWithProofData],
+    transactions: &[T],
+    expected_root: B256,
+) -> Result<B256, PayloadError> {
+    let root = transactions_root(transactions)?;
+    if root != expected_root {
+        return Err(PayloadError::RootMismatch { expected: expected_root, got: root });
+    }
+
+    // NOTE: for blob transactions the payload only contains the transaction without its sidecar,
+    // whose hash is the same as the one we saved in the constraints cache.
+    let payload_hashes = transactions
+        .iter()
+        .map(|tx| keccak256(tx.as_ref()))
+        .collect::<HashSet<_>>();
+
+    let missing = constraints
+        .iter()
+        .flat_map(|c| c.proof_data.iter().map(|(hash, _)| *hash))
+        .filter(|hash| !payload_hashes.contains(hash))
+        .collect::<Vec<_>>();
+
+    if !missing.is_empty() {
+        return Err(PayloadError::MissingTransactions(missing));
+    }
+
+    Ok(root)
+}
+
+/// Computes the SSZ hash tree root of a list of raw transactions, as found in the
+/// `transactions_root` field of an execution payload header.
+pub fn transactions_root<T: AsRef<[u8]>>(transactions: &[T]) -> Result<B256, PayloadError> {
+    let inner = transactions
+        .iter()
+        .map(|tx| List::<u8, MAX_BYTES_PER_TRANSACTION>::try_from(tx.as_ref().to_vec()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| PayloadError::Merkleization)?;
+
+    let list = List::<_, MAX_TRANSACTIONS_PER_PAYLOAD>::try_from(inner)
+        .map_err(|_| PayloadError::Merkleization)?;
+
+    list.hash_tree_root().map_err(|_| PayloadError::Merkleization)
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs::File;
@@ -88,9 +159,9 @@ mod tests {
 
     use crate::{
         constraints::ConstraintsCache,
-        proofs::verify_multiproofs,
+        proofs::{verify_multiproofs, verify_payload_transactions, PayloadError},
         testutil::*,
-        types::{InclusionProofs, SignedConstraints},
+        types::{ConstraintsMessage, ConstraintsWithProofData, InclusionProofs, SignedConstraints},
     };
 
     #[test]
@@ -204,6 +275,39 @@ mod tests {
         assert!(verify_multiproofs(&constraints_with_proof, &inclusion_proof, root).is_ok());
     }
 
+    #[test]
+    fn test_verify_payload_transactions() {
+        let (root, transactions) = read_test_transactions();
+
+        let constraints = ConstraintsMessage {
+            pubkey: Default::default(),
+            slot: 0,
+            top: false,
+            transactions: vec![transactions[26].clone(), transactions[3].clone()],
+        };
+        let constraints = vec![ConstraintsWithProofData::try_from(constraints).unwrap()];
+
+        let computed = verify_payload_transactions(&constraints, &transactions, root).unwrap();
+        assert_eq!(computed, root);
+
+        // A payload with a different root than the signed header must be rejected
+        assert!(matches!(
+            verify_payload_transactions(&constraints, &transactions, B256::ZERO),
+            Err(PayloadError::RootMismatch { .. })
+        ));
+
+        // A payload that doesn't contain a constrained transaction must be rejected, even if the
+        // root matches the header
+        let mut swapped = transactions.clone();
+        let removed = swapped.remove(26);
+        let swapped_root = super::transactions_root(&swapped).unwrap();
+        let err = verify_payload_transactions(&constraints, &swapped, swapped_root).unwrap_err();
+        let PayloadError::MissingTransactions(missing) = err else {
+            panic!("expected missing transactions error, got {err:?}");
+        };
+        assert_eq!(missing, vec![alloy::primitives::keccak256(&removed)]);
+    }
+
     /// Testdata from https://github.com/ferranbt/fastssz/blob/455b54c08c81c3a270b6a7160f92ce68408491d4/tests/codetrie_test.go#L195
     #[test]
     fn test_fastssz_multiproof() {
@@ -29,26 +29,29 @@ use cb_common::{
     constants::APPLICATION_BUILDER_DOMAIN,
     pbs::{
         error::{PbsError, ValidationError},
-        GetHeaderResponse, RelayClient, SignedExecutionPayloadHeader, EMPTY_TX_ROOT_HASH,
-        HEADER_START_TIME_UNIX_MS,
+        GetHeaderResponse, RelayClient, SignedBlindedBeaconBlock, SignedExecutionPayloadHeader,
+        SubmitBlindedBlockResponse, EMPTY_TX_ROOT_HASH, HEADER_START_TIME_UNIX_MS,
     },
     signature::verify_signed_message,
     types::Chain,
     utils::{get_user_agent, get_user_agent_with_version, ms_into_slot, utcnow_ms},
 };
-use cb_pbs::{register_validator, BuilderApi, BuilderApiState, PbsState};
+use cb_pbs::{register_validator, submit_block, BuilderApi, BuilderApiState, PbsState};
 
 use crate::metrics::{
-    GET_HEADER_WP_TAG, RELAY_INVALID_BIDS, RELAY_LATENCY, RELAY_STATUS_CODE, TIMEOUT_ERROR_CODE_STR,
+    GET_HEADER_WP_TAG, PAYLOAD_VERIFICATIONS, RELAY_INVALID_BIDS, RELAY_LATENCY,
+    RELAY_STATUS_CODE, TIMEOUT_ERROR_CODE_STR,
 };
 
 use super::{
+    audit::{AuditLog, PayloadAuditRecord, PayloadVerdict},
     constraints::ConstraintsCache,
     error::PbsClientError,
-    proofs::verify_multiproofs,
+    proofs::{verify_multiproofs, verify_payload_transactions, PayloadError},
     types::{
-        Config, GetHeaderParams, GetHeaderWithProofsResponse, RequestConfig, SignedConstraints,
-        SignedDelegation, SignedExecutionPayloadHeaderWithProofs, SignedRevocation,
+        Config, ConstraintsWithProofData, GetHeaderParams, GetHeaderWithProofsResponse,
+        RequestConfig, SignedConstraints, SignedDelegation, SignedExecutionPayloadHeaderWithProofs,
+        SignedRevocation,
     },
 };
 
@@ -69,18 +72,26 @@ pub struct BuilderState {
     constraints: ConstraintsCache,
     current_slot_info: Arc<Mutex<(u64, Uuid)>>,
     bid_cache: Arc<DashMap<u64, Vec<GetHeaderResponse>>>,
+    /// Constraints that have been removed from the cache in `get_header_with_proofs`, kept
+    /// around to verify the unblinded payload in `submit_block`.
+    payload_constraints: Arc<DashMap<u64, Vec<ConstraintsWithProofData>>>,
+    audit_log: AuditLog,
 }
 
 impl BuilderApiState for BuilderState {}
 
 impl BuilderState {
-    pub fn from_config(config: Config) -> Self {
-        Self {
+    pub fn from_config(config: Config) -> Result<Self> {
+        let audit_log = AuditLog::new(config.audit_log_path.as_deref())?;
+
+        Ok(Self {
             config,
             constraints: ConstraintsCache::new(),
             current_slot_info: Arc::new(Mutex::new((0, Uuid::new_v4()))),
             bid_cache: Arc::new(DashMap::new()),
-        }
+            payload_constraints: Arc::new(DashMap::new()),
+            audit_log,
+        })
     }
 
     pub fn get_or_update_slot_uuid(&self, last_slot: u64) -> Uuid {
@@ -107,9 +118,10 @@ impl BuilderState {
         slot_entry.iter().max_by_key(|bid| bid.data.message.value).cloned()
     }
 
-    /// Clear bids which are more than ~3 minutes old
+    /// Clear bids and payload constraints which are more than ~3 minutes old
     fn clear(&self, last_slot: u64) {
-        self.bid_cache.retain(|slot, _| last_slot.saturating_sub(*slot) < 15)
+        self.bid_cache.retain(|slot, _| last_slot.saturating_sub(*slot) < 15);
+        self.payload_constraints.retain(|slot, _| last_slot.saturating_sub(*slot) < 15);
     }
 }
 
@@ -138,6 +150,76 @@ impl BuilderApi<BuilderState> for ConstraintsApi {
         register_validator(registrations, req_headers, state).await
     }
 
+    /// Submit a signed blinded block to the relays and get the unblinded payload back.
+    ///
+    /// We intercept this call to verify that the payload returned by the relay contains all
+    /// the constrained transactions for the slot. The payload is returned regardless of the
+    /// outcome since the proposer has already signed the header, but the result is recorded
+    /// in metrics and in the audit log.
+    async fn submit_block(
+        signed_blinded_block: SignedBlindedBeaconBlock,
+        req_headers: HeaderMap,
+        state: PbsState<BuilderState>,
+    ) -> eyre::Result<SubmitBlindedBlockResponse> {
+        let slot = signed_blinded_block.message.slot;
+        let header = signed_blinded_block.message.body.execution_payload_header.clone();
+        let builder_state = state.data.clone();
+
+        let response = submit_block(signed_blinded_block, req_headers, state).await?;
+
+        let Some((_, constraints)) = builder_state.payload_constraints.remove(&slot) else {
+            debug!(slot, "No constraints to verify the payload against");
+            return Ok(response);
+        };
+
+        let payload = &response.data.execution_payload;
+        let mut record =
+            PayloadAuditRecord::new(slot, payload.block_hash, header.transactions_root);
+        record.constrained_transactions = constraints.iter().map(|c| c.proof_data.len()).sum();
+
+        if payload.block_hash != header.block_hash {
+            record.verdict = PayloadVerdict::BlockHashMismatch;
+            record.error = Some(format!(
+                "block hash mismatch: expected {}, got {}",
+                header.block_hash, payload.block_hash
+            ));
+        } else {
+            let start = Instant::now();
+            let transactions = payload.transactions.iter().map(|tx| &tx[..]).collect::<Vec<_>>();
+            match verify_payload_transactions(&constraints, &transactions, header.transactions_root)
+            {
+                Ok(_) => debug!(slot, "Verified payload in {:?}", start.elapsed()),
+                Err(e) => {
+                    record.verdict = match e {
+                        PayloadError::RootMismatch { .. } => PayloadVerdict::RootMismatch,
+                        PayloadError::MissingTransactions(ref missing) => {
+                            record.missing_transactions = missing.clone();
+                            PayloadVerdict::MissingTransactions
+                        }
+                        PayloadError::Merkleization => PayloadVerdict::Error,
+                    };
+                    record.error = Some(e.to_string());
+                }
+            }
+        }
+
+        if record.verdict == PayloadVerdict::Valid {
+            info!(slot, block_hash = %record.block_hash, "Payload contains all constraints");
+        } else {
+            error!(
+                slot,
+                block_hash = %record.block_hash,
+                error = ?record.error,
+                "Payload returned by relay doesn't satisfy the constraints"
+            );
+        }
+
+        PAYLOAD_VERIFICATIONS.with_label_values(&[record.verdict.as_str()]).inc();
+        builder_state.audit_log.record(&record);
+
+        Ok(response)
+    }
+
     /// Gets the extra routes for supporting the constraints API as defined in
     /// the spec: <https://docs.boltprotocol.xyz/technical-docs/api/builder>.
     fn extra_routes() -> Option<Router<PbsState<BuilderState>>> {
@@ -298,6 +380,11 @@ async fn get_header_with_proofs(
         }
     }
 
+    // Keep the constraints around to verify the payload once it gets unblinded
+    if let Some(constraints) = maybe_constraints {
+        state.data.payload_constraints.insert(params.slot, constraints);
+    }
+
     if let Some(winning_bid) = state.data.add_bids(params.slot, relay_bids) {
         let header_with_proofs = GetHeaderWithProofsResponse {
             data: SignedExecutionPayloadHeaderWithProofs {
@@ -9,7 +9,7 @@ use axum::http::HeaderMap;
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
-use std::ops::Deref;
+use std::{ops::Deref, path::PathBuf};
 use tracing::error;
 use tree_hash::TreeHash;
 
@@ -25,7 +25,11 @@ use cb_common::{
 pub type HashTreeRoot = tree_hash::Hash256;
 
 #[derive(Debug, Clone, Deserialize)]
-pub struct Config {}
+pub struct Config {
+    /// Optional path to a file where payload verification results are appended as JSON lines.
+    #[serde(default)]
+    pub audit_log_path: Option<PathBuf>,
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 pub struct GetHeaderParams {
@@ -1,4 +1,7 @@
-use std::path::PathBuf;
+use std::{
+    path::{is_separator, Path, PathBuf},
+    str::FromStr,
+};
 
 use alloy::{
     primitives::{Address, FixedBytes, B256, U256},
@@ -11,7 +14,7 @@ use clap::{
 };
 use reqwest::Url;
 
-use crate::common::{keystore::DEFAULT_KEYSTORE_PASSWORD, parse_ether_value};
+use crate::common::{keystore::DEFAULT_KEYSTORE_PASSWORD, network::NetworkSpec, parse_ether_value};
 
 /// `bolt` is a CLI tool to interact with bolt Protocol ✨
 #[derive(Parser, Debug, Clone)]
@@ -39,6 +42,12 @@ pub enum Cmd {
     /// Handle operators in the bolt network.
     Operators(OperatorsCommand),
 
+    /// Handle the commitment challenges of the bolt network.
+    Challenges(ChallengesCommand),
+
+    /// Verify the commitments issued by bolt sidecars.
+    Commitments(CommitmentsCommand),
+
     /// Useful data generation commands.
     Generate(GenerateCommand),
 
@@ -56,6 +65,8 @@ impl Cmd {
             Self::Send(cmd) => cmd.run().await,
             Self::Validators(cmd) => cmd.run().await,
             Self::Operators(cmd) => cmd.run().await,
+            Self::Challenges(cmd) => cmd.run().await,
+            Self::Commitments(cmd) => cmd.run().await,
             Self::Generate(cmd) => cmd.run(),
             Self::PubkeyHash(cmd) => cmd.run(),
         }
@@ -73,7 +84,8 @@ pub struct DelegateCommand {
     #[clap(long, env = "OUTPUT_FILE_PATH", default_value = "delegations.json")]
     pub out: String,
 
-    /// The chain for which the delegation message is intended.
+    /// The chain for which the delegation message is intended: one of "mainnet", "holesky",
+    /// "helder", "kurtosis", or the path to a consensus-spec `config.yaml` of a custom network.
     #[clap(long, env = "CHAIN")]
     pub chain: Chain,
 
@@ -188,6 +200,58 @@ pub enum ValidatorsSubcommand {
         #[clap(long, env = "ADMIN_PRIVATE_KEY")]
         admin_private_key: B256,
     },
+    /// Register validators with a BLS signature of the registration message by each of their
+    /// keys, which proves their ownership to the BoltValidatorsV2 contract.
+    RegisterSafe {
+        /// The URL of the RPC to broadcast the transactions.
+        #[clap(long, env = "RPC_URL")]
+        rpc_url: Url,
+
+        /// The max gas limit the validators are willing to reserve to commitments.
+        #[clap(long, env = "MAX_COMMITTED_GAS_LIMIT")]
+        max_committed_gas_limit: u32,
+
+        /// The authorized operator for the validators.
+        #[clap(long, env = "AUTHORIZED_OPERATOR")]
+        authorized_operator: Address,
+
+        /// The private key to sign the transactions with, which becomes the controller of the
+        /// validators.
+        #[clap(long, env = "ADMIN_PRIVATE_KEY", hide_env_values = true)]
+        admin_private_key: B256,
+
+        /// The source of the validator keys. The registration message is not a consensus
+        /// message, so only local keys can sign it.
+        #[clap(subcommand)]
+        source: KeysSource,
+    },
+
+    /// Update the max gas limit that validators are willing to reserve to commitments.
+    UpdateGasLimit {
+        /// The URL of the RPC to broadcast the transactions.
+        #[clap(long, env = "RPC_URL")]
+        rpc_url: Url,
+
+        /// The new max gas limit of the validators.
+        #[clap(long, env = "MAX_COMMITTED_GAS_LIMIT")]
+        max_committed_gas_limit: u32,
+
+        /// The pubkey hashes of the validators, as listed by `bolt validators list`.
+        #[clap(long, env = "PUBKEY_HASHES", value_delimiter = ',', required = true)]
+        pubkey_hashes: Vec<FixedBytes<20>>,
+
+        /// The private key of the controller of the validators.
+        #[clap(long, env = "ADMIN_PRIVATE_KEY", hide_env_values = true)]
+        admin_private_key: B256,
+    },
+
+    /// List all the validators registered in bolt.
+    List {
+        /// The URL of the RPC to read data from.
+        #[clap(long, env = "RPC_URL")]
+        rpc_url: Url,
+    },
+
     /// Check the status of a validator (batch).
     Status {
         /// The URL of the RPC to broadcast the transaction.
@@ -368,6 +432,137 @@ pub enum SymbioticSubcommand {
     },
 }
 
+#[derive(Debug, Clone, Parser)]
+pub struct ChallengesCommand {
+    #[clap(subcommand)]
+    pub subcommand: ChallengesSubcommand,
+}
+
+#[derive(Debug, Clone, Parser)]
+pub enum ChallengesSubcommand {
+    /// Open a challenge against signed commitments suspected to be broken, paying the challenge
+    /// bond set in the BoltParameters contract.
+    Open {
+        /// The URL of the RPC to read data from and broadcast the transaction.
+        #[clap(long, env = "RPC_URL")]
+        rpc_url: Url,
+        /// The address of the BoltChallenger contract.
+        #[clap(long, env = "BOLT_CHALLENGER_ADDRESS")]
+        challenger: Address,
+        /// The private key to sign the transaction with, which pays the bond.
+        #[clap(long, env = "PRIVATE_KEY", hide_env_values = true)]
+        private_key: B256,
+        /// The path to the JSON file of the signed commitments to challenge, as a single
+        /// commitment or an array of commitments with the same signer and slot.
+        #[clap(long, env = "COMMITMENTS_PATH")]
+        commitments_path: PathBuf,
+    },
+
+    /// List the open challenges, or all the challenges stored by the contract.
+    List {
+        /// The URL of the RPC to read data from.
+        #[clap(long, env = "RPC_URL")]
+        rpc_url: Url,
+        /// The address of the BoltChallenger contract.
+        #[clap(long, env = "BOLT_CHALLENGER_ADDRESS")]
+        challenger: Address,
+        /// Whether to list all the challenges stored by the contract, not only the open ones.
+        /// Resolved challenges are removed from the contract, so they are never listed.
+        #[clap(long, default_value_t = false)]
+        all: bool,
+    },
+
+    /// Show a challenge by its ID.
+    Show {
+        /// The URL of the RPC to read data from.
+        #[clap(long, env = "RPC_URL")]
+        rpc_url: Url,
+        /// The address of the BoltChallenger contract.
+        #[clap(long, env = "BOLT_CHALLENGER_ADDRESS")]
+        challenger: Address,
+        /// The ID of the challenge.
+        id: B256,
+    },
+
+    /// Resolve a challenge that was not defended before its expiry, as breached.
+    ResolveExpired {
+        /// The URL of the RPC to read data from and broadcast the transaction.
+        #[clap(long, env = "RPC_URL")]
+        rpc_url: Url,
+        /// The address of the BoltChallenger contract.
+        #[clap(long, env = "BOLT_CHALLENGER_ADDRESS")]
+        challenger: Address,
+        /// The private key to sign the transaction with.
+        #[clap(long, env = "PRIVATE_KEY", hide_env_values = true)]
+        private_key: B256,
+        /// The ID of the challenge.
+        id: B256,
+    },
+
+    /// Watch the challenges opened against the commitments of an operator, and defend them
+    /// with inclusion proofs before they expire. Runs until interrupted.
+    Defend {
+        /// The URL of the RPC to read data from and broadcast the transactions. It must serve
+        /// `eth_getProof` for recent blocks.
+        #[clap(long, env = "RPC_URL")]
+        rpc_url: Url,
+        /// The address of the BoltChallenger contract.
+        #[clap(long, env = "BOLT_CHALLENGER_ADDRESS")]
+        challenger: Address,
+        /// The private key to sign the resolution transactions with. Any funded account can
+        /// defend a challenge.
+        #[clap(long, env = "PRIVATE_KEY", hide_env_values = true)]
+        private_key: B256,
+        /// The commitment signer of the operator to defend. Defaults to the address of the
+        /// private key.
+        #[clap(long, env = "COMMITMENT_SIGNER")]
+        commitment_signer: Option<Address>,
+        /// The interval between polls for new challenges, in seconds.
+        #[clap(long, env = "POLL_INTERVAL", default_value_t = 12)]
+        poll_interval: u64,
+    },
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct CommitmentsCommand {
+    #[clap(subcommand)]
+    pub subcommand: CommitmentsSubcommand,
+}
+
+#[derive(Debug, Clone, Parser)]
+pub enum CommitmentsSubcommand {
+    /// Verify an inclusion commitment returned by a sidecar: recompute the digest of the request
+    /// and recover its signer. Runs offline unless an RPC URL is provided.
+    Verify {
+        /// The path to the JSON file of the inclusion commitment.
+        commitment_path: PathBuf,
+        /// The expected signer of the commitment, i.e. the commitment signer of the operator.
+        #[clap(long, env = "COMMITMENT_SIGNER")]
+        signer: Option<Address>,
+        /// The path to the JSON file of the signed constraints accompanying the commitment, as a
+        /// single object or an array. Their BLS signatures are verified with the commit-boost
+        /// domain of the chain.
+        #[clap(long, env = "CONSTRAINTS_PATH", requires = "chain")]
+        constraints_path: Option<PathBuf>,
+        /// The chain of the constraints: one of "mainnet", "holesky", "helder", "kurtosis", or
+        /// the path to a consensus-spec `config.yaml` of a custom network. Its preset also sets
+        /// the number of slots per epoch used to look up the proposer on the beacon node.
+        #[clap(long, env = "CHAIN")]
+        chain: Option<Chain>,
+        /// The URL of an execution RPC. If set, checks on chain that the signer is the operator
+        /// registered for the proposer of the slot in the BoltManager contract.
+        #[clap(long)]
+        rpc_url: Option<Url>,
+        /// The BLS public key of the proposer of the slot, for the on-chain check.
+        #[clap(long, env = "PROPOSER_PUBKEY")]
+        proposer_pubkey: Option<String>,
+        /// The URL of a beacon node to look up the proposer of the slot with, for the on-chain
+        /// check. Ignored if the proposer public key is provided.
+        #[clap(long, env = "BEACON_URL")]
+        beacon_url: Option<Url>,
+    },
+}
+
 #[derive(Debug, Clone, Parser)]
 pub struct GenerateCommand {
     #[clap(subcommand)]
@@ -515,13 +710,37 @@ pub struct DirkTlsCredentials {
 }
 
 /// Supported chains for the CLI
-#[derive(Debug, Clone, Copy, ValueEnum, Hash, PartialEq, Eq)]
-#[clap(rename_all = "kebab_case")]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 pub enum Chain {
     Mainnet,
     Holesky,
     Helder,
     Kurtosis,
+    /// A custom network, loaded from a consensus-spec `config.yaml`.
+    Custom(NetworkSpec),
+}
+
+impl FromStr for Chain {
+    type Err = eyre::Report;
+
+    /// Parse a chain from its name, or from the path to the `config.yaml` of a custom network.
+    ///
+    /// The value is only considered a path if it exists or contains a path separator.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mainnet" => Ok(Self::Mainnet),
+            "holesky" => Ok(Self::Holesky),
+            "helder" => Ok(Self::Helder),
+            "kurtosis" => Ok(Self::Kurtosis),
+            path if Path::new(path).exists() || path.contains(is_separator) => {
+                NetworkSpec::from_config_path(path).map(Self::Custom)
+            }
+            other => Err(eyre::eyre!(
+                "unknown chain {other}: expected mainnet, holesky, helder, kurtosis \
+                 or the path to the config.yaml of a custom network"
+            )),
+        }
+    }
 }
 
 impl Chain {
@@ -532,6 +751,15 @@ impl Chain {
             Self::Holesky => [1, 1, 112, 0],
             Self::Helder => [16, 0, 0, 0],
             Self::Kurtosis => [16, 0, 0, 56],
+            Self::Custom(spec) => spec.genesis_fork_version,
+        }
+    }
+
+    /// Get the number of slots in an epoch for the given chain.
+    pub fn slots_per_epoch(&self) -> u64 {
+        match self {
+            Self::Custom(spec) => spec.slots_per_epoch,
+            _ => 32,
         }
     }
 
@@ -547,6 +775,8 @@ impl Chain {
     }
 
     /// Get the chain ID for the given chain. Returns `None` if the chain ID is not supported.
+    ///
+    /// Custom networks are never returned, since they are only identified by their config file.
     pub fn from_id(id: u64) -> Option<Self> {
         match id {
             1 => Some(Self::Mainnet),
@@ -563,6 +793,9 @@ impl Chain {
     }
 
     /// Tries to get the chain ID from an online provider.
+    ///
+    /// Like [Chain::from_id], this never returns a custom network: on-chain commands are only
+    /// supported on the chains with known bolt deployments.
     pub async fn try_from_provider<T, P>(provider: &P) -> eyre::Result<Self>
     where
         T: Transport + Clone,
@@ -587,11 +820,25 @@ const fn cli_styles() -> clap::builder::Styles {
 
 #[cfg(test)]
 mod tests {
-    use super::Opts;
+    use std::str::FromStr;
+
+    use super::{Chain, Opts};
 
     #[test]
     pub fn verify_cli() {
         use clap::CommandFactory;
         Opts::command().debug_assert()
     }
+
+    #[test]
+    fn test_parse_chain() {
+        assert_eq!(Chain::from_str("holesky").unwrap(), Chain::Holesky);
+
+        // Unknown names are not mistaken for paths
+        let err = Chain::from_str("sepolia").unwrap_err();
+        assert!(err.to_string().starts_with("unknown chain sepolia"));
+
+        let err = Chain::from_str("./missing/config.yaml").unwrap_err();
+        assert!(err.to_string().starts_with("Failed to read network config"));
+    }
 }
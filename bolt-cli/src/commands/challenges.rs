@@ -0,0 +1,653 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use alloy::{
+    eips::{eip2718::Encodable2718, BlockId, BlockNumberOrTag},
+    network::EthereumWallet,
+    primitives::{keccak256, Address, Bytes, B256, U256},
+    providers::{Provider, ProviderBuilder},
+    rpc::types::BlockTransactionsKind,
+    signers::local::PrivateKeySigner,
+    sol_types::SolInterface,
+    transports::Transport,
+};
+use chrono::DateTime;
+use eyre::{bail, ensure, Context, ContextCompat};
+use serde::Deserialize;
+use tracing::{error, info, warn};
+
+use crate::{
+    cli::{ChallengesCommand, ChallengesSubcommand},
+    common::{proofs::transactions_trie_proofs, request_confirmation, try_parse_contract_error},
+    contracts::bolt::{
+        BoltChallengerV1::{
+            self, BoltChallengerV1Errors, BoltChallengerV1Instance, Challenge, ChallengeStatus,
+            Proof, SignedCommitment, TransactionData,
+        },
+        BoltParametersV1,
+    },
+};
+
+/// The max number of blocks to query `ChallengeOpened` logs for in a single request, as most
+/// RPC providers limit the block range of `eth_getLogs`.
+const MAX_LOGS_BLOCK_RANGE: u64 = 1_000;
+
+impl ChallengesCommand {
+    /// Run the `challenges` subcommand.
+    pub async fn run(self) -> eyre::Result<()> {
+        match self.subcommand {
+            ChallengesSubcommand::Open { rpc_url, challenger, private_key, commitments_path } => {
+                let signer =
+                    PrivateKeySigner::from_bytes(&private_key).wrap_err("valid private key")?;
+
+                let provider = ProviderBuilder::new()
+                    .with_recommended_fillers()
+                    .wallet(EthereumWallet::from(signer))
+                    .on_http(rpc_url);
+
+                let commitments = read_signed_commitments(&commitments_path)?;
+                let challenger = BoltChallengerV1::new(challenger, provider.clone());
+
+                let parameters = challenger.parameters().call().await?._0;
+                let bond =
+                    BoltParametersV1::new(parameters, provider).CHALLENGE_BOND().call().await?._0;
+
+                let id = challenge_id(&commitments);
+                info!(%id, count = commitments.len(), %bond, "Opening challenge");
+
+                request_confirmation();
+
+                match challenger.openChallenge(commitments).value(bond).send().await {
+                    Ok(pending) => {
+                        info!(
+                            hash = ?pending.tx_hash(),
+                            "openChallenge transaction sent, awaiting receipt..."
+                        );
+                        let receipt = pending.get_receipt().await?;
+                        if !receipt.status() {
+                            bail!("Transaction failed: {:?}", receipt)
+                        }
+
+                        info!(%id, "Successfully opened challenge");
+                    }
+                    Err(e) => match try_parse_contract_error::<BoltChallengerV1Errors>(e)? {
+                        BoltChallengerV1Errors::ChallengeAlreadyExists(_) => {
+                            bail!("A challenge for these commitments already exists")
+                        }
+                        BoltChallengerV1Errors::IncorrectChallengeBond(_) => {
+                            bail!("Incorrect challenge bond, expected {bond}")
+                        }
+                        BoltChallengerV1Errors::SlotInTheFuture(_) => {
+                            bail!("The slot of the commitments is in the future")
+                        }
+                        BoltChallengerV1Errors::BlockIsNotFinalized(_) => {
+                            bail!("The slot of the commitments is not finalized yet")
+                        }
+                        BoltChallengerV1Errors::EmptyCommitments(_) => {
+                            bail!("No commitments to challenge")
+                        }
+                        BoltChallengerV1Errors::UnexpectedMixedSlots(_) => {
+                            bail!("The commitments must all be for the same slot")
+                        }
+                        BoltChallengerV1Errors::UnexpectedMixedSigners(_) => {
+                            bail!("The commitments must all be signed by the same signer")
+                        }
+                        BoltChallengerV1Errors::UnexpectedMixedSenders(_) => {
+                            bail!("The committed transactions must all have the same sender")
+                        }
+                        BoltChallengerV1Errors::UnexpectedNonceOrder(_) => {
+                            bail!("The committed transactions must be ordered by nonce")
+                        }
+                        other => {
+                            bail!("Unexpected error with selector {:?}", other.selector())
+                        }
+                    },
+                }
+
+                Ok(())
+            }
+
+            ChallengesSubcommand::List { rpc_url, challenger, all } => {
+                let provider = ProviderBuilder::new().on_http(rpc_url);
+                let challenger = BoltChallengerV1::new(challenger, provider.clone());
+
+                let parameters = challenger.parameters().call().await?._0;
+                let max_duration = BoltParametersV1::new(parameters, provider)
+                    .MAX_CHALLENGE_DURATION()
+                    .call()
+                    .await?
+                    ._0;
+
+                let challenges = if all {
+                    challenger.getAllChallenges().call().await?._0
+                } else {
+                    challenger.getOpenChallenges().call().await?._0
+                };
+
+                println!("Found {} challenges", challenges.len());
+                for challenge in challenges {
+                    println!();
+                    println!("id:         {}", challenge.id);
+                    println!("status:     {}", status_name(&challenge.status));
+                    println!("slot:       {}", challenge.targetSlot);
+                    println!("signer:     {}", challenge.commitmentSigner);
+                    println!("receiver:   {}", challenge.commitmentReceiver);
+                    println!("challenger: {}", challenge.challenger);
+                    println!("txs:        {}", challenge.committedTxs.len());
+                    println!("opened at:  {}", format_timestamp(challenge.openedAt));
+                    println!("expires at: {}", format_timestamp(challenge.openedAt + max_duration));
+                }
+
+                Ok(())
+            }
+
+            ChallengesSubcommand::Show { rpc_url, challenger, id } => {
+                let provider = ProviderBuilder::new().on_http(rpc_url);
+                let challenger = BoltChallengerV1::new(challenger, provider);
+
+                match challenger.getChallengeByID(id).call().await {
+                    Ok(challenge) => {
+                        println!("{}", serde_json::to_string_pretty(&challenge._0)?);
+                    }
+                    Err(e) => match try_parse_contract_error::<BoltChallengerV1Errors>(e)? {
+                        BoltChallengerV1Errors::ChallengeDoesNotExist(_) => {
+                            bail!("Challenge {id} does not exist")
+                        }
+                        other => {
+                            bail!("Unexpected error with selector {:?}", other.selector())
+                        }
+                    },
+                }
+
+                Ok(())
+            }
+
+            ChallengesSubcommand::ResolveExpired { rpc_url, challenger, private_key, id } => {
+                let signer =
+                    PrivateKeySigner::from_bytes(&private_key).wrap_err("valid private key")?;
+
+                let provider = ProviderBuilder::new()
+                    .with_recommended_fillers()
+                    .wallet(EthereumWallet::from(signer))
+                    .on_http(rpc_url);
+
+                let challenger = BoltChallengerV1::new(challenger, provider);
+
+                info!(%id, "Resolving expired challenge");
+
+                request_confirmation();
+
+                match challenger.resolveExpiredChallenge(id).send().await {
+                    Ok(pending) => {
+                        info!(
+                            hash = ?pending.tx_hash(),
+                            "resolveExpiredChallenge transaction sent, awaiting receipt..."
+                        );
+                        let receipt = pending.get_receipt().await?;
+                        if !receipt.status() {
+                            bail!("Transaction failed: {:?}", receipt)
+                        }
+
+                        info!(%id, "Successfully resolved expired challenge as breached");
+                    }
+                    Err(e) => match try_parse_contract_error::<BoltChallengerV1Errors>(e)? {
+                        BoltChallengerV1Errors::ChallengeNotExpired(_) => {
+                            bail!("Challenge {id} has not expired yet")
+                        }
+                        BoltChallengerV1Errors::ChallengeAlreadyResolved(_) => {
+                            bail!("Challenge {id} is already resolved")
+                        }
+                        BoltChallengerV1Errors::ChallengeDoesNotExist(_) => {
+                            bail!("Challenge {id} does not exist")
+                        }
+                        other => {
+                            bail!("Unexpected error with selector {:?}", other.selector())
+                        }
+                    },
+                }
+
+                Ok(())
+            }
+
+            ChallengesSubcommand::Defend {
+                rpc_url,
+                challenger,
+                private_key,
+                commitment_signer,
+                poll_interval,
+            } => {
+                let signer =
+                    PrivateKeySigner::from_bytes(&private_key).wrap_err("valid private key")?;
+                let commitment_signer = commitment_signer.unwrap_or(signer.address());
+
+                let provider = ProviderBuilder::new()
+                    .with_recommended_fillers()
+                    .wallet(EthereumWallet::from(signer))
+                    .on_http(rpc_url);
+
+                let poll_interval = Duration::from_secs(poll_interval);
+                defend_challenges(provider, challenger, commitment_signer, poll_interval).await
+            }
+        }
+    }
+}
+
+/// A signed commitment to a single transaction, in the format accepted by the challenger: the
+/// raw transaction is either in `tx`, or the only element of `txs` as returned by the sidecar.
+#[derive(Debug, Deserialize)]
+struct SignedCommitmentJson {
+    slot: u64,
+    signature: Bytes,
+    #[serde(default)]
+    tx: Option<Bytes>,
+    #[serde(default)]
+    txs: Vec<Bytes>,
+}
+
+/// The contents of a signed commitments file: a single commitment or an array of them.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SignedCommitmentsFile {
+    One(SignedCommitmentJson),
+    Many(Vec<SignedCommitmentJson>),
+}
+
+/// Reads the signed commitments to challenge from the JSON file at the given path.
+fn read_signed_commitments(path: &Path) -> eyre::Result<Vec<SignedCommitment>> {
+    let contents = fs::read_to_string(path)
+        .wrap_err_with(|| format!("failed to read commitments from {}", path.display()))?;
+
+    let commitments = match serde_json::from_str(&contents)? {
+        SignedCommitmentsFile::One(commitment) => vec![commitment],
+        SignedCommitmentsFile::Many(commitments) => commitments,
+    };
+
+    commitments.into_iter().map(signed_commitment_from_json).collect()
+}
+
+/// Converts a signed commitment from its JSON format to the one of the challenger contract.
+fn signed_commitment_from_json(commitment: SignedCommitmentJson) -> eyre::Result<SignedCommitment> {
+    let tx = match (commitment.tx, commitment.txs.as_slice()) {
+        (Some(tx), []) => tx,
+        (None, [tx]) => tx.clone(),
+        (None, []) => bail!("commitment for slot {} has no transaction", commitment.slot),
+        _ => bail!("commitment for slot {} has more than one transaction", commitment.slot),
+    };
+
+    let mut signature = commitment.signature.to_vec();
+    ensure!(signature.len() == 65, "commitment signature must be 65 bytes long");
+    // The contract only recovers signatures with a `v` of 27 or 28
+    if signature[64] < 27 {
+        signature[64] += 27;
+    }
+
+    Ok(SignedCommitment { slot: commitment.slot, signature: signature.into(), signedTx: tx })
+}
+
+/// Computes the ID of the challenge of the given commitments, as the contract does:
+/// `keccak( keccak(signature_1) || keccak(signature_2) || ... )`.
+fn challenge_id(commitments: &[SignedCommitment]) -> B256 {
+    let hashes = commitments
+        .iter()
+        .flat_map(|commitment| keccak256(&commitment.signature).0)
+        .collect::<Vec<_>>();
+    keccak256(hashes)
+}
+
+/// Returns the human-readable name of a challenge status.
+fn status_name(status: &ChallengeStatus) -> &'static str {
+    match status {
+        ChallengeStatus::Open => "open",
+        ChallengeStatus::Defended => "defended",
+        ChallengeStatus::Breached => "breached",
+        ChallengeStatus::__Invalid => "invalid",
+    }
+}
+
+/// Formats a UNIX timestamp in seconds as an RFC 3339 date.
+fn format_timestamp(timestamp: u64) -> String {
+    DateTime::from_timestamp(timestamp as i64, 0)
+        .map(|date| date.to_rfc3339())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+/// Watches the challenges opened against the commitments of the given signer, and defends them
+/// until they expire. Challenges that fail to be defended are retried on every poll.
+async fn defend_challenges<T, P>(
+    provider: P,
+    challenger: Address,
+    commitment_signer: Address,
+    poll_interval: Duration,
+) -> eyre::Result<()>
+where
+    T: Transport + Clone,
+    P: Provider<T> + Clone,
+{
+    let challenger = BoltChallengerV1::new(challenger, provider.clone());
+
+    let parameters = challenger.parameters().call().await?._0;
+    let max_duration = BoltParametersV1::new(parameters, provider.clone())
+        .MAX_CHALLENGE_DURATION()
+        .call()
+        .await?
+        ._0;
+
+    // Challenges opened before starting are found among the open ones. The block number is
+    // read first, so that challenges opened in the meantime are found by the logs instead.
+    let mut next_block = provider.get_block_number().await?;
+    let mut pending = challenger
+        .getOpenChallenges()
+        .call()
+        .await?
+        ._0
+        .into_iter()
+        .filter(|challenge| challenge.commitmentSigner == commitment_signer)
+        .map(|challenge| (challenge.id, challenge))
+        .collect::<HashMap<_, _>>();
+
+    info!(%commitment_signer, open = pending.len(), max_duration, "Watching challenges");
+
+    let mut interval = tokio::time::interval(poll_interval);
+    loop {
+        interval.tick().await;
+
+        match poll_opened_challenges(&provider, &challenger, commitment_signer, next_block).await {
+            Ok((opened, latest)) => {
+                for challenge in opened {
+                    warn!(id = %challenge.id, slot = %challenge.targetSlot, "Challenge opened");
+                    pending.insert(challenge.id, challenge);
+                }
+                next_block = latest + 1;
+            }
+            Err(err) => warn!(?err, "Failed to poll new challenges"),
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let ids = pending.keys().copied().collect::<Vec<_>>();
+        for id in ids {
+            let deadline = pending[&id].openedAt + max_duration;
+            if now > deadline {
+                error!(%id, "Challenge expired before it could be defended");
+                pending.remove(&id);
+                continue;
+            }
+
+            match defend_challenge(&provider, &challenger, &pending[&id]).await {
+                Ok(()) => {
+                    pending.remove(&id);
+                }
+                Err(err) => warn!(%id, deadline, ?err, "Failed to defend challenge, retrying"),
+            }
+        }
+    }
+}
+
+/// Returns the challenges opened against the given signer since the given block, and the
+/// latest block number up to which they were looked for. The logs are queried in ranges of
+/// [MAX_LOGS_BLOCK_RANGE] blocks.
+async fn poll_opened_challenges<T, P>(
+    provider: &P,
+    challenger: &BoltChallengerV1Instance<T, P>,
+    commitment_signer: Address,
+    from_block: u64,
+) -> eyre::Result<(Vec<Challenge>, u64)>
+where
+    T: Transport + Clone,
+    P: Provider<T> + Clone,
+{
+    let latest = provider.get_block_number().await?;
+    if latest < from_block {
+        return Ok((Vec::new(), from_block - 1));
+    }
+
+    let mut challenges = Vec::new();
+    for start in (from_block..=latest).step_by(MAX_LOGS_BLOCK_RANGE as usize) {
+        let end = (start + MAX_LOGS_BLOCK_RANGE - 1).min(latest);
+        let events = challenger
+            .ChallengeOpened_filter()
+            .topic3(commitment_signer.into_word())
+            .from_block(start)
+            .to_block(end)
+            .query()
+            .await?;
+
+        for (event, _) in events {
+            challenges.push(challenger.getChallengeByID(event.challengeId).call().await?._0);
+        }
+    }
+
+    Ok((challenges, latest))
+}
+
+/// Builds the inclusion proof of the committed transactions of the challenge, and submits it.
+async fn defend_challenge<T, P>(
+    provider: &P,
+    challenger: &BoltChallengerV1Instance<T, P>,
+    challenge: &Challenge,
+) -> eyre::Result<()>
+where
+    T: Transport + Clone,
+    P: Provider<T> + Clone,
+{
+    let proof = build_proof(provider, challenge).await?;
+
+    match challenger.resolveOpenChallenge(challenge.id, proof).send().await {
+        Ok(pending) => {
+            info!(
+                id = %challenge.id,
+                hash = ?pending.tx_hash(),
+                "resolveOpenChallenge transaction sent, awaiting receipt..."
+            );
+            let receipt = pending.get_receipt().await?;
+            if !receipt.status() {
+                bail!("Transaction failed: {:?}", receipt)
+            }
+
+            info!(id = %challenge.id, "Successfully defended challenge");
+            Ok(())
+        }
+        Err(e) => match try_parse_contract_error::<BoltChallengerV1Errors>(e)? {
+            BoltChallengerV1Errors::ChallengeAlreadyResolved(_) => {
+                info!(id = %challenge.id, "Challenge already resolved");
+                Ok(())
+            }
+            BoltChallengerV1Errors::ChallengeDoesNotExist(_) => {
+                warn!(id = %challenge.id, "Challenge does not exist");
+                Ok(())
+            }
+            other => bail!("Failed to resolve challenge, error selector {:?}", other.selector()),
+        },
+    }
+}
+
+/// Builds the proof of inclusion of the committed transactions of the challenge, which must all
+/// be included in the same block.
+///
+/// Committed transactions that are not included must have been invalidated by their sender, as
+/// the contract considers a challenge defended if the nonce of the sender at the top of the
+/// block is higher than a committed one, or if its balance can't pay for the gas limit of a
+/// committed transaction at the base fee of the block. The account proof alone defends those.
+async fn build_proof<T, P>(provider: &P, challenge: &Challenge) -> eyre::Result<Proof>
+where
+    T: Transport + Clone,
+    P: Provider<T> + Clone,
+{
+    let mut inclusion_block_number = None;
+    let mut indexes = Vec::with_capacity(challenge.committedTxs.len());
+
+    for tx in &challenge.committedTxs {
+        // The transactions after the first one not included are never reached by the contract
+        let Some(receipt) = provider.get_transaction_receipt(tx.txHash).await? else { break };
+        let (Some(number), Some(index)) = (receipt.block_number, receipt.transaction_index) else {
+            break;
+        };
+
+        if inclusion_block_number.is_some_and(|n| n != number) {
+            bail!("committed transactions are included in different blocks");
+        }
+        inclusion_block_number = Some(number);
+        indexes.push(index as usize);
+    }
+
+    // Without any included transaction, the invalidation is proven at the latest block, where
+    // the nonce of the sender is the highest. It must be within the lookback of the contract.
+    let number = match inclusion_block_number {
+        Some(number) => number,
+        None => provider.get_block_number().await?,
+    };
+    let previous_number = number - 1;
+
+    let previous_block = provider
+        .get_block_by_number(
+            BlockNumberOrTag::Number(previous_number),
+            BlockTransactionsKind::Hashes,
+        )
+        .await?
+        .wrap_err_with(|| format!("block {previous_number} not found"))?;
+    let inclusion_block = provider
+        .get_block_by_number(BlockNumberOrTag::Number(number), BlockTransactionsKind::Full)
+        .await?
+        .wrap_err_with(|| format!("block {number} not found"))?;
+
+    let previous_header = Bytes::from(alloy_rlp::encode(&previous_block.header.inner));
+    let inclusion_header = Bytes::from(alloy_rlp::encode(&inclusion_block.header.inner));
+    ensure!(keccak256(&previous_header) == previous_block.header.hash, "invalid previous header");
+    ensure!(
+        keccak256(&inclusion_header) == inclusion_block.header.hash,
+        "invalid inclusion header"
+    );
+
+    let encoded_txs = inclusion_block
+        .transactions
+        .txns()
+        .map(|tx| Bytes::from(tx.inner.encoded_2718()))
+        .collect::<Vec<_>>();
+    let mut txs_proof = transactions_trie_proofs(&encoded_txs, &indexes);
+    ensure!(
+        txs_proof.root == inclusion_block.header.transactions_root,
+        "transactions root mismatch in block {number}"
+    );
+
+    // The nonce and balance of the sender are checked at the top of the inclusion block
+    let account = provider
+        .get_proof(challenge.commitmentReceiver, Vec::new())
+        .block_id(BlockId::number(previous_number))
+        .await?;
+
+    let included = indexes.len();
+    if let Some(tx) = challenge.committedTxs.get(included) {
+        let base_fee = U256::from(inclusion_block.header.base_fee_per_gas.unwrap_or_default());
+        ensure!(
+            is_invalidated(
+                account.nonce,
+                account.balance,
+                base_fee,
+                &challenge.committedTxs[..=included]
+            ),
+            "committed transaction {} is not included nor invalidated in block {number}",
+            tx.txHash
+        );
+
+        // The contract expects a proof for each committed transaction, even unused ones
+        txs_proof.proofs.resize(challenge.committedTxs.len(), Bytes::new());
+        indexes.resize(challenge.committedTxs.len(), 0);
+        info!(id = %challenge.id, included, "Defending challenge with invalidated transactions");
+    }
+
+    Ok(Proof {
+        // The contract checks the previous header against `blockhash(inclusionBlockNumber)`,
+        // so the number of the previous block must be provided for the hashes to match.
+        inclusionBlockNumber: U256::from(previous_number),
+        previousBlockHeaderRLP: previous_header,
+        inclusionBlockHeaderRLP: inclusion_header,
+        accountMerkleProof: Bytes::from(alloy_rlp::encode(&account.account_proof)),
+        txMerkleProofs: txs_proof.proofs,
+        txIndexesInBlock: indexes.into_iter().map(U256::from).collect(),
+    })
+}
+
+/// Returns whether the contract considers the challenge defended because the sender
+/// invalidated one of the given committed transactions, given its nonce and balance at the top
+/// of the block and the base fee of the block. Mirrors the checks of `BoltChallengerV1._resolve`,
+/// which are evaluated before the inclusion proof of each transaction.
+///
+/// NOTE: the transactions before the invalidated one must be included in the block, which is
+/// checked by their inclusion proofs.
+fn is_invalidated(nonce: u64, balance: U256, base_fee: U256, txs: &[TransactionData]) -> bool {
+    let (mut nonce, mut balance) = (U256::from(nonce), balance);
+
+    for tx in txs {
+        let max_fee = base_fee * tx.gasLimit;
+        if nonce > tx.nonce || balance < max_fee {
+            return true;
+        }
+
+        balance -= max_fee;
+        nonce += U256::from(1);
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{B256, U256};
+
+    use crate::contracts::bolt::BoltChallengerV1::TransactionData;
+
+    use super::{challenge_id, is_invalidated, signed_commitment_from_json, SignedCommitmentsFile};
+
+    #[test]
+    fn test_is_invalidated() {
+        let gwei = U256::from(1_000_000_000u64);
+        let tx = |nonce: u64| TransactionData {
+            txHash: B256::with_last_byte(nonce as u8),
+            nonce: U256::from(nonce),
+            gasLimit: U256::from(21_000),
+        };
+        let txs = [tx(5), tx(6)];
+        let max_fee = gwei * U256::from(21_000);
+
+        // The sender sent another transaction with the committed nonce
+        assert!(is_invalidated(6, U256::MAX, gwei, &txs[..1]));
+        assert!(!is_invalidated(5, U256::MAX, gwei, &txs[..1]));
+
+        // The sender can't pay for the gas limit of the committed transaction at the base fee
+        assert!(is_invalidated(5, max_fee - U256::from(1), gwei, &txs[..1]));
+
+        // The balance is spent by the first transaction, included in the block
+        assert!(is_invalidated(5, max_fee, gwei, &txs));
+        assert!(!is_invalidated(5, max_fee * U256::from(2), gwei, &txs));
+    }
+
+    #[test]
+    fn test_signed_commitments_from_json() -> eyre::Result<()> {
+        let json = r#"[
+            { "slot": 1, "signature": "0x{sig}", "tx": "0x02" },
+            { "slot": 1, "signature": "0x{sig}", "txs": ["0x03"] },
+            { "slot": 1, "signature": "0x{sig}", "txs": ["0x04", "0x05"] }
+        ]"#
+        .replace("{sig}", &"01".repeat(65));
+
+        let SignedCommitmentsFile::Many(commitments) = serde_json::from_str(&json)? else {
+            panic!("expected an array of commitments");
+        };
+        let mut commitments = commitments.into_iter().map(signed_commitment_from_json);
+
+        let first = commitments.next().unwrap()?;
+        assert_eq!(first.signedTx.as_ref(), [0x02]);
+        assert_eq!(first.signature[64], 28, "v must be normalized");
+
+        let second = commitments.next().unwrap()?;
+        assert_eq!(second.signedTx.as_ref(), [0x03]);
+        assert!(commitments.next().unwrap().is_err(), "multiple txs are not challengeable");
+
+        assert_ne!(challenge_id(&[first.clone()]), challenge_id(&[first, second]));
+
+        Ok(())
+    }
+}
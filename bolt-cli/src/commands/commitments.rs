@@ -0,0 +1,334 @@
+use std::{fs, path::Path};
+
+use alloy::{
+    consensus::transaction::PooledTransaction,
+    eips::eip2718::Decodable2718,
+    primitives::{keccak256, Bytes, PrimitiveSignature, TxHash, B256},
+    providers::ProviderBuilder,
+    signers::k256::sha2::{Digest, Sha256},
+    sol_types::SolInterface,
+};
+use ethereum_consensus::crypto::{PublicKey as BlsPublicKey, Signature as BlsSignature};
+use eyre::{bail, ensure, Context, ContextCompat, Result};
+use reqwest::Url;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Value;
+use tracing::{info, warn};
+
+use crate::{
+    cli::{Chain, CommitmentsCommand, CommitmentsSubcommand},
+    common::{
+        hash::compress_bls_pubkey, parse_bls_public_key, signing::verify_commit_boost_root,
+        try_parse_contract_error,
+    },
+    contracts::{
+        bolt::BoltManager::{self, BoltManagerErrors},
+        deployments_for_chain,
+    },
+};
+
+impl CommitmentsCommand {
+    /// Run the `commitments` subcommand.
+    pub async fn run(self) -> Result<()> {
+        match self.subcommand {
+            CommitmentsSubcommand::Verify {
+                commitment_path,
+                signer,
+                constraints_path,
+                chain,
+                rpc_url,
+                proposer_pubkey,
+                beacon_url,
+            } => {
+                let commitment: InclusionCommitment = read_json(&commitment_path)?;
+                let tx_hashes = commitment.tx_hashes()?;
+
+                let digest = commitment_digest(&tx_hashes, commitment.slot);
+                let recovered = commitment
+                    .signature()?
+                    .recover_address_from_prehash(&digest)
+                    .wrap_err("failed to recover the signer of the commitment")?;
+                info!(
+                    slot = commitment.slot,
+                    %digest,
+                    signer = %recovered,
+                    "Recovered commitment signer"
+                );
+
+                if let Some(expected) = signer {
+                    ensure!(
+                        recovered == expected,
+                        "Commitment signed by {recovered}, expected {expected}"
+                    );
+                    info!(%expected, "Commitment signer matches the expected one");
+                }
+
+                if let Some(path) = constraints_path {
+                    let chain = chain.wrap_err("the chain is required to verify constraints")?;
+                    let constraints = match read_json(&path)? {
+                        SignedConstraintsFile::One(constraints) => vec![constraints],
+                        SignedConstraintsFile::Many(constraints) => constraints,
+                    };
+
+                    verify_constraints(&constraints, commitment.slot, &tx_hashes, chain)?;
+                    info!(count = constraints.len(), "Constraints signatures are valid");
+                }
+
+                if let Some(rpc_url) = rpc_url {
+                    let provider = ProviderBuilder::new().on_http(rpc_url);
+                    let onchain = Chain::try_from_provider(&provider).await?;
+
+                    let proposer = match (proposer_pubkey, beacon_url) {
+                        (Some(pubkey), _) => parse_bls_public_key(&pubkey)?,
+                        (None, Some(beacon_url)) => {
+                            // A custom network passed with `--chain` may use a different preset
+                            let slots_per_epoch = chain.unwrap_or(onchain).slots_per_epoch();
+                            fetch_proposer_pubkey(&beacon_url, commitment.slot, slots_per_epoch)
+                                .await?
+                        }
+                        (None, None) => {
+                            bail!("the proposer pubkey or a beacon URL is required on chain")
+                        }
+                    };
+
+                    let manager =
+                        BoltManager::new(deployments_for_chain(onchain).bolt.manager, provider);
+
+                    let pubkey_hash = compress_bls_pubkey(&proposer);
+                    let status = match manager.getProposerStatus(pubkey_hash).call().await {
+                        Ok(status) => status._0,
+                        Err(e) => match try_parse_contract_error::<BoltManagerErrors>(e)? {
+                            BoltManagerErrors::ValidatorDoesNotExist(_) => {
+                                bail!("Proposer {proposer} is not registered in bolt")
+                            }
+                            other => {
+                                bail!("Unexpected error with selector {:?}", other.selector())
+                            }
+                        },
+                    };
+
+                    ensure!(
+                        status.operator == recovered,
+                        "Commitment signed by {recovered}, but the operator of the proposer is {}",
+                        status.operator
+                    );
+                    if !status.active {
+                        warn!(operator = %status.operator, "Proposer operator is not active");
+                    }
+                    info!(
+                        %proposer,
+                        operator = %status.operator,
+                        "Commitment signer is the registered operator of the proposer"
+                    );
+                }
+
+                info!("Commitment verified successfully");
+                Ok(())
+            }
+        }
+    }
+}
+
+/// An inclusion commitment, as returned by a sidecar.
+#[derive(Debug, Deserialize)]
+struct InclusionCommitment {
+    /// The slot at which the transactions must be included.
+    slot: u64,
+    /// The EIP-2718 encoded committed transactions.
+    txs: Vec<Bytes>,
+    /// The ECDSA signature of the commitment digest by the operator, with a parity of 0 or 1.
+    signature: Bytes,
+}
+
+impl InclusionCommitment {
+    /// Returns the parsed signature of the commitment.
+    fn signature(&self) -> Result<PrimitiveSignature> {
+        PrimitiveSignature::from_raw(&self.signature).wrap_err("invalid commitment signature")
+    }
+
+    /// Returns the hashes of the committed transactions.
+    fn tx_hashes(&self) -> Result<Vec<TxHash>> {
+        self.txs.iter().map(decode_tx_hash).collect()
+    }
+}
+
+/// Signed constraints, as sent by a sidecar to the relays.
+#[derive(Debug, Deserialize)]
+struct SignedConstraints {
+    message: ConstraintsMessage,
+    signature: BlsSignature,
+}
+
+/// The message of signed constraints.
+#[derive(Debug, Deserialize)]
+struct ConstraintsMessage {
+    pubkey: BlsPublicKey,
+    slot: u64,
+    top: bool,
+    transactions: Vec<Bytes>,
+}
+
+impl ConstraintsMessage {
+    /// Returns the digest of the message, which is signed with the commit-boost domain.
+    /// digest = sha256(pubkey | le_bytes(slot) | top | tx_hash1 | tx_hash2 | ...)
+    fn digest(&self, tx_hashes: &[TxHash]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.pubkey.as_ref());
+        hasher.update(self.slot.to_le_bytes());
+        hasher.update((self.top as u8).to_le_bytes());
+
+        for hash in tx_hashes {
+            hasher.update(hash);
+        }
+
+        hasher.finalize().into()
+    }
+}
+
+/// The contents of a signed constraints file: a single object or an array of them.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SignedConstraintsFile {
+    One(SignedConstraints),
+    Many(Vec<SignedConstraints>),
+}
+
+/// Reads and deserializes the JSON file at the given path.
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let contents =
+        fs::read_to_string(path).wrap_err_with(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_str(&contents).wrap_err_with(|| format!("invalid JSON in {}", path.display()))
+}
+
+/// Returns the hash of an EIP-2718 encoded transaction, in its pooled form.
+fn decode_tx_hash(raw: &Bytes) -> Result<TxHash> {
+    let tx = PooledTransaction::decode_2718(&mut raw.as_ref())
+        .wrap_err("failed to decode committed transaction")?;
+    Ok(*tx.hash())
+}
+
+/// Computes the digest of an inclusion request, which is signed by the operator.
+/// digest = keccak256(tx_hash1 | tx_hash2 | ... | le_bytes(slot))
+fn commitment_digest(tx_hashes: &[TxHash], slot: u64) -> B256 {
+    let mut data = Vec::with_capacity(tx_hashes.len() * 32 + 8);
+    for hash in tx_hashes {
+        data.extend_from_slice(hash.as_slice());
+    }
+    data.extend_from_slice(&slot.to_le_bytes());
+
+    keccak256(&data)
+}
+
+/// Verifies the BLS signatures of the constraints, and that they cover all the committed
+/// transactions of the slot.
+fn verify_constraints(
+    constraints: &[SignedConstraints],
+    slot: u64,
+    committed: &[TxHash],
+    chain: Chain,
+) -> Result<()> {
+    let mut constrained = Vec::new();
+
+    for signed in constraints {
+        let message = &signed.message;
+        ensure!(
+            message.slot == slot,
+            "Constraints for slot {}, expected slot {slot}",
+            message.slot
+        );
+
+        let tx_hashes =
+            message.transactions.iter().map(decode_tx_hash).collect::<Result<Vec<_>>>()?;
+        let signature = blst::min_pk::Signature::from_bytes(signed.signature.as_ref())
+            .map_err(|e| eyre::eyre!("Failed to parse signature: {:?}", e))?;
+
+        verify_commit_boost_root(
+            message.pubkey.clone(),
+            message.digest(&tx_hashes),
+            &signature,
+            &chain,
+        )
+        .wrap_err_with(|| format!("invalid constraints signature by {}", message.pubkey))?;
+
+        constrained.extend(tx_hashes);
+    }
+
+    for hash in committed {
+        ensure!(constrained.contains(hash), "Committed transaction {hash} is not constrained");
+    }
+
+    Ok(())
+}
+
+/// Fetches the public key of the proposer of the given slot from the beacon node.
+async fn fetch_proposer_pubkey(
+    beacon_url: &Url,
+    slot: u64,
+    slots_per_epoch: u64,
+) -> Result<BlsPublicKey> {
+    let epoch = slot / slots_per_epoch;
+    let url = beacon_url.join(&format!("eth/v1/validator/duties/proposer/{epoch}"))?;
+    let res = reqwest::get(url).await?.error_for_status()?.json::<Value>().await?;
+
+    let duties = res.pointer("/data").and_then(Value::as_array).wrap_err("missing duties")?;
+    for duty in duties {
+        let duty_slot = duty.pointer("/slot").and_then(Value::as_str).wrap_err("missing slot")?;
+        if duty_slot.parse::<u64>()? == slot {
+            let pubkey =
+                duty.pointer("/pubkey").and_then(Value::as_str).wrap_err("missing pubkey")?;
+            return parse_bls_public_key(pubkey);
+        }
+    }
+
+    bail!("no proposer duty found for slot {slot}")
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        consensus::{SignableTransaction, TxEip1559, TxEnvelope},
+        eips::eip2718::Encodable2718,
+        network::TxSignerSync,
+        primitives::{hex, U256},
+        signers::{local::PrivateKeySigner, SignerSync},
+    };
+
+    use super::{commitment_digest, InclusionCommitment};
+
+    #[test]
+    fn test_verify_inclusion_commitment() -> eyre::Result<()> {
+        let user = PrivateKeySigner::random();
+        let operator = PrivateKeySigner::random();
+
+        let mut tx = TxEip1559 {
+            chain_id: 1,
+            gas_limit: 21_000,
+            value: U256::from(1),
+            ..Default::default()
+        };
+        let signature = user.sign_transaction_sync(&mut tx)?;
+        let envelope = TxEnvelope::from(tx.into_signed(signature));
+        let raw = envelope.encoded_2718();
+
+        let tx_hash = *envelope.tx_hash();
+        let digest = commitment_digest(&[tx_hash], 42);
+        let signature = operator.sign_hash_sync(&digest)?;
+
+        // Sidecars encode the parity of the signature as 0 or 1
+        let mut signature_bytes = signature.as_bytes();
+        signature_bytes[64] -= 27;
+
+        let json = format!(
+            r#"{{ "slot": 42, "txs": ["{}"], "signature": "{}" }}"#,
+            hex::encode_prefixed(&raw),
+            hex::encode_prefixed(signature_bytes)
+        );
+        let commitment: InclusionCommitment = serde_json::from_str(&json)?;
+
+        assert_eq!(commitment.tx_hashes()?, vec![tx_hash]);
+        let recovered = commitment.signature()?.recover_address_from_prehash(&digest)?;
+        assert_eq!(recovered, operator.address());
+
+        Ok(())
+    }
+}
@@ -1,13 +1,12 @@
 use ethereum_consensus::crypto::{PublicKey as BlsPublicKey, Signature as BlsSignature};
 use eyre::Result;
-use lighthouse_eth2_keystore::Keystore;
 use tracing::debug;
 
 use crate::{
     cli::{Action, Chain},
     common::{
-        keystore::{keystore_paths, KeystoreError, KeystoreSecret},
-        signing::compute_commit_boost_signing_root,
+        keystore::{decrypt_secret_keys, KeystoreSecret},
+        signing::{compute_commit_boost_signing_root, BLS_DST_PREFIX},
     },
 };
 
@@ -29,31 +28,30 @@ pub fn generate_from_keystore(
     chain: Chain,
     action: Action,
 ) -> Result<Vec<SignedMessage>> {
-    let keystores_paths = keystore_paths(keys_path)?;
-    let mut signed_messages = Vec::with_capacity(keystores_paths.len());
-    debug!("Found {} keys in the keystore", keystores_paths.len());
+    let secret_keys = decrypt_secret_keys(keys_path, &keystore_secret)?;
+    let mut signed_messages = Vec::with_capacity(secret_keys.len());
+    debug!("Found {} keys in the keystore", secret_keys.len());
 
-    for path in keystores_paths {
-        let ks = Keystore::from_json_file(path).map_err(KeystoreError::Eth2Keystore)?;
-        let password = keystore_secret.get(ks.pubkey()).ok_or(KeystoreError::MissingPassword)?;
-        let kp = ks.decrypt_keypair(password.as_bytes()).map_err(KeystoreError::Eth2Keystore)?;
-        let validator_pubkey = BlsPublicKey::try_from(kp.pk.serialize().to_vec().as_ref())?;
-        let validator_private_key = kp.sk;
+    for validator_private_key in secret_keys {
+        let validator_pubkey =
+            BlsPublicKey::try_from(validator_private_key.sk_to_pk().to_bytes().as_ref())?;
 
         match action {
             Action::Delegate => {
                 let message = DelegationMessage::new(validator_pubkey, delegatee_pubkey.clone());
                 let signing_root = compute_commit_boost_signing_root(message.digest(), &chain)?;
-                let signature = validator_private_key.sign(signing_root.0.into());
-                let signature = BlsSignature::try_from(signature.serialize().as_ref())?;
+                let signature =
+                    validator_private_key.sign(signing_root.as_ref(), BLS_DST_PREFIX, &[]);
+                let signature = BlsSignature::try_from(signature.to_bytes().as_ref())?;
                 let signed = SignedDelegation { message, signature };
                 signed_messages.push(SignedMessage::Delegation(signed));
             }
             Action::Revoke => {
                 let message = RevocationMessage::new(validator_pubkey, delegatee_pubkey.clone());
                 let signing_root = compute_commit_boost_signing_root(message.digest(), &chain)?;
-                let signature = validator_private_key.sign(signing_root.0.into());
-                let signature = BlsSignature::try_from(signature.serialize().as_ref())?;
+                let signature =
+                    validator_private_key.sign(signing_root.as_ref(), BLS_DST_PREFIX, &[]);
+                let signature = BlsSignature::try_from(signature.to_bytes().as_ref())?;
                 let signed = SignedRevocation { message, signature };
                 signed_messages.push(SignedMessage::Revocation(signed));
             }
@@ -65,9 +63,17 @@ pub fn generate_from_keystore(
 
 #[cfg(test)]
 mod tests {
+    use ethereum_consensus::crypto::{PublicKey as BlsPublicKey, Signature as BlsSignature};
+    use lighthouse_eth2_keystore::Keystore;
+
     use crate::{
         cli::{Action, Chain},
-        common::{keystore, parse_bls_public_key},
+        commands::delegate::types::SignedMessage,
+        common::{
+            keystore::{self, KeystoreError},
+            parse_bls_public_key,
+            signing::compute_commit_boost_signing_root,
+        },
     };
 
     use super::generate_from_keystore;
@@ -98,4 +104,48 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_delegation_signatures_match_lighthouse_keypairs() -> eyre::Result<()> {
+        let keys_path = env!("CARGO_MANIFEST_DIR").to_string() + "/test_data/lighthouse/validators";
+        let secrets_path = env!("CARGO_MANIFEST_DIR").to_string() + "/test_data/lighthouse/secrets";
+
+        let delegatee_pubkey = "0x83eeddfac5e60f8fe607ee8713efb8877c295ad9f8ca075f4d8f6f2ae241a30dd57f78f6f3863a9fe0d5b5db9d550b93";
+        let delegatee_pubkey = parse_bls_public_key(delegatee_pubkey)?;
+        let chain = Chain::Mainnet;
+
+        let signed_delegations = generate_from_keystore(
+            &keys_path,
+            keystore::KeystoreSecret::from_directory(&secrets_path)?,
+            delegatee_pubkey,
+            chain,
+            Action::Delegate,
+        )?;
+
+        // Sign the same messages with the lighthouse keypairs decrypted from the keystores
+        let keystore_secret = keystore::KeystoreSecret::from_directory(&secrets_path)?;
+        let keystores_paths = keystore::keystore_paths(&keys_path)?;
+        assert_eq!(signed_delegations.len(), keystores_paths.len());
+
+        for (path, signed_message) in keystores_paths.into_iter().zip(signed_delegations) {
+            let SignedMessage::Delegation(signed) = signed_message else {
+                panic!("expected a signed delegation");
+            };
+
+            let ks = Keystore::from_json_file(path).map_err(KeystoreError::Eth2Keystore)?;
+            let password = keystore_secret.get(ks.pubkey()).expect("to get the password");
+            let kp =
+                ks.decrypt_keypair(password.as_bytes()).map_err(KeystoreError::Eth2Keystore)?;
+
+            let signing_root = compute_commit_boost_signing_root(signed.message.digest(), &chain)?;
+            let signature = kp.sk.sign(signing_root.0.into());
+            let signature = BlsSignature::try_from(signature.serialize().as_ref())?;
+
+            let validator_pubkey = BlsPublicKey::try_from(kp.pk.serialize().to_vec().as_ref())?;
+            assert_eq!(signed.message.validator_pubkey, validator_pubkey);
+            assert_eq!(signed.signature, signature);
+        }
+
+        Ok(())
+    }
 }
@@ -16,6 +16,12 @@ pub mod validators;
 /// Module for the operators-related commands to interact with the bolt network.
 pub mod operators;
 
+/// Module for the challenges-related commands to interact with the bolt network.
+pub mod challenges;
+
+/// Module for the commitments-related commands to verify the commitments of bolt sidecars.
+pub mod commitments;
+
 /// Module for generating various types of data like BLS keys.
 pub mod generate;
 
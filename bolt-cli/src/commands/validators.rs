@@ -1,20 +1,36 @@
 use alloy::{
-    network::EthereumWallet, providers::ProviderBuilder, signers::local::PrivateKeySigner,
-    sol_types::SolInterface,
+    network::EthereumWallet,
+    primitives::{Address, U256},
+    providers::{Provider, ProviderBuilder},
+    signers::local::PrivateKeySigner,
+    sol_types::{SolInterface, SolValue},
+};
+use blst::min_pk::{
+    PublicKey as BlstPublicKey, SecretKey as BlstSecretKey, Signature as BlstSignature,
 };
 use ethereum_consensus::crypto::PublicKey as BlsPublicKey;
-use eyre::Context;
+use eyre::{bail, Context};
 use tracing::{info, warn};
 
 use crate::{
-    cli::{Chain, ValidatorsCommand, ValidatorsSubcommand},
-    common::{hash::compress_bls_pubkey, request_confirmation, try_parse_contract_error},
+    cli::{Chain, KeysSource, ValidatorsCommand, ValidatorsSubcommand},
+    common::{
+        hash::compress_bls_pubkey,
+        keystore::{decrypt_secret_keys, KeystoreSecret},
+        request_confirmation, try_parse_contract_error,
+    },
     contracts::{
-        bolt::BoltValidators::{self, BoltValidatorsErrors},
+        bolt::{
+            BoltValidators::{self, BoltValidatorsErrors},
+            BoltValidatorsV2::{self, BoltValidatorsV2Errors, G1Point, G2Point},
+        },
         deployments_for_chain,
     },
 };
 
+/// The domain separation tag of the BLS signatures verified by the BoltValidatorsV2 contract.
+const REGISTRATION_DST: &[u8] = b"";
+
 impl ValidatorsCommand {
     pub async fn run(self) -> eyre::Result<()> {
         match self.subcommand {
@@ -99,6 +115,191 @@ impl ValidatorsCommand {
                 Ok(())
             }
 
+            ValidatorsSubcommand::RegisterSafe {
+                rpc_url,
+                max_committed_gas_limit,
+                authorized_operator,
+                admin_private_key,
+                source,
+            } => {
+                let signer = PrivateKeySigner::from_bytes(&admin_private_key)
+                    .wrap_err("valid private key")?;
+                let controller = signer.address();
+
+                let provider = ProviderBuilder::new()
+                    .with_recommended_fillers()
+                    .wallet(EthereumWallet::from(signer))
+                    .on_http(rpc_url);
+
+                let chain = Chain::try_from_provider(&provider).await?;
+                let chain_id = provider.get_chain_id().await?;
+
+                let bolt_validators_address = deployments_for_chain(chain).bolt.validators;
+                let bolt_validators = BoltValidatorsV2::new(bolt_validators_address, provider);
+
+                let keys = load_secret_keys(source)?;
+
+                info!(
+                    validators = keys.len(),
+                    %max_committed_gas_limit,
+                    %authorized_operator,
+                    ?chain,
+                    "Registering validators with BLS signatures"
+                );
+
+                request_confirmation();
+
+                for key in keys {
+                    // The registration message commits to the sequence number the validator
+                    // will get, which depends on the number of validators registered before it.
+                    let registered = bolt_validators.getAllValidators().call().await?._0.len();
+                    let sequence = registered as u32 + 1;
+
+                    let message = registration_message(chain_id, controller, sequence);
+                    let signature = key.sign(&message, REGISTRATION_DST, &[]);
+
+                    let pending = bolt_validators
+                        .registerValidator(
+                            g1_point(&key.sk_to_pk()),
+                            g2_point(&signature),
+                            max_committed_gas_limit,
+                            authorized_operator,
+                        )
+                        .send()
+                        .await;
+
+                    match pending {
+                        Ok(pending) => {
+                            info!(
+                                hash = ?pending.tx_hash(),
+                                "registerValidator transaction sent, awaiting receipt..."
+                            );
+                            let receipt = pending.get_receipt().await?;
+                            if !receipt.status() {
+                                eyre::bail!("Transaction failed: {:?}", receipt)
+                            }
+
+                            info!("Successfully registered validator");
+                        }
+                        Err(e) => match try_parse_contract_error::<BoltValidatorsV2Errors>(e)? {
+                            BoltValidatorsV2Errors::ValidatorAlreadyExists(b) => {
+                                eyre::bail!(
+                                    "Validator already exists (pubkeyHash: {:?})",
+                                    b.pubkeyHash
+                                )
+                            }
+                            BoltValidatorsV2Errors::InvalidBLSSignature(_) => {
+                                eyre::bail!(
+                                    "Invalid BLS signature over the registration message with \
+                                     sequence number {sequence}. The sequence number is stale \
+                                     if other validators were registered in the meantime"
+                                )
+                            }
+                            BoltValidatorsV2Errors::InvalidAuthorizedOperator(_) => {
+                                eyre::bail!("Invalid authorized operator")
+                            }
+                            BoltValidatorsV2Errors::InvalidPubkey(_) => {
+                                eyre::bail!("Invalid validator public key")
+                            }
+                            other => unreachable!(
+                                "Unexpected error with selector {:?}",
+                                other.selector()
+                            ),
+                        },
+                    }
+                }
+
+                Ok(())
+            }
+
+            ValidatorsSubcommand::UpdateGasLimit {
+                rpc_url,
+                max_committed_gas_limit,
+                pubkey_hashes,
+                admin_private_key,
+            } => {
+                let signer = PrivateKeySigner::from_bytes(&admin_private_key)
+                    .wrap_err("valid private key")?;
+
+                let provider = ProviderBuilder::new()
+                    .with_recommended_fillers()
+                    .wallet(EthereumWallet::from(signer))
+                    .on_http(rpc_url);
+
+                let chain = Chain::try_from_provider(&provider).await?;
+
+                let bolt_validators_address = deployments_for_chain(chain).bolt.validators;
+                let bolt_validators = BoltValidatorsV2::new(bolt_validators_address, provider);
+
+                info!(
+                    validators = pubkey_hashes.len(),
+                    %max_committed_gas_limit,
+                    ?chain,
+                    "Updating max committed gas limit"
+                );
+
+                request_confirmation();
+
+                for pubkey_hash in pubkey_hashes {
+                    match bolt_validators
+                        .updateMaxCommittedGasLimit(pubkey_hash, max_committed_gas_limit)
+                        .send()
+                        .await
+                    {
+                        Ok(pending) => {
+                            info!(
+                                hash = ?pending.tx_hash(),
+                                %pubkey_hash,
+                                "updateMaxCommittedGasLimit transaction sent, awaiting receipt..."
+                            );
+                            let receipt = pending.get_receipt().await?;
+                            if !receipt.status() {
+                                eyre::bail!("Transaction failed: {:?}", receipt)
+                            }
+
+                            info!(%pubkey_hash, "Successfully updated max committed gas limit");
+                        }
+                        Err(e) => match try_parse_contract_error::<BoltValidatorsV2Errors>(e)? {
+                            BoltValidatorsV2Errors::UnauthorizedCaller(_) => {
+                                eyre::bail!("Not the controller of validator {pubkey_hash}")
+                            }
+                            BoltValidatorsV2Errors::ValidatorDoesNotExist(_) => {
+                                eyre::bail!("Validator {pubkey_hash} is not registered")
+                            }
+                            other => unreachable!(
+                                "Unexpected error with selector {:?}",
+                                other.selector()
+                            ),
+                        },
+                    }
+                }
+
+                Ok(())
+            }
+
+            ValidatorsSubcommand::List { rpc_url } => {
+                let provider = ProviderBuilder::new().on_http(rpc_url);
+
+                let chain = Chain::try_from_provider(&provider).await?;
+
+                let registry = deployments_for_chain(chain).bolt.validators;
+                let bolt_validators = BoltValidatorsV2::new(registry, provider);
+
+                let validators = bolt_validators.getAllValidators().call().await?._0;
+                info!(validators = validators.len(), %registry, ?chain, "Registered validators");
+
+                for info in validators {
+                    info!(
+                        pubkey_hash = %info.pubkeyHash,
+                        operator = %info.authorizedOperator,
+                        controller = %info.controller,
+                        gas_limit = info.maxCommittedGasLimit,
+                    );
+                }
+
+                Ok(())
+            }
+
             ValidatorsSubcommand::Status { rpc_url, pubkeys_path, pubkeys } => {
                 let provider = ProviderBuilder::new().on_http(rpc_url);
 
@@ -149,6 +350,58 @@ impl ValidatorsCommand {
     }
 }
 
+/// Loads the BLS secret keys of the validators to register from the given source.
+fn load_secret_keys(source: KeysSource) -> eyre::Result<Vec<BlstSecretKey>> {
+    match source {
+        KeysSource::SecretKeys { secret_keys } => secret_keys
+            .iter()
+            .map(|sk| {
+                let bytes = hex::decode(sk.trim().trim_start_matches("0x"))?;
+                BlstSecretKey::from_bytes(&bytes)
+                    .map_err(|e| eyre::eyre!("Failed to parse secret key: {:?}", e))
+            })
+            .collect(),
+        KeysSource::LocalKeystore { opts } => {
+            let keystore_secret = KeystoreSecret::from_keystore_options(&opts)?;
+            decrypt_secret_keys(&opts.path, &keystore_secret)
+        }
+        // Remote signers only sign the signing roots of consensus messages
+        KeysSource::Dirk { .. } | KeysSource::Web3Signer { .. } => {
+            bail!("Safe registration requires local keys: use `secret-keys` or `local-keystore`")
+        }
+    }
+}
+
+/// Returns the registration message of the validator with the given sequence number, as
+/// reconstructed by the BoltValidatorsV2 contract:
+/// `abi.encodePacked(block.chainid, msg.sender, sequenceNumber)`.
+fn registration_message(chain_id: u64, controller: Address, sequence: u32) -> Vec<u8> {
+    (U256::from(chain_id), controller, sequence).abi_encode_packed()
+}
+
+/// Splits a 48-byte base field element into the two 32-byte limbs used by the contracts.
+fn fp_limbs(bytes: &[u8]) -> [U256; 2] {
+    [U256::from_be_slice(&bytes[..16]), U256::from_be_slice(&bytes[16..48])]
+}
+
+/// Converts a BLS public key to the decompressed G1 point expected by the contracts.
+fn g1_point(pubkey: &BlstPublicKey) -> G1Point {
+    let bytes = pubkey.serialize();
+    G1Point { x: fp_limbs(&bytes[..48]), y: fp_limbs(&bytes[48..]) }
+}
+
+/// Converts a BLS signature to the decompressed G2 point expected by the contracts. The
+/// serialized coordinates start with their imaginary part.
+fn g2_point(signature: &BlstSignature) -> G2Point {
+    let bytes = signature.serialize();
+    G2Point {
+        x_I: fp_limbs(&bytes[..48]),
+        x: fp_limbs(&bytes[48..96]),
+        y_I: fp_limbs(&bytes[96..144]),
+        y: fp_limbs(&bytes[144..]),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use alloy::{
@@ -159,6 +412,7 @@ mod tests {
     };
     use reqwest::Url;
 
+    use super::{g1_point, registration_message, BlstSecretKey};
     use crate::cli::{ValidatorsCommand, ValidatorsSubcommand};
 
     #[tokio::test]
@@ -198,4 +452,29 @@ mod tests {
 
         command.run().await.expect("run command");
     }
+
+    #[test]
+    fn test_registration_encoding() {
+        // The public key of the secret key 1 is the generator of G1
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let generator = BlstSecretKey::from_bytes(&one).expect("valid secret key").sk_to_pk();
+
+        let point = g1_point(&generator);
+        assert_eq!(
+            point.x[0],
+            U256::from_str_radix("17f1d3a73197d7942695638c4fa9ac0f", 16).unwrap()
+        );
+        assert_eq!(
+            point.x[1],
+            U256::from_str_radix(
+                "c3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb",
+                16
+            )
+            .unwrap()
+        );
+
+        // uint256 chain ID, address, then the uint32 sequence number
+        assert_eq!(registration_message(17000, Address::ZERO, 1).len(), 32 + 20 + 4);
+    }
 }
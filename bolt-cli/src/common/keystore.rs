@@ -6,7 +6,9 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use eyre::{bail, Context, ContextCompat, Result};
+use blst::min_pk::SecretKey as BlstSecretKey;
+use eyre::{bail, eyre, Context, ContextCompat, Result};
+use lighthouse_eth2_keystore::Keystore;
 
 use crate::cli::LocalKeystoreOpts;
 
@@ -132,6 +134,26 @@ pub fn keystore_paths(keys_path: &str) -> Result<Vec<PathBuf>> {
     Ok(keystores_paths)
 }
 
+/// Reads and decrypts the secret keys of all the keystore files provided in `keys_path`,
+/// with the passwords of the given [`KeystoreSecret`].
+pub fn decrypt_secret_keys(
+    keys_path: &str,
+    keystore_secret: &KeystoreSecret,
+) -> Result<Vec<BlstSecretKey>> {
+    keystore_paths(keys_path)?
+        .into_iter()
+        .map(|path| {
+            let ks = Keystore::from_json_file(path).map_err(KeystoreError::Eth2Keystore)?;
+            let password =
+                keystore_secret.get(ks.pubkey()).ok_or(KeystoreError::MissingPassword)?;
+            let kp =
+                ks.decrypt_keypair(password.as_bytes()).map_err(KeystoreError::Eth2Keystore)?;
+            BlstSecretKey::from_bytes(kp.sk.serialize().as_bytes())
+                .map_err(|e| eyre!("Failed to parse secret key: {:?}", e))
+        })
+        .collect()
+}
+
 fn read_path(entry: io::Result<DirEntry>) -> Result<PathBuf> {
     Ok(entry.map_err(KeystoreError::ReadFromDirectory)?.path())
 }
@@ -25,6 +25,12 @@ pub mod signing;
 /// Utilities for hashing messages and custom types.
 pub mod hash;
 
+/// Custom network definitions loaded from consensus-spec config files.
+pub mod network;
+
+/// Utilities for building merkle proofs of execution data.
+pub mod proofs;
+
 /// Utilities for working with Consensys' Web3Signer remote keystore.
 pub mod web3signer;
 
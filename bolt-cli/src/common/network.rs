@@ -0,0 +1,98 @@
+use std::{fs, path::Path};
+
+use eyre::{bail, eyre, Context, Result};
+use serde::Deserialize;
+use serde_yaml::Value;
+
+/// The definition of a custom network, loaded from a standard consensus-spec `config.yaml`.
+///
+/// Only the values needed by the CLI are kept.
+///
+/// Reference: <https://github.com/ethereum/consensus-specs/blob/dev/configs/mainnet.yaml>
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct NetworkSpec {
+    /// The genesis fork version, used to compute signing domains (`GENESIS_FORK_VERSION`).
+    pub genesis_fork_version: [u8; 4],
+    /// The number of slots in an epoch, as defined by the preset of the network (`PRESET_BASE`).
+    pub slots_per_epoch: u64,
+}
+
+/// The values of a consensus-spec `config.yaml` needed by the CLI. Other values are ignored.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct ConsensusConfig {
+    /// Fork versions are parsed as integers by YAML if they are not quoted.
+    genesis_fork_version: Value,
+    /// Defaults to the mainnet preset if unset.
+    preset_base: Option<String>,
+}
+
+impl NetworkSpec {
+    /// Load a network definition from the `config.yaml` file at the given path.
+    pub fn from_config_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read network config at {}", path.display()))?;
+
+        Self::from_config_yaml(&contents)
+    }
+
+    /// Parse a network definition from the contents of a `config.yaml` file.
+    pub fn from_config_yaml(contents: &str) -> Result<Self> {
+        let config: ConsensusConfig =
+            serde_yaml::from_str(contents).wrap_err("Invalid network config")?;
+
+        let genesis_fork_version = parse_fork_version(&config.genesis_fork_version)
+            .ok_or_else(|| eyre!("Invalid GENESIS_FORK_VERSION in network config"))?;
+
+        let slots_per_epoch = match config.preset_base.as_deref() {
+            None | Some("mainnet") => 32,
+            Some("minimal") => 8,
+            Some(other) => bail!("Unknown PRESET_BASE {other} in network config"),
+        };
+
+        Ok(Self { genesis_fork_version, slots_per_epoch })
+    }
+}
+
+/// Parse a fork version, either from a hex string or from the integer it was read as.
+fn parse_fork_version(value: &Value) -> Option<[u8; 4]> {
+    match value {
+        Value::String(hex) => alloy::hex::decode(hex).ok()?.try_into().ok(),
+        Value::Number(number) => Some(u32::try_from(number.as_u64()?).ok()?.to_be_bytes()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NetworkSpec;
+
+    #[test]
+    fn test_parse_network_config() {
+        let config = r#"
+PRESET_BASE: 'minimal'
+CONFIG_NAME: 'devnet'  # A local devnet
+GENESIS_FORK_VERSION: 0x10000038
+SECONDS_PER_SLOT: 6
+DEPOSIT_CHAIN_ID: 3151908
+BLOB_SCHEDULE:
+  - EPOCH: 0
+    MAX_BLOBS_PER_BLOCK: 6
+"#;
+
+        let spec = NetworkSpec::from_config_yaml(config).unwrap();
+        assert_eq!(spec.genesis_fork_version, [16, 0, 0, 56]);
+        assert_eq!(spec.slots_per_epoch, 8);
+
+        let quoted = "GENESIS_FORK_VERSION: '0x01017000'";
+        let spec = NetworkSpec::from_config_yaml(quoted).unwrap();
+        assert_eq!(spec.genesis_fork_version, [1, 1, 112, 0]);
+        assert_eq!(spec.slots_per_epoch, 32);
+
+        assert!(NetworkSpec::from_config_yaml("PRESET_BASE: mainnet").is_err());
+
+        let unknown = "PRESET_BASE: 'gnosis'\nGENESIS_FORK_VERSION: 0x00000064";
+        assert!(NetworkSpec::from_config_yaml(unknown).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+use alloy::primitives::{Bytes, B256};
+use alloy_trie::{proof::ProofRetainer, root::adjust_index_for_rlp, HashBuilder, Nibbles};
+
+/// The merkle proofs of some transactions in the transactions trie of a block.
+#[derive(Debug, Clone)]
+pub struct TransactionsProof {
+    /// The root of the transactions trie.
+    pub root: B256,
+    /// The proof of each requested transaction, in the order of the requested indexes.
+    pub proofs: Vec<Bytes>,
+}
+
+/// Builds the transactions trie of a block from its EIP-2718 encoded transactions, and returns
+/// the proofs of the transactions at the given indexes.
+///
+/// Each proof is the RLP-encoded list of the trie nodes from the root to the leaf, which is the
+/// format expected by the `BoltChallenger` contract.
+pub fn transactions_trie_proofs(encoded_txs: &[Bytes], indexes: &[usize]) -> TransactionsProof {
+    let targets = indexes.iter().map(|index| transactions_trie_key(*index)).collect::<Vec<_>>();
+
+    let mut builder =
+        HashBuilder::default().with_proof_retainer(ProofRetainer::new(targets.clone()));
+
+    // Leaves must be added in the order of their keys, which are the RLP-encoded indexes
+    for i in 0..encoded_txs.len() {
+        let index = adjust_index_for_rlp(i, encoded_txs.len());
+        builder.add_leaf(transactions_trie_key(index), &encoded_txs[index]);
+    }
+
+    let root = builder.root();
+    let nodes = builder.take_proof_nodes();
+
+    let proofs = targets
+        .iter()
+        .map(|key| {
+            let path = nodes
+                .matching_nodes_sorted(key)
+                .into_iter()
+                .map(|(_, node)| node)
+                .collect::<Vec<_>>();
+            Bytes::from(alloy_rlp::encode(path))
+        })
+        .collect();
+
+    TransactionsProof { root, proofs }
+}
+
+/// Returns the key of the transaction at the given index in the transactions trie.
+fn transactions_trie_key(index: usize) -> Nibbles {
+    Nibbles::unpack(alloy_rlp::encode_fixed_size(&index))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::Bytes;
+    use alloy_trie::{proof::verify_proof, root::ordered_trie_root_with_encoder};
+
+    use super::{transactions_trie_key, transactions_trie_proofs};
+
+    #[test]
+    fn test_transactions_trie_proofs() -> eyre::Result<()> {
+        // Enough transactions to have the RLP-encoded index 0 out of order
+        let txs = (0..200u8).map(|i| Bytes::from(vec![i; 64])).collect::<Vec<_>>();
+        let indexes = [0, 1, 127, 128, 199];
+
+        let proof = transactions_trie_proofs(&txs, &indexes);
+        let root = ordered_trie_root_with_encoder(&txs, |tx, buf| buf.extend_from_slice(tx));
+        assert_eq!(proof.root, root);
+
+        for (index, encoded) in indexes.iter().zip(proof.proofs.iter()) {
+            let nodes: Vec<Bytes> = alloy_rlp::decode_exact(encoded)?;
+            let expected = Some(txs[*index].to_vec());
+            verify_proof(proof.root, transactions_trie_key(*index), expected, &nodes)?;
+        }
+
+        Ok(())
+    }
+}
@@ -7,7 +7,7 @@ sol! {
     interface BoltManager {
         #[derive(Debug, Default, Serialize)]
         struct ProposerStatus {
-            bytes32 pubkeyHash;
+            bytes20 pubkeyHash;
             bool active;
             address operator;
             string operatorRPC;
@@ -25,7 +25,7 @@ sol! {
             uint256 timestamp;
         }
 
-        function getProposerStatus(bytes32 pubkeyHash) external view returns (ProposerStatus memory);
+        function getProposerStatus(bytes20 pubkeyHash) external view returns (ProposerStatus memory);
 
         function isOperator(address operator) public view returns (bool);
 
@@ -37,7 +37,7 @@ sol! {
         function getOperatorData(address operator) public view returns (Operator memory);
 
         error InvalidQuery();
-        error ValidatorDoesNotExist();
+        error ValidatorDoesNotExist(bytes20 pubkeyHash);
         error OperatorNotRegistered();
         error KeyNotFound(address key);
     }
@@ -81,6 +81,66 @@ sol! {
     }
 }
 
+sol! {
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    interface BoltValidatorsV2 {
+        #[derive(Debug, Serialize)]
+        struct ValidatorInfo {
+            bytes20 pubkeyHash;
+            uint32 maxCommittedGasLimit;
+            address authorizedOperator;
+            address controller;
+        }
+
+        /// A decompressed BLS12-381 G1 point, with each coordinate split into two 32-byte limbs.
+        #[derive(Debug)]
+        struct G1Point {
+            uint256[2] x;
+            uint256[2] y;
+        }
+
+        /// A decompressed BLS12-381 G2 point, with each coordinate split into two 32-byte limbs.
+        #[derive(Debug)]
+        struct G2Point {
+            uint256[2] x;
+            uint256[2] x_I;
+            uint256[2] y;
+            uint256[2] y_I;
+        }
+
+        /// @notice Get all validators in the system
+        /// @dev This function should be used with caution as it can return a large amount of data.
+        function getAllValidators() external view returns (ValidatorInfo[] memory);
+
+        /// @notice Register a single Validator with a BLS signature of the registration message
+        /// @dev The message is `abi.encodePacked(block.chainid, msg.sender, sequenceNumber)`.
+        function registerValidator(G1Point calldata pubkey, G2Point calldata signature, uint32 maxCommittedGasLimit, address authorizedOperator) external;
+
+        /// @notice Update the maximum gas limit that a validator can commit for preconfirmations
+        /// @dev Only the `controller` of the validator can update this value.
+        function updateMaxCommittedGasLimit(bytes20 pubkeyHash, uint32 maxCommittedGasLimit) external;
+
+        /// @notice Helper to compute the hash of a BLS public key
+        function hashPubkey(G1Point calldata pubkey) external pure returns (bytes20);
+
+        #[derive(Debug)]
+        error InvalidBLSSignature();
+        #[derive(Debug)]
+        error InvalidAuthorizedOperator();
+        #[derive(Debug)]
+        error UnsafeRegistrationNotAllowed();
+        #[derive(Debug)]
+        error UnauthorizedCaller();
+        #[derive(Debug)]
+        error InvalidPubkey();
+        #[derive(Debug)]
+        error ValidatorAlreadyExists(bytes20 pubkeyHash);
+        #[derive(Debug)]
+        error ValidatorDoesNotExist(bytes20 pubkeyHash);
+    }
+}
+
 // Mainnet Genesis: deprecated
 sol! {
     #[allow(missing_docs)]
@@ -326,3 +386,142 @@ sol! {
         function getCurrentEpochStartTimestamp() external view returns (uint48);
     }
 }
+
+sol! {
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    interface BoltParametersV1 {
+        /// @notice Returns the duration of a challenge in seconds, after which it can be resolved as breached
+        function MAX_CHALLENGE_DURATION() external view returns (uint48);
+
+        /// @notice Returns the bond required to open a challenge, in wei
+        function CHALLENGE_BOND() external view returns (uint256);
+
+        /// @notice Returns the number of recent blocks whose hash is visible to the EVM
+        function BLOCKHASH_EVM_LOOKBACK() external view returns (uint256);
+
+        /// @notice Returns the number of slots to wait before a slot can be challenged
+        function JUSTIFICATION_DELAY() external view returns (uint256);
+    }
+}
+
+sol! {
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    interface BoltChallengerV1 {
+        #[derive(Debug, Serialize)]
+        enum ChallengeStatus {
+            Open,
+            Defended,
+            Breached
+        }
+
+        #[derive(Debug, Serialize)]
+        struct Challenge {
+            bytes32 id;
+            uint48 openedAt;
+            ChallengeStatus status;
+            uint256 targetSlot;
+            address challenger;
+            address commitmentSigner;
+            address commitmentReceiver;
+            TransactionData[] committedTxs;
+        }
+
+        #[derive(Debug)]
+        struct SignedCommitment {
+            uint64 slot;
+            bytes signature;
+            bytes signedTx;
+        }
+
+        #[derive(Debug, Serialize)]
+        struct TransactionData {
+            bytes32 txHash;
+            uint256 nonce;
+            uint256 gasLimit;
+        }
+
+        #[derive(Debug)]
+        struct Proof {
+            // block number where the transactions are included
+            uint256 inclusionBlockNumber;
+            // RLP-encoded block header of the previous block of the inclusion block
+            bytes previousBlockHeaderRLP;
+            // RLP-encoded block header where the committed transactions are included
+            bytes inclusionBlockHeaderRLP;
+            // merkle inclusion proof of the account in the state trie of the previous block
+            bytes accountMerkleProof;
+            // merkle inclusion proofs of the transactions in the transaction trie of the inclusion block
+            bytes[] txMerkleProofs;
+            // indexes of the committed transactions in the block
+            uint256[] txIndexesInBlock;
+        }
+
+        #[derive(Debug)]
+        error SlotInTheFuture();
+        #[derive(Debug)]
+        error BlockIsNotFinalized();
+        #[derive(Debug)]
+        error IncorrectChallengeBond();
+        #[derive(Debug)]
+        error ChallengeAlreadyExists();
+        #[derive(Debug)]
+        error ChallengeAlreadyResolved();
+        #[derive(Debug)]
+        error ChallengeDoesNotExist();
+        #[derive(Debug)]
+        error BlockIsTooOld();
+        #[derive(Debug)]
+        error InvalidBlockHash();
+        #[derive(Debug)]
+        error InvalidParentBlockHash();
+        #[derive(Debug)]
+        error AccountDoesNotExist();
+        #[derive(Debug)]
+        error TransactionNotIncluded();
+        #[derive(Debug)]
+        error WrongTransactionHashProof();
+        #[derive(Debug)]
+        error InvalidBlockNumber();
+        #[derive(Debug)]
+        error BondTransferFailed();
+        #[derive(Debug)]
+        error ChallengeNotExpired();
+        #[derive(Debug)]
+        error ChallengeExpired();
+        #[derive(Debug)]
+        error EmptyCommitments();
+        #[derive(Debug)]
+        error UnexpectedMixedSenders();
+        #[derive(Debug)]
+        error UnexpectedMixedSlots();
+        #[derive(Debug)]
+        error UnexpectedMixedSigners();
+        #[derive(Debug)]
+        error UnexpectedNonceOrder();
+        #[derive(Debug)]
+        error InvalidProofsLength();
+        #[derive(Debug)]
+        error BeaconRootNotFound();
+
+        event ChallengeOpened(bytes32 indexed challengeId, address indexed challenger, address indexed commitmentSigner);
+        event ChallengeDefended(bytes32 indexed challengeId);
+        event ChallengeBreached(bytes32 indexed challengeId);
+
+        /// @notice Returns the address of the Bolt Parameters contract
+        function parameters() external view returns (address);
+
+        function getAllChallenges() external view returns (Challenge[] memory);
+
+        function getOpenChallenges() external view returns (Challenge[] memory);
+
+        function getChallengeByID(bytes32 challengeID) external view returns (Challenge memory);
+
+        function openChallenge(SignedCommitment[] calldata commitments) external payable;
+
+        function resolveExpiredChallenge(bytes32 challengeID) external;
+
+        function resolveOpenChallenge(bytes32 challengeID, Proof calldata proof) external;
+    }
+}
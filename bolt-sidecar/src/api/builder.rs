@@ -1,34 +1,40 @@
 use std::{sync::Arc, time::Duration};
 
+use alloy::primitives::{B256, U256};
 use axum::{
     body::{self, Body},
     extract::{Path, Request, State},
-    http::StatusCode,
-    response::Html,
+    http::{HeaderMap, StatusCode},
+    response::{Html, Response},
     routing::{get, post},
-    Json, Router,
+    Router,
 };
 use beacon_api_client::VersionedValue;
-use ethereum_consensus::{
-    builder::SignedValidatorRegistration,
-    deneb::mainnet::SignedBlindedBeaconBlock,
-    primitives::{BlsPublicKey, Hash32},
-    Fork,
-};
+use ethereum_consensus::primitives::{BlsPublicKey, Hash32};
 use parking_lot::Mutex;
 use serde::Deserialize;
 use thiserror::Error;
 use tokio::net::TcpListener;
 use tracing::{debug, error, info, warn};
 
-use super::spec::{
-    BuilderApiError, ConstraintsApi, GET_HEADER_PATH, GET_PAYLOAD_PATH, REGISTER_VALIDATORS_PATH,
-    STATUS_PATH,
+use super::{
+    encoding::{
+        consensus_version, decode_signed_blinded_block, decode_validator_registrations,
+        encode_builder_bid, encode_get_payload_response, Encoding, SignedBlindedBlock,
+    },
+    spec::{
+        BuilderApiError, ConstraintsApi, GET_HEADER_PATH, GET_PAYLOAD_PATH,
+        REGISTER_VALIDATORS_PATH, STATUS_PATH,
+    },
 };
 use crate::{
     builder::PayloadFetcher,
     client::ConstraintsClient,
-    primitives::{GetPayloadResponse, SignedBuilderBid},
+    config::ChainConfig,
+    primitives::{
+        proofs::verify_multiproofs, GetPayloadResponse, SignedBuilderBidWithProofs,
+        VersionedSignedBuilderBid,
+    },
     telemetry::ApiMetrics,
 };
 
@@ -46,6 +52,10 @@ pub struct BuilderProxyServer<T, P> {
     local_payload: Mutex<Option<GetPayloadResponse>>,
     /// The payload fetcher to get locally built payloads.
     payload_fetcher: P,
+    /// The minimum value in wei for a relay bid to be accepted.
+    min_bid: U256,
+    /// The chain configuration, used to find the fork of blinded blocks without a version.
+    chain: ChainConfig,
 }
 
 /// Parameters for the get_header request.
@@ -64,8 +74,8 @@ where
     P: PayloadFetcher + Send + Sync,
 {
     /// Create a new builder proxy server.
-    pub fn new(proxy_target: T, payload_fetcher: P) -> Self {
-        Self { proxy_target, local_payload: Mutex::new(None), payload_fetcher }
+    pub fn new(proxy_target: T, payload_fetcher: P, min_bid: U256, chain: ChainConfig) -> Self {
+        Self { proxy_target, local_payload: Mutex::new(None), payload_fetcher, min_bid, chain }
     }
 
     /// Gets the status. Just forwards the request to constraints client and returns the status.
@@ -89,11 +99,21 @@ where
 
     /// Registers the validators. Just forwards the request to constraints client
     /// and returns the status.
+    ///
+    /// The registrations can be either JSON or SSZ encoded, based on the `Content-Type` header.
     pub async fn register_validators(
         State(server): State<Arc<Self>>,
-        Json(registrations): Json<Vec<SignedValidatorRegistration>>,
+        headers: HeaderMap,
+        body: body::Bytes,
     ) -> Result<StatusCode, BuilderApiError> {
-        debug!("Received register validators request");
+        let encoding = Encoding::from_content_type(&headers);
+        debug!(?encoding, "Received register validators request");
+
+        let registrations = decode_validator_registrations(encoding, &body).map_err(|e| {
+            error!(error = %e, "Failed to parse validator registrations");
+            e
+        })?;
+
         let response = server.proxy_target.register_validators(registrations).await;
         response.map(|_| StatusCode::OK)
     }
@@ -101,51 +121,89 @@ where
     /// Gets the header. NOTE: converts this request to a get_header_with_proofs
     /// request to the modified constraints client.
     ///
-    /// In case of a builder or relay failure, we return the locally built block header
-    /// and store the actual payload so we can return it later.
+    /// The inclusion proofs of the bid are verified against the constraints of the slot.
+    /// We return the locally built block header instead of the relay bid, and store the actual
+    /// payload so we can return it later, if:
+    /// - the builder or relay fails, or the proofs are missing or invalid;
+    /// - the relay bid is below the configured minimum bid;
+    /// - the relay bid is below the value of the locally built block.
+    ///
+    /// The response is either JSON or SSZ encoded, based on the `Accept` header, and carries
+    /// the fork of the returned bid.
     pub async fn get_header(
         State(server): State<Arc<Self>>,
         Path(params): Path<GetHeaderParams>,
-    ) -> Result<Json<VersionedValue<SignedBuilderBid>>, BuilderApiError> {
+        headers: HeaderMap,
+    ) -> Result<Response, BuilderApiError> {
         let start = std::time::Instant::now();
+        let encoding = Encoding::from_accept(&headers);
 
         let slot = params.slot;
         debug!(slot, pubkey = %params.public_key, "Received get_header request");
 
-        let err = match tokio::time::timeout(
+        let remote = match tokio::time::timeout(
             GET_HEADER_WITH_PROOFS_TIMEOUT,
             server.proxy_target.get_header_with_proofs(params),
         )
         .await
         {
-            Ok(res) => match res {
-                Err(builder_err) => builder_err,
-                Ok(header) => {
-                    // Clear the local payload cache if we have a successful response
-                    // By definition of `server.local_payload`, this will be `Some` IFF we have
-                    // signed a local header
-                    let mut local_payload = server.local_payload.lock();
-                    *local_payload = None;
-
-                    debug!(elapsed = ?start.elapsed(), "Returning signed builder bid");
-                    return Ok(Json(header));
-                }
-            },
-            Err(err) => BuilderApiError::Timeout(err),
+            Ok(Ok(header)) => server.verify_bid_proofs(slot, &header.data).await.map(|_| header),
+            Ok(Err(builder_err)) => Err(builder_err),
+            Err(err) => Err(BuilderApiError::Timeout(err)),
         };
 
-        // On ANY error, we fall back to locally built block
-        warn!(slot, elapsed = ?start.elapsed(), err = ?err, "Proxy error, fetching local payload instead");
+        let local = server.payload_fetcher.fetch_payload(slot).await;
+
+        let decision = match remote {
+            Ok(ref header) => HeaderDecision::new(
+                header.data.bid.value(),
+                local.as_ref().map(|local| local.bid.value()),
+                server.min_bid,
+            ),
+            Err(ref err) => {
+                // On ANY error, we fall back to locally built block
+                warn!(slot, elapsed = ?start.elapsed(), ?err, "Proxy error, fetching local payload instead");
+                HeaderDecision::LocalRelayError
+            }
+        };
+
+        let remote_value = remote.as_ref().ok().map(|header| header.data.bid.value());
+        let local_value = local.as_ref().map(|local| local.bid.value());
+        info!(
+            slot,
+            ?remote_value,
+            ?local_value,
+            decision = decision.as_str(),
+            "Chose header source"
+        );
+        ApiMetrics::increment_header_decisions(decision.as_str());
+
+        if let (HeaderDecision::Remote, Ok(header)) = (decision, remote) {
+            // Clear the local payload cache if we have a successful response
+            // By definition of `server.local_payload`, this will be `Some` IFF we
+            // have signed a local header
+            let mut local_payload = server.local_payload.lock();
+            *local_payload = None;
+
+            let bid = VersionedValue::<VersionedSignedBuilderBid> {
+                version: header.version,
+                data: header.data.bid,
+                meta: header.meta,
+            };
 
-        let Some(payload_and_bid) = server.payload_fetcher.fetch_payload(slot).await else {
+            debug!(elapsed = ?start.elapsed(), ?encoding, "Returning signed builder bid");
+            return encode_builder_bid(encoding, bid);
+        }
+
+        let Some(payload_and_bid) = local else {
             // In this case, we don't have a fallback block which means we haven't made any
             // commitments. This means the EL should fallback to local block building.
             debug!("No local payload with commitments produced for slot {slot}");
             return Err(BuilderApiError::FailedToFetchLocalPayload(slot));
         };
 
-        let hash = payload_and_bid.bid.message.header.block_hash.clone();
-        let number = payload_and_bid.bid.message.header.block_number;
+        let hash = payload_and_bid.bid.header().block_hash.clone();
+        let number = payload_and_bid.bid.header().block_number;
         info!(elapsed = ?start.elapsed(), %hash, "Fetched local payload for slot {slot}");
 
         {
@@ -155,24 +213,67 @@ where
             *local_payload = Some(payload_and_bid.payload);
         }
 
-        let versioned_bid = VersionedValue::<SignedBuilderBid> {
-            version: Fork::Deneb,
+        let versioned_bid = VersionedValue::<VersionedSignedBuilderBid> {
+            version: payload_and_bid.bid.version(),
             data: payload_and_bid.bid,
             meta: Default::default(),
         };
 
         info!(elapsed = ?start.elapsed(), %hash, number, ?versioned_bid, "Returning locally built header");
-        Ok(Json(versioned_bid))
+        encode_builder_bid(encoding, versioned_bid)
+    }
+
+    /// Verifies the inclusion proofs of a remote bid against the signed constraints for the
+    /// given slot. Bids for slots without constraints don't need any proofs.
+    async fn verify_bid_proofs(
+        &self,
+        slot: u64,
+        bid: &SignedBuilderBidWithProofs,
+    ) -> Result<(), BuilderApiError> {
+        let constraints = self
+            .payload_fetcher
+            .fetch_constraints(slot)
+            .await
+            .ok_or(BuilderApiError::FailedToFetchConstraints(slot))?;
+
+        if constraints.is_empty() {
+            return Ok(());
+        }
+
+        let start = std::time::Instant::now();
+        let root = B256::from_slice(bid.bid.header().transactions_root.as_ref());
+        let result = verify_multiproofs(&constraints, &bid.proofs, root);
+        ApiMetrics::increment_remote_bid_proof_verifications(result.is_ok());
+        result?;
+
+        debug!(
+            slot,
+            proofs = bid.proofs.total_leaves(),
+            elapsed = ?start.elapsed(),
+            "Verified inclusion proofs of remote bid"
+        );
+
+        Ok(())
     }
 
     /// Gets the payload. If we have a locally built payload, we return it.
     /// Otherwise, we forward the request to the constraints client.
+    ///
+    /// The signed blinded block can be either JSON or SSZ encoded, based on the `Content-Type`
+    /// header. The response is encoded based on the `Accept` header.
+    ///
+    /// The fork of the block is read from the `Eth-Consensus-Version` header, or derived from
+    /// its slot with the fork schedule of the chain if missing.
     pub async fn get_payload(
         State(server): State<Arc<Self>>,
         req: Request<Body>,
-    ) -> Result<Json<GetPayloadResponse>, BuilderApiError> {
+    ) -> Result<Response, BuilderApiError> {
         let start = std::time::Instant::now();
 
+        let request_encoding = Encoding::from_content_type(req.headers());
+        let response_encoding = Encoding::from_accept(req.headers());
+        let version = consensus_version(req.headers());
+
         let body_bytes =
             body::to_bytes(req.into_body(), MAX_BLINDED_BLOCK_LENGTH).await.map_err(|e| {
                 error!(error = %e, "Failed to read request body");
@@ -180,14 +281,15 @@ where
             })?;
 
         // Convert to signed blinded beacon block
-        let signed_blinded_block = serde_json::from_slice::<SignedBlindedBeaconBlock>(&body_bytes)
-            .map_err(|e| {
-                error!(error = %e, "Failed to parse signed blinded block");
-                e
-            })?;
+        let signed_blinded_block =
+            decode_signed_blinded_block(request_encoding, version, body_bytes, &server.chain)
+                .map_err(|e| {
+                    error!(error = %e, ?request_encoding, "Failed to parse signed blinded block");
+                    e
+                })?;
 
-        let slot = signed_blinded_block.message.slot;
-        debug!(slot, "Received get_payload request");
+        let slot = signed_blinded_block.slot;
+        debug!(slot, version = %signed_blinded_block.version, "Received get_payload request");
 
         // If we have a locally built payload, it means we signed a local header.
         // Return it and clear the cache.
@@ -197,26 +299,22 @@ where
             info!("Valid local block found, returning: {:?}", local_payload.block_hash());
             ApiMetrics::increment_local_blocks_proposed();
 
-            return Ok(Json(local_payload));
+            return encode_get_payload_response(response_encoding, local_payload);
         }
 
         // TODO: how do we deal with failures here? What if we submit the signed blinded block but
         // don't get a response? should we ignore the error or proceed with a local block
         // (highly risky -> equivocation risk)
-        let payload = server
-            .proxy_target
-            .get_payload(signed_blinded_block)
-            .await
-            .map(Json)
-            .map_err(|e| {
+        let payload =
+            server.proxy_target.get_payload(signed_blinded_block).await.map_err(|e| {
                 error!(elapsed = ?start.elapsed(), error = %e, "Failed to get payload from constraints client");
                 e
             })?;
 
-        info!(elapsed = ?start.elapsed(), slot, "Returning payload from constraints client");
+        info!(elapsed = ?start.elapsed(), slot, ?response_encoding, "Returning payload from constraints client");
         ApiMetrics::increment_remote_blocks_proposed();
 
-        Ok(payload)
+        encode_get_payload_response(response_encoding, payload)
     }
 }
 
@@ -227,6 +325,10 @@ pub struct BuilderProxyConfig {
     pub constraints_client: ConstraintsClient,
     /// The port on which the builder proxy should listen.
     pub server_port: u16,
+    /// The minimum value in wei for a relay bid to be accepted.
+    pub min_bid: U256,
+    /// The chain configuration.
+    pub chain: ChainConfig,
 }
 
 /// Start the builder proxy with the given payload fetcher and configuration.
@@ -243,7 +345,12 @@ where
         "Starting builder proxy..."
     );
 
-    let server = Arc::new(BuilderProxyServer::new(config.constraints_client, payload_fetcher));
+    let server = Arc::new(BuilderProxyServer::new(
+        config.constraints_client,
+        payload_fetcher,
+        config.min_bid,
+        config.chain,
+    ));
 
     let router = Router::new()
         .route("/", get(index))
@@ -264,6 +371,44 @@ async fn index() -> Html<&'static str> {
     Html("Hello")
 }
 
+/// The source of the header returned to the beacon node, and the reason it was chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderDecision {
+    /// The relay bid is valid and worth at least as much as the local block and the minimum bid.
+    Remote,
+    /// The relay failed to return a valid bid.
+    LocalRelayError,
+    /// The relay bid is below the configured minimum bid.
+    LocalBelowMinBid,
+    /// The relay bid is below the value of the locally built block.
+    LocalHigherValue,
+}
+
+impl HeaderDecision {
+    /// Decides which header to return given a valid relay bid value, the value of the locally
+    /// built block (if any) and the minimum bid.
+    pub fn new(remote_value: U256, local_value: Option<U256>, min_bid: U256) -> Self {
+        if remote_value < min_bid {
+            return Self::LocalBelowMinBid;
+        }
+
+        match local_value {
+            Some(local_value) if remote_value < local_value => Self::LocalHigherValue,
+            _ => Self::Remote,
+        }
+    }
+
+    /// Returns the label used for this decision in logs and metrics.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Remote => "remote",
+            Self::LocalRelayError => "local_relay_error",
+            Self::LocalBelowMinBid => "local_below_min_bid",
+            Self::LocalHigherValue => "local_higher_value",
+        }
+    }
+}
+
 /// Errors that can occur when checking the integrity of a locally built payload.
 #[derive(Error, Debug, Clone)]
 #[allow(missing_docs)]
@@ -299,12 +444,14 @@ macro_rules! assert_payload_fields_eq {
 /// when we submit the full payload.
 #[inline]
 fn check_locally_built_payload_integrity(
-    signed_blinded_block: &SignedBlindedBeaconBlock,
+    signed_blinded_block: &SignedBlindedBlock,
     local_payload: &GetPayloadResponse,
 ) -> Result<(), LocalPayloadIntegrityError> {
-    let header_signed_by_cl = &signed_blinded_block.message.body.execution_payload_header;
+    let header_signed_by_cl = &signed_blinded_block.execution_payload_header;
     let local_execution_payload = local_payload.execution_payload();
 
+    assert_payload_fields_eq!(signed_blinded_block.version, local_payload.version(), Version);
+
     assert_payload_fields_eq!(
         &header_signed_by_cl.block_hash,
         local_execution_payload.block_hash(),
@@ -403,3 +550,28 @@ fn check_locally_built_payload_integrity(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::U256;
+
+    use super::HeaderDecision;
+
+    #[test]
+    fn test_header_decision() {
+        let gwei = |n: u64| U256::from(n) * U256::from(1_000_000_000u64);
+
+        assert_eq!(HeaderDecision::new(gwei(10), None, U256::ZERO), HeaderDecision::Remote);
+        assert_eq!(HeaderDecision::new(gwei(10), Some(gwei(5)), gwei(1)), HeaderDecision::Remote);
+        assert_eq!(HeaderDecision::new(gwei(10), Some(gwei(10)), gwei(10)), HeaderDecision::Remote);
+        assert_eq!(
+            HeaderDecision::new(gwei(10), Some(gwei(11)), U256::ZERO),
+            HeaderDecision::LocalHigherValue
+        );
+        assert_eq!(
+            HeaderDecision::new(gwei(10), Some(gwei(5)), gwei(20)),
+            HeaderDecision::LocalBelowMinBid
+        );
+        assert_eq!(HeaderDecision::new(gwei(10), None, gwei(20)), HeaderDecision::LocalBelowMinBid);
+    }
+}
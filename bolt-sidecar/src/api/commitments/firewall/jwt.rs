@@ -1,13 +1,10 @@
-use alloy::{
-    primitives::keccak256,
-    signers::{local::PrivateKeySigner, SignerSync},
-};
+use alloy::primitives::keccak256;
 use jsonwebtoken::encode;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     common::time::current_timestamp,
-    config::chain::Chain,
+    crypto::SignerECDSA,
     primitives::signature::{AlloySignatureWrapper, ECDSASignatureExt},
 };
 
@@ -33,24 +30,23 @@ pub struct ProposerAuthClaims {
 
 impl ProposerAuthClaims {
     /// Creates a new instance of the [ProposerAuthClaims] and creates a signature with
-    /// the provided signer.
+    /// the provided signer, which can be a local key or a remote signer like commit-boost.
     ///
     /// If the expiry is not provided, it will default to 60 seconds from now.
-    pub fn new_from_signer(
+    pub async fn new_from_signer<S: SignerECDSA + Sync>(
         rpc_url: String,
-        chain: Chain,
+        chain_id: u64,
         expiry: Option<u64>,
-        signer: PrivateKeySigner,
-    ) -> Self {
+        signer: &S,
+    ) -> eyre::Result<Self> {
         let expiry = expiry.unwrap_or(current_timestamp() + 60);
 
-        let digest = [rpc_url.clone(), chain.id().to_string(), expiry.to_string()].concat();
+        let digest = [rpc_url.clone(), chain_id.to_string(), expiry.to_string()].concat();
         let digest_hash = keccak256(digest);
-        let signature = signer.sign_hash_sync(&digest_hash).expect("failed to sign the digest");
-        let signature = AlloySignatureWrapper::try_from(signature.as_bytes().as_ref())
-            .expect("failed to convert the signature");
+        let signature = signer.sign_hash(&digest_hash).await?;
+        let signature = AlloySignatureWrapper::try_from(signature.as_bytes().as_ref())?;
 
-        Self { rpc_url, chain_id: chain.id(), expiry, signature: signature.to_hex() }
+        Ok(Self { rpc_url, chain_id, expiry, signature: signature.to_hex() })
     }
 
     /// Encodes the claims into a JWT, with default HS256 algorithm and empty secret key.
@@ -70,14 +66,16 @@ mod tests {
     use super::ProposerAuthClaims;
     use crate::{common::time::current_timestamp, config::chain::Chain};
 
-    #[test]
-    fn test_encode_decode_proposer_auth_claim() {
+    #[tokio::test]
+    async fn test_encode_decode_proposer_auth_claim() {
         let rpc_url = "http://localhost:8545".to_string();
-        let chain = Chain::Mainnet;
+        let chain_id = Chain::Mainnet.id();
         let expiry = current_timestamp() + 60;
         let signer = PrivateKeySigner::random();
 
-        let claim = ProposerAuthClaims::new_from_signer(rpc_url, chain, Some(expiry), signer);
+        let claim = ProposerAuthClaims::new_from_signer(rpc_url, chain_id, Some(expiry), &signer)
+            .await
+            .expect("failed to sign the claim");
         let jwt = claim.to_jwt().expect("failed to encode the claim");
         let decoded_claim = jsonwebtoken::decode::<ProposerAuthClaims>(
             &jwt,
@@ -92,15 +90,17 @@ mod tests {
         assert_eq!(claim.signature, decoded_claim.signature);
     }
 
-    #[test]
-    fn test_jwt_expiry() {
+    #[tokio::test]
+    async fn test_jwt_expiry() {
         let rpc_url = "http://localhost:8545".to_string();
-        let chain = Chain::Mainnet;
+        let chain_id = Chain::Mainnet.id();
         // Set to 120 seconds past, default validation has a leeway of 60 seconds
         let expiry = current_timestamp() - 120;
         let signer = PrivateKeySigner::random();
 
-        let claim = ProposerAuthClaims::new_from_signer(rpc_url, chain, Some(expiry), signer);
+        let claim = ProposerAuthClaims::new_from_signer(rpc_url, chain_id, Some(expiry), &signer)
+            .await
+            .expect("failed to sign the claim");
         let jwt = claim.to_jwt().expect("failed to encode the claim");
         let result = jsonwebtoken::decode::<ProposerAuthClaims>(
             &jwt,
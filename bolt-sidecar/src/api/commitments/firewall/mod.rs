@@ -6,3 +6,9 @@ pub mod processor;
 
 /// The Commitments receiver.
 pub mod receiver;
+
+/// The state, stats and priorities of the connected firewall RPCs.
+pub mod rpcs;
+
+/// The notifications pushed by the sidecar to the connected websocket servers.
+pub mod notifications;
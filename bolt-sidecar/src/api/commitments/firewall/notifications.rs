@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use ethereum_consensus::crypto::PublicKey;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::primitives::jsonrpc::JsonRpcRequest;
+
+/// The JSON-RPC method of the notifications pushed to the connected websocket servers.
+pub const NOTIFICATION_METHOD: &str = "bolt_notification";
+
+/// The capacity of the notifications channel. Processors lagging behind by more than this
+/// number of notifications skip the oldest ones.
+pub const NOTIFICATIONS_CHANNEL_CAPACITY: usize = 64;
+
+/// The sender half of the notifications channel, shared by the driver and the processors.
+///
+/// It keeps the latest [SidecarNotification::ProposerSlots], as they are only pushed when a new
+/// epoch starts: the processors send them as soon as their connection is established.
+#[derive(Debug, Clone)]
+pub struct NotificationsTx {
+    tx: broadcast::Sender<SidecarNotification>,
+    proposer_slots: Arc<RwLock<Option<SidecarNotification>>>,
+}
+
+impl Default for NotificationsTx {
+    fn default() -> Self {
+        Self {
+            tx: broadcast::channel(NOTIFICATIONS_CHANNEL_CAPACITY).0,
+            proposer_slots: Arc::new(RwLock::new(None)),
+        }
+    }
+}
+
+impl NotificationsTx {
+    /// Pushes a notification to the open websocket connections, if any.
+    pub fn send(&self, notification: SidecarNotification) {
+        if matches!(notification, SidecarNotification::ProposerSlots { .. }) {
+            *self.proposer_slots.write() = Some(notification.clone());
+        }
+
+        // An error only means that no websocket connection is open at the moment.
+        let _ = self.tx.send(notification);
+    }
+
+    /// Subscribes to the notifications pushed from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<SidecarNotification> {
+        self.tx.subscribe()
+    }
+
+    /// Returns the latest proposer slots notification, if any.
+    pub fn proposer_slots(&self) -> Option<SidecarNotification> {
+        self.proposer_slots.read().clone()
+    }
+}
+
+/// A slot for which one of the validators of the sidecar is the proposer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposerSlot {
+    /// The slot number.
+    pub slot: u64,
+    /// The public key of the validator proposing the slot.
+    pub validator_pubkey: PublicKey,
+}
+
+/// A notification pushed by the sidecar to the connected websocket servers, so that RPCs can
+/// route their traffic without polling other sources.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SidecarNotification {
+    /// The slots of the current and next epoch proposed by the validators of the sidecar.
+    /// Sent when a new epoch starts, and when a connection is established.
+    #[serde(rename_all = "camelCase")]
+    ProposerSlots {
+        /// The current epoch.
+        epoch: u64,
+        /// The proposer slots of the current and next epoch.
+        slots: Vec<ProposerSlot>,
+    },
+    /// The commitment deadline of the slot has been reached: no more requests are accepted
+    /// for it.
+    #[serde(rename_all = "camelCase")]
+    CommitmentDeadline {
+        /// The slot whose deadline has been reached.
+        slot: u64,
+    },
+    /// The gas left to commit in a slot, sent after each accepted commitment.
+    #[serde(rename_all = "camelCase")]
+    RemainingGas {
+        /// The target slot.
+        slot: u64,
+        /// The remaining gas that can be committed.
+        remaining_gas: u64,
+    },
+    /// The sidecar is shutting down and stops accepting requests.
+    Shutdown,
+}
+
+impl SidecarNotification {
+    /// Returns the JSON-RPC request carrying the notification. Notifications have no ID, as no
+    /// response is expected.
+    pub fn to_request(&self) -> JsonRpcRequest {
+        JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            method: NOTIFICATION_METHOD.to_string(),
+            id: None,
+            params: vec![serde_json::to_value(self).expect("to serialize notification")],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_notification_serialization() {
+        let notification = SidecarNotification::RemainingGas { slot: 10, remaining_gas: 21_000 };
+        let request = serde_json::to_value(notification.to_request()).unwrap();
+
+        assert_eq!(request["method"], NOTIFICATION_METHOD);
+        assert_eq!(
+            request["params"][0],
+            json!({ "type": "remainingGas", "slot": 10, "remainingGas": 21_000 })
+        );
+
+        let shutdown = serde_json::to_value(SidecarNotification::Shutdown).unwrap();
+        assert_eq!(shutdown, json!({ "type": "shutdown" }));
+    }
+
+    #[test]
+    fn test_latest_proposer_slots() {
+        let notifications = NotificationsTx::default();
+        assert!(notifications.proposer_slots().is_none());
+
+        let proposer_slots = SidecarNotification::ProposerSlots { epoch: 1, slots: Vec::new() };
+        notifications.send(proposer_slots.clone());
+        notifications.send(SidecarNotification::CommitmentDeadline { slot: 32 });
+
+        // The proposer slots are kept even without open connections
+        assert_eq!(notifications.proposer_slots(), Some(proposer_slots));
+    }
+}
@@ -6,17 +6,22 @@ use futures::{
 };
 use serde::Serialize;
 use serde_json::{json, Value};
-use std::{collections::HashSet, sync::Arc, time::Duration};
 use std::{collections::VecDeque, future::Future, pin::Pin, task::Poll};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::{
     net::TcpStream,
     sync::{
         mpsc,
         oneshot::{self, error::RecvError},
-        watch,
+        watch, OwnedSemaphorePermit,
     },
     time::Interval,
 };
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
 use tokio_tungstenite::{
     tungstenite::{self, Message},
     MaybeTlsStream, WebSocketStream,
@@ -26,10 +31,15 @@ use uuid::Uuid;
 
 use crate::{
     api::commitments::{
-        server::CommitmentEvent,
+        limiter::{QuotaCharge, RateLimiter},
+        server::{
+            headers::{auth_from_str, authenticate_request},
+            CommitmentEvent,
+        },
         spec::{
-            CommitmentError, MetadataResponse, GET_METADATA_METHOD, GET_VERSION_METHOD,
-            REQUEST_INCLUSION_METHOD,
+            parse_commitment_hash, CommitmentError, MetadataResponse, GET_COMMITMENT_METHOD,
+            GET_COMMITMENT_STATUS_METHOD, GET_FULFILLMENT_REPORT_METHOD, GET_METADATA_METHOD,
+            GET_VERSION_METHOD, REQUEST_INCLUSION_METHOD,
         },
     },
     common::BOLT_SIDECAR_VERSION,
@@ -42,6 +52,12 @@ use crate::{
         misc::{Identified, IntoIdentified},
         CommitmentRequest, InclusionRequest,
     },
+    state::CommitmentIndex,
+};
+
+use super::{
+    notifications::{NotificationsTx, SidecarNotification},
+    rpcs::{FirewallRpcs, LOWER_PRIORITY_DELAY},
 };
 
 /// The interval at which to send ping messages from connected clients.
@@ -83,18 +99,44 @@ impl Future for PendingCommitmentResponse {
 }
 
 /// The internal state of the [CommitmentRequestProcessor].
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct ProcessorState {
     /// The running limits of the sidecar.
     limits: LimitsOpts,
     /// The available validator public keys in the sidecar.
     available_validators: HashSet<PublicKey>,
+    /// The rate limits and quotas, shared by all the connected websocket servers.
+    limiter: Arc<RateLimiter>,
+    /// The state and priorities of the connected websocket servers.
+    pub(super) rpcs: Arc<FirewallRpcs>,
+    /// The notifications to push to the connected websocket servers.
+    notifications: NotificationsTx,
+    /// The index of the issued commitments, to answer commitment queries.
+    commitment_index: Arc<CommitmentIndex>,
+    /// Whether to accept inclusion requests forwarded without the user signature.
+    allow_unsigned_requests: bool,
 }
 
 impl ProcessorState {
     /// Creates a new instance of the [ProcessorState].
-    pub fn new(limits: LimitsOpts, available_validators: HashSet<PublicKey>) -> Self {
-        Self { limits, available_validators }
+    pub fn new(
+        limits: LimitsOpts,
+        available_validators: HashSet<PublicKey>,
+        rpcs: Arc<FirewallRpcs>,
+        notifications: NotificationsTx,
+        commitment_index: Arc<CommitmentIndex>,
+        allow_unsigned_requests: bool,
+    ) -> Self {
+        let limiter = Arc::new(RateLimiter::new(limits));
+        Self {
+            limits,
+            available_validators,
+            limiter,
+            rpcs,
+            notifications,
+            commitment_index,
+            allow_unsigned_requests,
+        }
     }
 }
 
@@ -120,8 +162,13 @@ pub struct CommitmentRequestProcessor {
     /// SAFETY: the `poll` implementation of this struct promptly handles these responses and
     /// ensures this vector doesn't grow indefinitely.
     pending_commitment_responses: FuturesUnordered<PendingCommitmentResponse>,
+    /// The in-flight permits of the pending commitment requests, released with their response,
+    /// and the time at which the requests were received.
+    in_flight_requests: HashMap<Uuid, (OwnedSemaphorePermit, Instant)>,
     /// The collection of outgoing messages to be sent to the connected websocket server.
     outgoing_messages: VecDeque<Message>,
+    /// The stream of notifications to push to the connected websocket server.
+    notifications: BroadcastStream<SidecarNotification>,
 }
 
 impl CommitmentRequestProcessor {
@@ -134,6 +181,16 @@ impl CommitmentRequestProcessor {
         shutdown_rx: watch::Receiver<()>,
     ) -> Self {
         let (write_sink, read_stream) = stream.split();
+        let notifications = BroadcastStream::new(state.notifications.subscribe());
+
+        // Send the current lookahead right away, as it's only pushed again at the next epoch.
+        let mut outgoing_messages = VecDeque::new();
+        if let Some(proposer_slots) = state.notifications.proposer_slots() {
+            let request = proposer_slots.to_request();
+            let message =
+                Message::text(serde_json::to_string(&request).expect("to stringify request"));
+            outgoing_messages.push_back(message);
+        }
 
         Self {
             url,
@@ -144,7 +201,9 @@ impl CommitmentRequestProcessor {
             ping_interval: tokio::time::interval(PING_INTERVAL),
             shutdown_rx,
             pending_commitment_responses: FuturesUnordered::new(),
-            outgoing_messages: VecDeque::new(),
+            in_flight_requests: HashMap::new(),
+            outgoing_messages,
+            notifications,
         }
     }
 }
@@ -174,7 +233,13 @@ impl Future for CommitmentRequestProcessor {
                 this.handle_commitment_response(response);
             }
 
-            // 2. If the write sink is ready, process outgoing messages.
+            // 2. Queue the notifications to push to the connected websocket server.
+            while let Poll::Ready(Some(notification)) = this.notifications.poll_next_unpin(cx) {
+                progress = true;
+                this.handle_notification(notification);
+            }
+
+            // 3. If the write sink is ready, process outgoing messages.
             match this.write_sink.poll_ready_unpin(cx) {
                 Poll::Ready(Ok(())) => {
                     while let Some(message) = this.outgoing_messages.pop_front() {
@@ -194,7 +259,7 @@ impl Future for CommitmentRequestProcessor {
                 Poll::Pending => { /* fallthrough */ }
             }
 
-            // 3. Ensure the write sink is flushed so that message are sent to the caller server.
+            // 4. Ensure the write sink is flushed so that message are sent to the caller server.
             //
             // NOTE: We're not considering "progress" flushing the sink, i.e. `Poll::Ready(())`.
             // That is because flushing an empty sink would lead to run this loop indefinitely
@@ -203,7 +268,7 @@ impl Future for CommitmentRequestProcessor {
                 continue;
             }
 
-            // 4. Handle shutdown signals before accepting any new work
+            // 5. Handle shutdown signals before accepting any new work
             //
             // NOTE: (thedevbirb, 2025-01-15) this is a temporary workaround to ensure that the
             // shutdown signal wakes the task. https://github.com/chainbound/bolt/issues/673
@@ -214,6 +279,7 @@ impl Future for CommitmentRequestProcessor {
             match shutdown.poll_unpin(cx) {
                 Poll::Ready(Ok(_)) => {
                     info!("received shutdown signal. closing websocket connection...");
+                    this.notify_shutdown(cx);
                     return Poll::Ready(InterruptReason::Shutdown);
                 }
                 Poll::Ready(Err(_)) => {
@@ -224,7 +290,7 @@ impl Future for CommitmentRequestProcessor {
 
             // Incoming work tasks
 
-            // 5. Handle incoming websocket messages from the read stream.
+            // 6. Handle incoming websocket messages from the read stream.
             while let Poll::Ready(maybe_message) = this.read_stream.poll_next_unpin(cx) {
                 progress = true;
 
@@ -259,7 +325,7 @@ impl Future for CommitmentRequestProcessor {
                 }
             }
 
-            // 6. Handle ping messages
+            // 7. Handle ping messages
             match this.ping_interval.poll_tick(cx) {
                 Poll::Ready(_) => {
                     progress = true;
@@ -280,12 +346,17 @@ impl Future for CommitmentRequestProcessor {
 impl CommitmentRequestProcessor {
     fn handle_commitment_response(&mut self, response: Identified<CommitmentResponse, Uuid>) {
         let id = response.id();
+        let latency =
+            self.in_flight_requests.remove(&id).map(|(_, received_at)| received_at.elapsed());
 
         let Ok(result_commitment) = response.into_inner() else {
             error!("failed to receive commitment response. dropped sender");
+            self.state.rpcs.on_response(&self.url, false, latency);
             return;
         };
 
+        self.state.rpcs.on_response(&self.url, result_commitment.is_ok(), latency);
+
         let response: JsonRpcResponse = match result_commitment {
             Ok(commitment) => JsonRpcSuccessResponse::new(json!(commitment))
                 .with_id(Value::String(id.to_string()))
@@ -302,9 +373,41 @@ impl CommitmentRequestProcessor {
         self.outgoing_messages.push_back(message);
     }
 
+    fn handle_notification(
+        &mut self,
+        notification: Result<SidecarNotification, BroadcastStreamRecvError>,
+    ) {
+        match notification {
+            Ok(notification) => {
+                trace!(?notification, "pushing notification to websocket connection");
+                let request = notification.to_request();
+                let message =
+                    Message::text(serde_json::to_string(&request).expect("to stringify request"));
+                self.outgoing_messages.push_back(message);
+            }
+            Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                warn!(skipped, "notifications stream lagged behind, skipped notifications");
+            }
+        }
+    }
+
+    /// Tells the connected websocket server that the sidecar stops accepting requests.
+    ///
+    /// NOTE: this is best-effort, as the connection is closed right after: the notification is
+    /// only sent if the write sink is ready.
+    fn notify_shutdown(&mut self, cx: &mut std::task::Context<'_>) {
+        let request = SidecarNotification::Shutdown.to_request();
+        let message = Message::text(serde_json::to_string(&request).expect("to stringify request"));
+
+        if let Poll::Ready(Ok(())) = self.write_sink.poll_ready_unpin(cx) {
+            if self.write_sink.start_send_unpin(message).is_ok() {
+                let _ = self.write_sink.poll_flush_unpin(cx);
+            }
+        }
+    }
+
     fn handle_text_message(&mut self, text: String) {
         trace!(text, "received text message from websocket connection");
-        let (tx, rx) = oneshot::channel();
 
         let request = match serde_json::from_str::<JsonRpcRequestUuid>(&text) {
             Ok(req) => req,
@@ -357,47 +460,30 @@ impl CommitmentRequestProcessor {
 
                 self.send_response(response.with_uuid(id));
             }
+            GET_COMMITMENT_METHOD | GET_COMMITMENT_STATUS_METHOD => {
+                let response: JsonRpcResponse =
+                    match self.query_commitment(&request.method, &request.params) {
+                        Ok(result) => JsonRpcSuccessResponse::new(result).into(),
+                        Err(err) => JsonRpcErrorResponse::new(err.into()).into(),
+                    };
+                self.send_response(response.with_uuid(id));
+            }
+            GET_FULFILLMENT_REPORT_METHOD => {
+                let report = self.state.commitment_index.fulfillment_report();
+                let response: JsonRpcResponse = JsonRpcSuccessResponse::new(json!(report)).into();
+                self.send_response(response.with_uuid(id));
+            }
             REQUEST_INCLUSION_METHOD => {
-                let Some(param) = request.params.first().cloned() else {
-                    let response: JsonRpcResponse = JsonRpcErrorResponse::new(
-                        CommitmentError::InvalidParams("missing inclusion request".into()).into(),
-                    )
-                    .with_uuid(id)
-                    .into();
-                    self.send_response(response);
-                    return;
-                };
-
-                let inclusion_request = match serde_json::from_value::<InclusionRequest>(param) {
-                    Ok(req) => req,
-                    Err(e) => {
-                        let msg = format!("failed to parse inclusion request: {}", e);
-                        error!(?e, "failed to parse inclusion request");
-                        let response: JsonRpcResponse =
-                            JsonRpcErrorResponse::new(CommitmentError::InvalidParams(msg).into())
-                                .with_uuid(id)
-                                .into();
-                        self.send_response(response);
-                        return;
-                    }
-                };
+                self.state.rpcs.on_request(&self.url);
 
-                let commitment_request = CommitmentRequest::Inclusion(inclusion_request);
-                let commitment_event =
-                    CommitmentEvent { request: commitment_request, response: tx };
+                if let Err(err) = self.handle_inclusion_request(id, request.params) {
+                    warn!(?err, "rejected inclusion request");
+                    self.state.rpcs.on_response(&self.url, false, None);
 
-                if let Err(e) = self.api_events_tx.try_send(commitment_event) {
-                    error!(?e, "failed to send commitment event through channel");
                     let response: JsonRpcResponse =
-                        JsonRpcErrorResponse::new(CommitmentError::Internal.into())
-                            .with_uuid(id)
-                            .into();
+                        JsonRpcErrorResponse::new(err.into()).with_uuid(id).into();
                     self.send_response(response);
-                    return;
                 }
-
-                // Push the pending commitment response to the queue
-                self.pending_commitment_responses.push(PendingCommitmentResponse::new(rx, id));
             }
             other => {
                 warn!("unsupported method: {}", other);
@@ -405,9 +491,225 @@ impl CommitmentRequestProcessor {
         };
     }
 
+    /// Answers a commitment query with the index of the issued commitments.
+    fn query_commitment(&self, method: &str, params: &[Value]) -> Result<Value, CommitmentError> {
+        let hash = parse_commitment_hash(params)?;
+        let index = &self.state.commitment_index;
+
+        let result = if method == GET_COMMITMENT_METHOD {
+            index.get_commitment(&hash).map(|commitment| json!(commitment))
+        } else {
+            index.get_status(&hash).map(|status| json!(status))
+        };
+
+        result.ok_or(CommitmentError::CommitmentNotFound(hash))
+    }
+
+    /// Authenticates and admits the inclusion request, then forwards it to the sidecar.
+    ///
+    /// Requests received from RPCs without the highest priority are forwarded after a delay
+    /// (see [LOWER_PRIORITY_DELAY]), and rejected as duplicates if any of their transactions
+    /// has been received from a higher priority RPC in the meantime. The sender quotas are
+    /// only charged for the copy that is forwarded.
+    fn handle_inclusion_request(
+        &mut self,
+        id: Uuid,
+        params: Vec<Value>,
+    ) -> Result<(), CommitmentError> {
+        let mut params = params.into_iter();
+        let Some(param) = params.next() else {
+            return Err(CommitmentError::InvalidParams("missing inclusion request".into()));
+        };
+
+        let mut inclusion_request: InclusionRequest =
+            serde_json::from_value(param).map_err(|e| {
+                error!(?e, "failed to parse inclusion request");
+                CommitmentError::InvalidParams(format!("failed to parse inclusion request: {}", e))
+            })?;
+
+        // Verify the user signature forwarded by the RPC, like the HTTP server does with
+        // the signature header.
+        let auth = params.next();
+        let allow_unsigned = self.state.allow_unsigned_requests;
+        authenticate_inclusion_request(&mut inclusion_request, auth.as_ref(), allow_unsigned)?;
+
+        // NOTE: the per-IP rate limits don't apply here, as all requests come from the
+        // connected websocket server.
+        let permit = self.state.limiter.try_acquire_in_flight()?;
+
+        // The quotas are keyed by the transaction senders, signed request or not
+        inclusion_request.recover_signers()?;
+
+        let priority = self.state.rpcs.priority(&self.url);
+        self.state.rpcs.claim(&inclusion_request, priority);
+
+        let (tx, rx) = oneshot::channel();
+        let limiter = self.state.limiter.clone();
+
+        if self.state.rpcs.is_primary(&self.url) {
+            // The request is sent to the sidecar right away, so that the requests of the
+            // primary RPC are processed in their order of arrival. Only waiting for the
+            // response is spawned.
+            let charge = limiter.charge_sender_quotas(&inclusion_request)?;
+            tokio::spawn(forward_inclusion_request(
+                inclusion_request,
+                charge,
+                limiter,
+                &self.api_events_tx,
+                tx,
+            ));
+        } else {
+            let rpcs = self.state.rpcs.clone();
+            let api_events_tx = self.api_events_tx.clone();
+
+            tokio::spawn(async move {
+                tokio::time::sleep(LOWER_PRIORITY_DELAY).await;
+
+                if rpcs.is_outranked(&inclusion_request, priority) {
+                    debug!("inclusion request received from a higher priority RPC");
+                    let _ = tx.send(Err(CommitmentError::Duplicate));
+                    return;
+                }
+
+                match limiter.charge_sender_quotas(&inclusion_request) {
+                    Ok(charge) => {
+                        forward_inclusion_request(
+                            inclusion_request,
+                            charge,
+                            limiter,
+                            &api_events_tx,
+                            tx,
+                        )
+                        .await
+                    }
+                    Err(e) => {
+                        let _ = tx.send(Err(e.into()));
+                    }
+                }
+            });
+        }
+
+        // Push the pending commitment response to the queue
+        self.pending_commitment_responses.push(PendingCommitmentResponse::new(rx, id));
+        self.in_flight_requests.insert(id, (permit, Instant::now()));
+
+        Ok(())
+    }
+
     fn send_response<T: Serialize>(&mut self, response: JsonRpcResponse<T>) {
         let message =
             Message::text(serde_json::to_string(&response).expect("to stringify response"));
         self.outgoing_messages.push_back(message);
     }
 }
+
+/// Forwards the inclusion request to the sidecar right away, and returns a future that sends
+/// its response back. The quotas charged for the request are refunded if it gets rejected.
+fn forward_inclusion_request(
+    request: InclusionRequest,
+    charge: QuotaCharge,
+    limiter: Arc<RateLimiter>,
+    api_events_tx: &mpsc::Sender<CommitmentEvent>,
+    response: oneshot::Sender<Result<SignedCommitment, CommitmentError>>,
+) -> impl Future<Output = ()> {
+    let (tx, rx) = oneshot::channel();
+    let commitment_event =
+        CommitmentEvent { request: CommitmentRequest::Inclusion(request), response: tx };
+
+    let sent = api_events_tx
+        .try_send(commitment_event)
+        .inspect_err(|e| error!(?e, "failed to send commitment event through channel"))
+        .is_ok();
+
+    async move {
+        let result = if sent {
+            rx.await.unwrap_or(Err(CommitmentError::Internal))
+        } else {
+            Err(CommitmentError::Internal)
+        };
+
+        if result.is_err() {
+            limiter.refund_sender_quotas(charge);
+        }
+
+        let _ = response.send(result);
+    }
+}
+
+/// Verifies the signature of an inclusion request received from the websocket connection.
+///
+/// The RPC forwards the user signature as the second parameter of the request, formatted
+/// like the `x-bolt-signature` header of the HTTP server: `<signer>:<signature>`. If
+/// `allow_unsigned` is set, requests without it are accepted.
+fn authenticate_inclusion_request(
+    request: &mut InclusionRequest,
+    auth: Option<&Value>,
+    allow_unsigned: bool,
+) -> Result<(), CommitmentError> {
+    let Some(auth) = auth else {
+        if !allow_unsigned {
+            return Err(CommitmentError::NoSignature);
+        }
+
+        warn!("accepting unsigned inclusion request, the RPC should forward the user signature");
+        return Ok(());
+    };
+
+    let auth = auth.as_str().ok_or_else(|| {
+        CommitmentError::InvalidParams("signature param must be a string".to_string())
+    })?;
+    let (signer, signature) = auth_from_str(auth)
+        .map_err(|e| CommitmentError::InvalidParams(format!("invalid signature param: {e}")))?;
+
+    authenticate_request(request, signer, signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        hex::ToHexExt,
+        signers::{k256::SecretKey, local::PrivateKeySigner},
+    };
+
+    use crate::test_util::{create_signed_inclusion_request, default_test_transaction};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_authenticate_inclusion_request() -> eyre::Result<()> {
+        let sk = SecretKey::random(&mut rand::thread_rng());
+        let signer = PrivateKeySigner::from(sk.clone());
+        let tx = default_test_transaction(signer.address(), None);
+
+        let mut unsigned = create_signed_inclusion_request(&[tx], &sk, 10).await?;
+        let signature = unsigned.signature.take().unwrap().0;
+        unsigned.signer = None;
+
+        let auth = json!(format!("{}:{}", signer.address(), signature.as_bytes().encode_hex()));
+
+        let mut req = unsigned.clone();
+        authenticate_inclusion_request(&mut req, Some(&auth), false)?;
+        assert_eq!(req.signer(), Some(signer.address()));
+
+        // Malformed signatures are invalid params
+        let mut req = unsigned.clone();
+        assert!(matches!(
+            authenticate_inclusion_request(&mut req, Some(&json!("0xdead")), false),
+            Err(CommitmentError::InvalidParams(_))
+        ));
+        assert!(matches!(
+            authenticate_inclusion_request(&mut req, Some(&json!(42)), false),
+            Err(CommitmentError::InvalidParams(_))
+        ));
+
+        // Unsigned requests are only accepted with the compatibility flag
+        assert!(matches!(
+            authenticate_inclusion_request(&mut req, None, false),
+            Err(CommitmentError::NoSignature)
+        ));
+        authenticate_inclusion_request(&mut req, None, true)?;
+        assert!(req.signer().is_none());
+
+        Ok(())
+    }
+}
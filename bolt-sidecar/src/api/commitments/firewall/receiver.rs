@@ -1,4 +1,3 @@
-use alloy::signers::local::PrivateKeySigner;
 use ethereum_consensus::crypto::PublicKey;
 use std::{
     collections::HashSet,
@@ -17,17 +16,18 @@ use reqwest::Url;
 
 use crate::{
     api::commitments::server::CommitmentEvent,
-    common::{
-        backoff::{retry_with_backoff_if, RetryConfig},
-        secrets::EcdsaSecretKeyWrapper,
-    },
-    config::{chain::Chain, limits::LimitsOpts},
+    common::backoff::{retry_with_backoff_if, RetryConfig},
+    config::limits::LimitsOpts,
+    crypto::SignerECDSA,
     primitives::misc::ShutdownSignal,
+    state::CommitmentIndex,
 };
 
 use super::{
     jwt::ProposerAuthClaims,
+    notifications::NotificationsTx,
     processor::{CommitmentRequestProcessor, InterruptReason, ProcessorState},
+    rpcs::{serve_status, FirewallRpcs},
 };
 
 /// The maximum messages size to receive via websocket connection, in bits, set to 32MiB.
@@ -47,17 +47,19 @@ enum ConnectionHandlerError {
     OnConnectionError(#[from] tokio_tungstenite::tungstenite::Error),
     #[error("error while processing commitments")]
     ProcessorInterrupted(InterruptReason),
+    #[error("error while creating the authentication JWT: {0}")]
+    AuthError(eyre::Report),
 }
 
 /// A [CommitmentsReceiver] connects to multiple firewall-ed websocket RPC servers and
 /// forwards [CommitmentEvent]s to a single receiver, return upon calling the
 /// `[CommitmentsReceiver::run]` method.
-pub struct CommitmentsReceiver {
-    /// The operator's private key to sign authentication requests when opening websocket
-    /// connections with RPCs.
-    operator_private_key: EcdsaSecretKeyWrapper,
+pub struct CommitmentsReceiver<ECDSA> {
+    /// The operator's signer to sign authentication requests when opening websocket
+    /// connections with RPCs. It can be a local key or a remote signer like commit-boost.
+    signer: ECDSA,
     /// The chain ID of the chain the sidecar is running. Used for authentication purposes.
-    chain: Chain,
+    chain_id: u64,
     /// The URLs of the websocket servers to connect to.
     urls: Vec<Url>,
     /// The shutdown signal.
@@ -66,33 +68,50 @@ pub struct CommitmentsReceiver {
     limits: LimitsOpts,
     /// The available validator public keys on the sidecar.
     available_validators: HashSet<PublicKey>,
+    /// The priorities of the websocket servers, in the same order as the URLs.
+    priorities: Vec<u32>,
+    /// The port to serve the status of the websocket servers on, if any.
+    status_port: Option<u16>,
+    /// Whether to accept inclusion requests forwarded without the user signature.
+    allow_unsigned_requests: bool,
+    /// The notifications to push to the connected websocket servers.
+    notifications: NotificationsTx,
+    /// The index of the issued commitments, to answer commitment queries.
+    commitment_index: Arc<CommitmentIndex>,
 }
 
-impl Debug for CommitmentsReceiver {
+impl<ECDSA: SignerECDSA> Debug for CommitmentsReceiver<ECDSA> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("CommitmentsReceiver")
-            .field("operator_private_key", &"********")
-            .field("chain", &self.chain)
+            .field("operator", &self.signer.public_key())
+            .field("chain_id", &self.chain_id)
             .field("urls", &self.urls)
+            .field("priorities", &self.priorities)
+            .field("status_port", &self.status_port)
             .finish()
     }
 }
 
-impl CommitmentsReceiver {
+impl<ECDSA: SignerECDSA + Clone + Sync + 'static> CommitmentsReceiver<ECDSA> {
     /// Creates a new instance of the commitments receiver.
     pub fn new(
-        operator_private_key: EcdsaSecretKeyWrapper,
-        chain: Chain,
+        signer: ECDSA,
+        chain_id: u64,
         limits: LimitsOpts,
         urls: Vec<Url>,
         available_validators: HashSet<PublicKey>,
     ) -> Self {
         Self {
-            operator_private_key,
-            chain,
+            signer,
+            chain_id,
             urls,
             limits,
             available_validators,
+            priorities: Vec::new(),
+            status_port: None,
+            allow_unsigned_requests: false,
+            notifications: NotificationsTx::default(),
+            commitment_index: Arc::new(CommitmentIndex::default()),
             signal: Box::pin(async {
                 let _ = tokio::signal::ctrl_c().await;
             }),
@@ -105,6 +124,38 @@ impl CommitmentsReceiver {
         self
     }
 
+    /// Sets the priorities of the websocket servers, in the same order as the URLs. When the
+    /// same transaction is received from several servers, the one with the highest priority
+    /// wins. Servers without a priority default to 0.
+    pub fn with_priorities(mut self, priorities: Vec<u32>) -> Self {
+        self.priorities = priorities;
+        self
+    }
+
+    /// Sets the port to serve the status of the websocket servers on.
+    pub fn with_status_port(mut self, port: u16) -> Self {
+        self.status_port = Some(port);
+        self
+    }
+
+    /// Sets whether to accept inclusion requests forwarded without the user signature, for
+    /// servers that don't forward it yet.
+    pub fn with_unsigned_requests(mut self, allow: bool) -> Self {
+        self.allow_unsigned_requests = allow;
+        self
+    }
+
+    /// Sets the index of the issued commitments, used to answer commitment queries.
+    pub fn with_commitment_index(mut self, commitment_index: Arc<CommitmentIndex>) -> Self {
+        self.commitment_index = commitment_index;
+        self
+    }
+
+    /// Returns the sender of the notifications pushed to every open websocket connection.
+    pub fn notifications(&self) -> NotificationsTx {
+        self.notifications.clone()
+    }
+
     /// Runs the [CommitmentsReceiver] and returns a receiver for incoming commitment
     /// events.
     pub fn run(self) -> mpsc::Receiver<CommitmentEvent> {
@@ -115,8 +166,19 @@ impl CommitmentsReceiver {
 
         ShutdownTicker::new(self.signal).spawn(shutdown_tx);
 
-        let signer = PrivateKeySigner::from_signing_key(self.operator_private_key.0);
-        let state = Arc::new(ProcessorState::new(self.limits, self.available_validators));
+        let rpcs = Arc::new(FirewallRpcs::new(&self.urls, &self.priorities));
+        if let Some(port) = self.status_port {
+            tokio::spawn(serve_status(port, rpcs.clone()));
+        }
+
+        let state = Arc::new(ProcessorState::new(
+            self.limits,
+            self.available_validators,
+            rpcs,
+            self.notifications,
+            self.commitment_index,
+            self.allow_unsigned_requests,
+        ));
         let retry_config = RetryConfig { initial_delay_ms: 100, max_delay_secs: 2, factor: 2 };
 
         for url in &self.urls {
@@ -125,8 +187,9 @@ impl CommitmentsReceiver {
             let url = url.to_string();
             let api_events_tx = api_events_tx.clone();
             let shutdown_rx = shutdown_rx.clone();
-            let signer = signer.clone();
+            let signer = self.signer.clone();
             let state = state.clone();
+            let chain_id = self.chain_id;
 
             tokio::spawn(async move {
                 retry_with_backoff_if(
@@ -136,20 +199,14 @@ impl CommitmentsReceiver {
                     // As such we also need to clone the inputs again.
                     move || {
                         let url = url.clone();
-                        let jwt = ProposerAuthClaims::new_from_signer(
-                            url.clone(),
-                            self.chain,
-                            None,
-                            signer.clone(),
-                        )
-                        .to_jwt()
-                        .expect("failed to produce JWT");
-
+                        let signer = signer.clone();
                         let api_events_tx = api_events_tx.clone();
                         let shutdown_rx = shutdown_rx.clone();
                         let state = state.clone();
 
                         async move {
+                            // A fresh JWT is signed for every connection attempt, as it expires.
+                            let jwt = create_jwt(url.clone(), chain_id, &signer).await?;
                             handle_connection(url, state, jwt, api_events_tx, shutdown_rx).await
                         }
                     },
@@ -168,6 +225,19 @@ impl CommitmentsReceiver {
     }
 }
 
+/// Creates the JWT to authenticate the websocket connection to the given RPC.
+async fn create_jwt<ECDSA: SignerECDSA + Sync>(
+    url: String,
+    chain_id: u64,
+    signer: &ECDSA,
+) -> Result<String, ConnectionHandlerError> {
+    let claims = ProposerAuthClaims::new_from_signer(url, chain_id, None, signer)
+        .await
+        .map_err(ConnectionHandlerError::AuthError)?;
+
+    claims.to_jwt().map_err(|e| ConnectionHandlerError::AuthError(e.into()))
+}
+
 /// Opens the websocket connection and starts the commitment request processor.
 async fn handle_connection(
     url: String,
@@ -187,12 +257,20 @@ async fn handle_connection(
     match connect_async_with_config(request, Some(ws_config), USE_NAGLE).await {
         Ok((stream, response)) => {
             info!(?url, ?response, "opened websocket connection");
+            state.rpcs.on_connected(&url);
 
             // For each opened connection, create a new commitment processor
             // able to handle incoming message requests.
-            let commitment_request_processor =
-                CommitmentRequestProcessor::new(url, state, api_events_tx, stream, shutdown_rx);
+            let rpcs = state.rpcs.clone();
+            let commitment_request_processor = CommitmentRequestProcessor::new(
+                url.clone(),
+                state,
+                api_events_tx,
+                stream,
+                shutdown_rx,
+            );
             let interrupt_reason = commitment_request_processor.await;
+            rpcs.on_disconnected(&url);
             Err(ConnectionHandlerError::ProcessorInterrupted(interrupt_reason))
         }
         Err(e) => Err(ConnectionHandlerError::OnConnectionError(e)),
@@ -225,6 +303,9 @@ fn handle_error(err: &ConnectionHandlerError) -> bool {
         ConnectionHandlerError::ProcessorInterrupted(reason) => {
             error!(?reason, "commitment processor interrupted. Retrying...");
         }
+        ConnectionHandlerError::AuthError(e) => {
+            error!(?e, "error while creating the authentication JWT. Retrying...");
+        }
     }
     !is_shutdown
 }
@@ -256,6 +337,7 @@ impl ShutdownTicker {
 mod tests {
     use std::{net::SocketAddr, ops::ControlFlow, time::Duration};
 
+    use alloy::signers::{k256::SecretKey, local::PrivateKeySigner};
     use axum::{
         extract::{
             ws::{CloseFrame, Message, Utf8Bytes, WebSocket},
@@ -263,7 +345,7 @@ mod tests {
         },
         response::IntoResponse,
         routing::get,
-        Json, Router,
+        Extension, Json, Router,
     };
     use axum_extra::{
         headers::{authorization::Bearer, Authorization, UserAgent},
@@ -273,18 +355,23 @@ mod tests {
     use futures::{FutureExt, SinkExt, StreamExt};
     use reqwest::StatusCode;
     use serde::Serialize;
-    use tokio::sync::broadcast;
+    use tokio::sync::{broadcast, mpsc};
     use tracing::{debug, error, info, warn};
     use uuid::Uuid;
 
     use crate::{
-        api::commitments::spec::{GET_VERSION_METHOD, REQUEST_INCLUSION_METHOD},
-        common::secrets::EcdsaSecretKeyWrapper,
+        api::commitments::{
+            firewall::notifications::{SidecarNotification, NOTIFICATION_METHOD},
+            spec::{GET_VERSION_METHOD, REQUEST_INCLUSION_METHOD},
+        },
         config::chain::Chain,
         primitives::{
-            commitment::SignedCommitment, jsonrpc::JsonRpcRequest, misc::IntoSigned,
-            signature::AlloySignatureWrapper, InclusionRequest,
+            commitment::SignedCommitment,
+            jsonrpc::JsonRpcRequest,
+            misc::IntoSigned,
+            signature::{AlloySignatureWrapper, ECDSASignatureExt},
         },
+        test_util::{create_signed_inclusion_request, default_test_transaction},
     };
 
     use super::*;
@@ -299,7 +386,7 @@ mod tests {
         const CONNECTIONS_SHUTDOWN_IN_SECS: u64 = 5;
         const SERVERS_SHUTDOWN_IN_SECS: u64 = 7;
 
-        let operator_private_key = EcdsaSecretKeyWrapper::random();
+        let operator_signer = PrivateKeySigner::random();
 
         // Create a Single-Producer-Multiple-Consumer (SPMC) channel via a broadcast that sends a
         // shutdown signal to all websocket servers.
@@ -307,15 +394,19 @@ mod tests {
 
         let (shutdown_connections_tx, mut shutdown_connections_rx) = broadcast::channel::<()>(1);
 
-        let port_1 = create_websocket_server(shutdown_servers_rx.resubscribe()).await;
-        let port_2 = create_websocket_server(shutdown_servers_rx.resubscribe()).await;
+        // The notifications received by the servers
+        let (received_tx, mut received_rx) = mpsc::unbounded_channel();
+
+        let port_1 =
+            create_websocket_server(shutdown_servers_rx.resubscribe(), received_tx.clone()).await;
+        let port_2 = create_websocket_server(shutdown_servers_rx.resubscribe(), received_tx).await;
 
         info!("Server 1 running on port: {}", port_1);
         info!("Server 2 running on port: {}", port_2);
 
         let stream = CommitmentsReceiver::new(
-            operator_private_key,
-            Chain::Holesky,
+            operator_signer,
+            Chain::Holesky.id(),
             LimitsOpts::default(),
             vec![
                 format!("ws://127.0.0.1:{}{}", port_1, FIREWALL_STREAM_PATH).parse().unwrap(),
@@ -325,6 +416,11 @@ mod tests {
         )
         .with_shutdown(async move { shutdown_connections_rx.recv().await.unwrap() }.boxed());
 
+        // The lookahead is pushed before the connection is established
+        let notifications = stream.notifications();
+        let proposer_slots = SidecarNotification::ProposerSlots { epoch: 1, slots: Vec::new() };
+        notifications.send(proposer_slots.clone());
+
         let mut api_events_rx = stream.run();
 
         info!("Waiting for {CONNECTIONS_SHUTDOWN_IN_SECS} seconds before shutting down the connection...");
@@ -335,10 +431,16 @@ mod tests {
                 if let Some(event) = api_events_rx.recv().await {
                     info!("Received commitment event: {:?}", event);
                     let req = event.request.as_inclusion_request().unwrap().clone();
+                    let slot = req.slot;
                     let dumb_signed_commitment = SignedCommitment::Inclusion(
                         req.into_signed(AlloySignatureWrapper::test_signature()),
                     );
                     event.response.send(Ok(dumb_signed_commitment)).unwrap();
+
+                    // Push a notification to the connected servers, as the driver does
+                    let notification =
+                        SidecarNotification::RemainingGas { slot, remaining_gas: 1_000_000 };
+                    notifications.send(notification);
                 }
             }
         })
@@ -354,11 +456,28 @@ mod tests {
 
         info!("Shutting down the servers...");
         shutdown_servers_tx.send(()).unwrap();
+
+        let mut received = Vec::new();
+        while let Ok(notification) = received_rx.try_recv() {
+            received.push(notification);
+        }
+
+        // The current lookahead is sent as soon as the connection is established
+        assert_eq!(received.first(), Some(&proposer_slots));
+        assert!(received.iter().any(|notification| matches!(
+            notification,
+            SidecarNotification::RemainingGas { slot: 10, remaining_gas: 1_000_000 }
+        )));
     }
 
-    // Creates a websocket server
-    async fn create_websocket_server(mut shutdown_rx: broadcast::Receiver<()>) -> u16 {
-        let app = Router::new().route(FIREWALL_STREAM_PATH, get(ws_handler));
+    // Creates a websocket server, forwarding the notifications it receives to the given channel
+    async fn create_websocket_server(
+        mut shutdown_rx: broadcast::Receiver<()>,
+        received_tx: mpsc::UnboundedSender<SidecarNotification>,
+    ) -> u16 {
+        let app = Router::new()
+            .route(FIREWALL_STREAM_PATH, get(ws_handler))
+            .layer(Extension(received_tx));
         // Bind to port 0 to let the OS pick a random port for us.
         // We will return it so that clients can connect with it.
         let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -389,6 +508,7 @@ mod tests {
         ConnectInfo(addr): ConnectInfo<SocketAddr>,
         user_agent: Option<TypedHeader<UserAgent>>,
         TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+        Extension(received_tx): Extension<mpsc::UnboundedSender<SidecarNotification>>,
     ) -> impl IntoResponse {
         let user_agent = if let Some(TypedHeader(user_agent)) = user_agent {
             user_agent.to_string()
@@ -419,14 +539,18 @@ mod tests {
 
         // finalize the upgrade process by returning upgrade callback.
         // we can customize the callback by sending additional info such as address.
-        ws.on_upgrade(move |socket| handle_socket(socket, addr))
+        ws.on_upgrade(move |socket| handle_socket(socket, addr, received_tx))
     }
 
     /// Example websocket upgrade callack.
     /// Reference: https://github.com/tokio-rs/axum/blob/da63c14467dd55b1615ddbc7fc4f08c11c3df022/examples/websockets/src/main.rs#L102
     ///
     /// Actual websocket statemachine (one will be spawned per connection)
-    async fn handle_socket(mut socket: WebSocket, who: SocketAddr) {
+    async fn handle_socket(
+        mut socket: WebSocket,
+        who: SocketAddr,
+        received_tx: mpsc::UnboundedSender<SidecarNotification>,
+    ) {
         // send a ping just to kick things off and get a response
         if socket.send(Message::Ping(Bytes::from_static(&[1, 2, 3]))).await.is_ok() {
             info!("Pinged {who}...");
@@ -441,13 +565,20 @@ mod tests {
         // does)
         let mut send_task = tokio::spawn(async move {
             let n_msg = 20;
-            let inclusion_request = InclusionRequest::default();
+            let sk = SecretKey::random(&mut rand::thread_rng());
+            let signer = PrivateKeySigner::from(sk.clone());
+            let tx = default_test_transaction(signer.address(), None);
+            let inclusion_request = create_signed_inclusion_request(&[tx], &sk, 10).await.unwrap();
+
+            // The user signature is forwarded as the second param, like the HTTP signature header
+            let signature = inclusion_request.signature.as_ref().unwrap().to_hex();
+            let auth = format!("{}:{}", signer.address(), signature);
 
             let inclusion_request_payload = JsonRpcRequest {
                 jsonrpc: "2.0".to_string(),
                 method: REQUEST_INCLUSION_METHOD.to_string(),
                 id: Some(serde_json::Value::String(Uuid::now_v7().to_string())),
-                params: vec![serde_json::to_value(inclusion_request).unwrap()],
+                params: vec![serde_json::to_value(inclusion_request).unwrap(), auth.into()],
             };
 
             let inclusion_request_msg = Message::Text(into_utf8_bytes(inclusion_request_payload));
@@ -506,6 +637,10 @@ mod tests {
             let mut cnt = 0;
             while let Some(Ok(msg)) = receiver.next().await {
                 cnt += 1;
+                if let Some(notification) = parse_notification(&msg) {
+                    let _ = received_tx.send(notification);
+                }
+
                 // print message and break if instructed to do so
                 if process_message(msg, who).is_break() {
                     break;
@@ -571,6 +706,18 @@ mod tests {
         ControlFlow::Continue(())
     }
 
+    /// Parses the notification pushed by the sidecar in the given message, if any.
+    fn parse_notification(msg: &Message) -> Option<SidecarNotification> {
+        let Message::Text(text) = msg else { return None };
+        let request = serde_json::from_str::<JsonRpcRequest>(text).ok()?;
+
+        if request.method != NOTIFICATION_METHOD {
+            return None;
+        }
+
+        serde_json::from_value(request.params.into_iter().next()?).ok()
+    }
+
     fn into_utf8_bytes<S: Serialize>(input: S) -> Utf8Bytes {
         let msg = serde_json::to_string(&input).unwrap();
         Utf8Bytes::from(msg)
@@ -0,0 +1,340 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use alloy::primitives::TxHash;
+use axum::{extract::State, routing::get, Json, Router};
+use parking_lot::{Mutex, RwLock};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpListener;
+use tracing::{error, info};
+
+use crate::{primitives::InclusionRequest, telemetry::ApiMetrics};
+
+/// The delay before forwarding the requests received from RPCs without the highest priority,
+/// to give a chance to the same transactions to arrive from a higher priority RPC.
+pub const LOWER_PRIORITY_DELAY: Duration = Duration::from_millis(100);
+
+/// The number of slots for which transaction claims are tracked. Older slots are pruned.
+const MAX_TRACKED_SLOTS: usize = 64;
+
+/// The running state of a connection to a firewall RPC.
+#[derive(Debug)]
+struct RpcState {
+    /// The priority of the RPC. Higher values win.
+    priority: u32,
+    /// When the current connection was opened, if connected.
+    connected_at: Option<Instant>,
+    /// When the RPC was last disconnected, if disconnected.
+    disconnected_at: Option<Instant>,
+    /// The total uptime of the previous connections.
+    uptime: Duration,
+    /// The number of connections opened.
+    connections: u64,
+    /// The number of inclusion requests received.
+    requests: u64,
+    /// The number of inclusion requests accepted.
+    accepted: u64,
+    /// The number of inclusion requests rejected.
+    rejected: u64,
+    /// The total latency of the requests forwarded to the sidecar.
+    total_latency: Duration,
+    /// The number of requests forwarded to the sidecar.
+    forwarded: u64,
+}
+
+impl RpcState {
+    fn new(priority: u32) -> Self {
+        Self {
+            priority,
+            connected_at: None,
+            disconnected_at: Some(Instant::now()),
+            uptime: Duration::ZERO,
+            connections: 0,
+            requests: 0,
+            accepted: 0,
+            rejected: 0,
+            total_latency: Duration::ZERO,
+            forwarded: 0,
+        }
+    }
+}
+
+/// The status of a firewall RPC, as exposed by the status endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcStatus {
+    /// The URL of the RPC.
+    pub url: String,
+    /// The priority of the RPC. Higher values win.
+    pub priority: u32,
+    /// Whether the RPC is currently connected.
+    pub connected: bool,
+    /// The total time the RPC has been connected, in seconds.
+    pub uptime_secs: u64,
+    /// The time since the RPC has been disconnected, in seconds, if disconnected.
+    pub downtime_secs: Option<u64>,
+    /// The number of reconnections to the RPC.
+    pub reconnects: u64,
+    /// The number of inclusion requests received from the RPC.
+    pub requests: u64,
+    /// The number of inclusion requests accepted.
+    pub accepted: u64,
+    /// The number of inclusion requests rejected.
+    pub rejected: u64,
+    /// The average latency of the accepted and rejected requests, in milliseconds.
+    pub avg_latency_ms: Option<u64>,
+}
+
+/// The shared view of the firewall RPCs the sidecar is connected to. It tracks the
+/// connection state and the traffic of each RPC, and coordinates their priorities:
+/// when the same transaction arrives from several RPCs, the one with the highest priority
+/// wins and the others are rejected as duplicates.
+#[derive(Debug, Default)]
+pub struct FirewallRpcs {
+    /// The state of each RPC, by URL.
+    rpcs: RwLock<BTreeMap<String, RpcState>>,
+    /// The highest priority of the RPCs that sent each transaction, by target slot.
+    claims: Mutex<BTreeMap<u64, HashMap<TxHash, u32>>>,
+}
+
+impl FirewallRpcs {
+    /// Creates a new instance with the given RPC URLs and their priorities, in the same order.
+    /// RPCs without a priority default to 0.
+    pub fn new(urls: &[Url], priorities: &[u32]) -> Self {
+        let rpcs = urls
+            .iter()
+            .enumerate()
+            .map(|(i, url)| {
+                let priority = priorities.get(i).copied().unwrap_or_default();
+                (url.to_string(), RpcState::new(priority))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        Self { rpcs: RwLock::new(rpcs), claims: Mutex::new(BTreeMap::new()) }
+    }
+
+    /// Returns the priority of the given RPC.
+    pub fn priority(&self, url: &str) -> u32 {
+        self.rpcs.read().get(url).map(|rpc| rpc.priority).unwrap_or_default()
+    }
+
+    /// Returns whether the given RPC has the highest priority among the connected RPCs, in
+    /// which case its requests can be forwarded right away. Disconnected RPCs are ignored, as
+    /// no request can arrive from them in the meantime.
+    pub fn is_primary(&self, url: &str) -> bool {
+        let rpcs = self.rpcs.read();
+        let priority = rpcs.get(url).map(|rpc| rpc.priority).unwrap_or_default();
+
+        rpcs.values().filter(|rpc| rpc.connected_at.is_some()).all(|rpc| rpc.priority <= priority)
+    }
+
+    /// Records that a connection to the given RPC has been opened.
+    pub fn on_connected(&self, url: &str) {
+        let mut rpcs = self.rpcs.write();
+        let rpc = rpcs.entry(url.to_string()).or_insert_with(|| RpcState::new(0));
+
+        rpc.connected_at = Some(Instant::now());
+        rpc.disconnected_at = None;
+        rpc.connections += 1;
+
+        ApiMetrics::set_firewall_rpc_connected(url, true);
+        if rpc.connections > 1 {
+            ApiMetrics::increment_firewall_rpc_reconnects(url);
+        }
+    }
+
+    /// Records that the connection to the given RPC has been closed.
+    pub fn on_disconnected(&self, url: &str) {
+        let mut rpcs = self.rpcs.write();
+        let Some(rpc) = rpcs.get_mut(url) else { return };
+
+        if let Some(connected_at) = rpc.connected_at.take() {
+            rpc.uptime += connected_at.elapsed();
+        }
+        rpc.disconnected_at = Some(Instant::now());
+
+        ApiMetrics::set_firewall_rpc_connected(url, false);
+    }
+
+    /// Records an inclusion request received from the given RPC.
+    pub fn on_request(&self, url: &str) {
+        if let Some(rpc) = self.rpcs.write().get_mut(url) {
+            rpc.requests += 1;
+        }
+    }
+
+    /// Records the outcome of an inclusion request received from the given RPC, with its
+    /// latency if it was forwarded to the sidecar.
+    pub fn on_response(&self, url: &str, accepted: bool, latency: Option<Duration>) {
+        let mut rpcs = self.rpcs.write();
+        let Some(rpc) = rpcs.get_mut(url) else { return };
+
+        if accepted {
+            rpc.accepted += 1;
+        } else {
+            rpc.rejected += 1;
+        }
+
+        if let Some(latency) = latency {
+            rpc.total_latency += latency;
+            rpc.forwarded += 1;
+            ApiMetrics::observe_firewall_rpc_latency(url, latency);
+        }
+
+        ApiMetrics::increment_firewall_rpc_requests(url, accepted);
+    }
+
+    /// Claims the transactions of the request for an RPC with the given priority.
+    pub fn claim(&self, request: &InclusionRequest, priority: u32) {
+        let mut claims = self.claims.lock();
+        let slot_claims = claims.entry(request.slot).or_default();
+
+        for tx in &request.txs {
+            let claim = slot_claims.entry(*tx.hash()).or_insert(priority);
+            *claim = (*claim).max(priority);
+        }
+
+        while claims.len() > MAX_TRACKED_SLOTS {
+            claims.pop_first();
+        }
+    }
+
+    /// Returns whether any transaction of the request has been claimed by an RPC with a
+    /// higher priority than the given one.
+    pub fn is_outranked(&self, request: &InclusionRequest, priority: u32) -> bool {
+        let claims = self.claims.lock();
+        let Some(slot_claims) = claims.get(&request.slot) else { return false };
+
+        request
+            .txs
+            .iter()
+            .any(|tx| slot_claims.get(tx.hash()).is_some_and(|claim| *claim > priority))
+    }
+
+    /// Returns the status of all the RPCs.
+    pub fn status(&self) -> Vec<RpcStatus> {
+        self.rpcs
+            .read()
+            .iter()
+            .map(|(url, rpc)| {
+                let current_uptime = rpc.connected_at.map(|at| at.elapsed()).unwrap_or_default();
+
+                RpcStatus {
+                    url: url.clone(),
+                    priority: rpc.priority,
+                    connected: rpc.connected_at.is_some(),
+                    uptime_secs: (rpc.uptime + current_uptime).as_secs(),
+                    downtime_secs: rpc.disconnected_at.map(|at| at.elapsed().as_secs()),
+                    reconnects: rpc.connections.saturating_sub(1),
+                    requests: rpc.requests,
+                    accepted: rpc.accepted,
+                    rejected: rpc.rejected,
+                    avg_latency_ms: (rpc.forwarded > 0)
+                        .then(|| rpc.total_latency.as_millis() as u64 / rpc.forwarded),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Serves the status of the firewall RPCs as JSON on `GET /status`, at the given port.
+pub async fn serve_status(port: u16, rpcs: Arc<FirewallRpcs>) {
+    let router = Router::new().route("/status", get(status)).with_state(rpcs);
+
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    let listener = match TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            error!(?err, "Failed to bind firewall status server");
+            return;
+        }
+    };
+
+    info!("Firewall status server bound to {addr}");
+
+    if let Err(err) = axum::serve(listener, router).await {
+        error!(?err, "Firewall status server error");
+    }
+}
+
+/// Status handler
+async fn status(State(rpcs): State<Arc<FirewallRpcs>>) -> Json<Vec<RpcStatus>> {
+    Json(rpcs.status())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::signers::{k256::SecretKey, local::PrivateKeySigner};
+
+    use crate::test_util::{create_signed_inclusion_request, default_test_transaction};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_priority_claims() -> eyre::Result<()> {
+        let primary: Url = "ws://primary.rpc".parse()?;
+        let secondary: Url = "ws://secondary.rpc".parse()?;
+        let rpcs = FirewallRpcs::new(&[primary.clone(), secondary.clone()], &[10]);
+        rpcs.on_connected(primary.as_str());
+        rpcs.on_connected(secondary.as_str());
+
+        assert!(rpcs.is_primary(primary.as_str()));
+        assert!(!rpcs.is_primary(secondary.as_str()));
+
+        // Only the connected RPCs are taken into account
+        rpcs.on_disconnected(primary.as_str());
+        assert!(rpcs.is_primary(secondary.as_str()));
+        rpcs.on_connected(primary.as_str());
+
+        let sk = SecretKey::random(&mut rand::thread_rng());
+        let signer = PrivateKeySigner::from(sk.clone());
+        let tx = default_test_transaction(signer.address(), None);
+        let request = create_signed_inclusion_request(&[tx], &sk, 10).await?;
+
+        // The secondary RPC sends the request first
+        rpcs.claim(&request, rpcs.priority(secondary.as_str()));
+        assert!(!rpcs.is_outranked(&request, rpcs.priority(secondary.as_str())));
+
+        // Then the same request arrives from the primary RPC, which wins
+        rpcs.claim(&request, rpcs.priority(primary.as_str()));
+        assert!(rpcs.is_outranked(&request, rpcs.priority(secondary.as_str())));
+        assert!(!rpcs.is_outranked(&request, rpcs.priority(primary.as_str())));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rpc_status() {
+        let url: Url = "ws://primary.rpc".parse().unwrap();
+        let rpcs = FirewallRpcs::new(&[url.clone()], &[]);
+        let url = url.as_str();
+
+        rpcs.on_connected(url);
+        rpcs.on_disconnected(url);
+        rpcs.on_connected(url);
+
+        rpcs.on_request(url);
+        rpcs.on_response(url, true, Some(Duration::from_millis(30)));
+        rpcs.on_request(url);
+        rpcs.on_response(url, false, Some(Duration::from_millis(10)));
+        rpcs.on_request(url);
+        rpcs.on_response(url, false, None);
+
+        let status = rpcs.status();
+        assert_eq!(status.len(), 1);
+
+        let status = &status[0];
+        assert!(status.connected);
+        assert_eq!(status.downtime_secs, None);
+        assert_eq!(status.reconnects, 1);
+        assert_eq!(status.requests, 3);
+        assert_eq!(status.accepted, 1);
+        assert_eq!(status.rejected, 2);
+        assert_eq!(status.avg_latency_ms, Some(20));
+    }
+}
@@ -0,0 +1,309 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::IpAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use alloy::primitives::Address;
+use parking_lot::Mutex;
+use thiserror::Error;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::{config::limits::LimitsOpts, primitives::InclusionRequest};
+
+/// The number of tracked IP addresses above which idle token buckets are pruned.
+const MAX_TRACKED_IPS: usize = 1_024;
+
+/// The number of slots for which sender quotas are tracked. Older slots are pruned.
+const MAX_TRACKED_SLOTS: usize = 64;
+
+/// Error type for the admission control of the commitments API.
+#[derive(Debug, Error)]
+pub enum RateLimitError {
+    /// The IP address exceeded its requests per second.
+    #[error("Too many requests from {0}")]
+    TooManyRequests(IpAddr),
+    /// The max number of in-flight requests has been reached.
+    #[error("Too many requests in flight")]
+    TooManyInFlight,
+    /// The sender exceeded its max number of commitments for the slot.
+    #[error("Sender {0} exceeded its commitments quota for slot {1}")]
+    CommitmentsQuotaExceeded(Address, u64),
+    /// The sender exceeded its max committed gas for the slot.
+    #[error("Sender {0} exceeded its gas quota for slot {1}")]
+    GasQuotaExceeded(Address, u64),
+}
+
+/// A token bucket refilled at a constant rate, up to its capacity.
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(capacity: f64) -> Self {
+        Self { tokens: capacity, last_refill: Instant::now() }
+    }
+
+    /// Refill the bucket and try to take a token from it.
+    fn try_take(&mut self, rate: f64) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(rate);
+        self.last_refill = now;
+
+        if self.tokens < 1.0 {
+            return false;
+        }
+
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+/// The usage of a sender in a given slot.
+#[derive(Debug, Default, Clone, Copy)]
+struct SenderUsage {
+    commitments: usize,
+    gas: u64,
+}
+
+/// The quotas charged to the transaction senders of a request by
+/// [RateLimiter::charge_sender_quotas], to refund with [RateLimiter::refund_sender_quotas] if
+/// the request is rejected.
+#[derive(Debug, Clone)]
+pub struct QuotaCharge {
+    slot: u64,
+    /// The gas charged to each sender, along with one commitment.
+    senders: HashMap<Address, u64>,
+}
+
+/// Admission control for the commitments API, shared by the HTTP server and the firewall
+/// processors. It enforces:
+///
+/// - a max number of requests per second per IP address,
+/// - a max number of commitments and committed gas per transaction sender per slot, if set,
+/// - a max number of requests in flight, i.e. waiting for a response from the driver.
+///
+/// NOTE: sender quotas are charged when a request is admitted, so that concurrent requests
+/// can't overshoot them, and refunded if the request is then rejected.
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// The limits to enforce.
+    limits: LimitsOpts,
+    /// The token buckets of the IP addresses that recently sent requests.
+    ip_buckets: Mutex<HashMap<IpAddr, TokenBucket>>,
+    /// The usage of each sender, by target slot.
+    sender_usage: Mutex<BTreeMap<u64, HashMap<Address, SenderUsage>>>,
+    /// The permits for requests in flight.
+    in_flight: Arc<Semaphore>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(LimitsOpts::default())
+    }
+}
+
+impl RateLimiter {
+    /// Creates a new rate limiter with the given limits.
+    pub fn new(limits: LimitsOpts) -> Self {
+        Self {
+            limits,
+            ip_buckets: Mutex::new(HashMap::new()),
+            sender_usage: Mutex::new(BTreeMap::new()),
+            in_flight: Arc::new(Semaphore::new(limits.max_in_flight_requests.get())),
+        }
+    }
+
+    /// Takes a request from the per-second budget of the given IP address.
+    pub fn check_ip(&self, ip: IpAddr) -> Result<(), RateLimitError> {
+        let rate = self.limits.max_requests_per_second_per_ip.get() as f64;
+        let mut buckets = self.ip_buckets.lock();
+
+        if buckets.len() > MAX_TRACKED_IPS {
+            // A bucket idle for a second is full again, so it's safe to forget about it.
+            buckets.retain(|_, bucket| bucket.last_refill.elapsed() < Duration::from_secs(1));
+        }
+
+        if buckets.entry(ip).or_insert_with(|| TokenBucket::new(rate)).try_take(rate) {
+            Ok(())
+        } else {
+            Err(RateLimitError::TooManyRequests(ip))
+        }
+    }
+
+    /// Acquires a permit for a request in flight. The permit is released when dropped.
+    pub fn try_acquire_in_flight(&self) -> Result<OwnedSemaphorePermit, RateLimitError> {
+        self.in_flight.clone().try_acquire_owned().map_err(|_| RateLimitError::TooManyInFlight)
+    }
+
+    /// Charges the quotas of the senders of the given request for its target slot. Each
+    /// sender is charged one commitment and the gas limit of its own transactions.
+    ///
+    /// NOTE: the transaction senders must have been recovered beforehand, as quotas are keyed
+    /// by them. Transactions without a sender are ignored.
+    pub fn charge_sender_quotas(
+        &self,
+        request: &InclusionRequest,
+    ) -> Result<QuotaCharge, RateLimitError> {
+        let mut charge = QuotaCharge { slot: request.slot, senders: HashMap::new() };
+        for tx in &request.txs {
+            let Some(sender) = tx.sender() else { continue };
+            *charge.senders.entry(*sender).or_default() += tx.gas_limit();
+        }
+
+        let mut sender_usage = self.sender_usage.lock();
+        let slot_usage = sender_usage.entry(charge.slot).or_default();
+
+        // Check all the senders first, so that a rejected request doesn't consume any quota.
+        for (sender, gas) in &charge.senders {
+            let usage = slot_usage.get(sender).copied().unwrap_or_default();
+
+            if self
+                .limits
+                .max_commitments_per_sender_per_slot
+                .is_some_and(|max| usage.commitments >= max.get())
+            {
+                return Err(RateLimitError::CommitmentsQuotaExceeded(*sender, charge.slot));
+            }
+
+            if self
+                .limits
+                .max_committed_gas_per_sender_per_slot
+                .is_some_and(|max| usage.gas + gas > max.get())
+            {
+                return Err(RateLimitError::GasQuotaExceeded(*sender, charge.slot));
+            }
+        }
+
+        for (sender, gas) in &charge.senders {
+            let usage = slot_usage.entry(*sender).or_default();
+            usage.commitments += 1;
+            usage.gas += gas;
+        }
+
+        while sender_usage.len() > MAX_TRACKED_SLOTS {
+            sender_usage.pop_first();
+        }
+
+        Ok(charge)
+    }
+
+    /// Refunds the quotas charged for a request that has been rejected.
+    pub fn refund_sender_quotas(&self, charge: QuotaCharge) {
+        let mut sender_usage = self.sender_usage.lock();
+        let Some(slot_usage) = sender_usage.get_mut(&charge.slot) else {
+            // The slot has been pruned already
+            return;
+        };
+
+        for (sender, gas) in charge.senders {
+            if let Some(usage) = slot_usage.get_mut(&sender) {
+                usage.commitments = usage.commitments.saturating_sub(1);
+                usage.gas = usage.gas.saturating_sub(gas);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZero;
+
+    use alloy::signers::{k256::SecretKey, local::PrivateKeySigner};
+
+    use crate::test_util::{create_signed_inclusion_request, default_test_transaction};
+
+    use super::*;
+
+    #[test]
+    fn test_ip_rate_limit() {
+        let limits = LimitsOpts {
+            max_requests_per_second_per_ip: NonZero::new(2).unwrap(),
+            ..Default::default()
+        };
+        let limiter = RateLimiter::new(limits);
+
+        let ip: IpAddr = [127, 0, 0, 1].into();
+        assert!(limiter.check_ip(ip).is_ok());
+        assert!(limiter.check_ip(ip).is_ok());
+        assert!(matches!(limiter.check_ip(ip), Err(RateLimitError::TooManyRequests(_))));
+
+        // Other IP addresses have their own budget
+        assert!(limiter.check_ip([127, 0, 0, 2].into()).is_ok());
+    }
+
+    #[test]
+    fn test_in_flight_limit() {
+        let limits =
+            LimitsOpts { max_in_flight_requests: NonZero::new(1).unwrap(), ..Default::default() };
+        let limiter = RateLimiter::new(limits);
+
+        let permit = limiter.try_acquire_in_flight().unwrap();
+        assert!(matches!(limiter.try_acquire_in_flight(), Err(RateLimitError::TooManyInFlight)));
+
+        drop(permit);
+        assert!(limiter.try_acquire_in_flight().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_sender_quotas() -> eyre::Result<()> {
+        let limits = LimitsOpts {
+            max_commitments_per_sender_per_slot: NonZero::new(2),
+            ..Default::default()
+        };
+        let limiter = RateLimiter::new(limits);
+
+        let sk = SecretKey::random(&mut rand::thread_rng());
+        let signer = PrivateKeySigner::from(sk.clone());
+        let tx = default_test_transaction(signer.address(), None);
+
+        let mut request = create_signed_inclusion_request(&[tx], &sk, 10).await?;
+
+        assert!(limiter.charge_sender_quotas(&request).is_ok());
+        let charge = limiter.charge_sender_quotas(&request)?;
+        assert!(matches!(
+            limiter.charge_sender_quotas(&request),
+            Err(RateLimitError::CommitmentsQuotaExceeded(_, 10))
+        ));
+
+        // Rejected requests are refunded
+        limiter.refund_sender_quotas(charge);
+        assert!(limiter.charge_sender_quotas(&request).is_ok());
+
+        // Quotas are keyed by the transaction senders, not by the request signer
+        let other_sk = SecretKey::random(&mut rand::thread_rng());
+        let other = PrivateKeySigner::from(other_sk.clone());
+        let other_tx = default_test_transaction(other.address(), None);
+        let mut other_request = create_signed_inclusion_request(&[other_tx], &other_sk, 10).await?;
+        assert!(limiter.charge_sender_quotas(&other_request).is_ok());
+        other_request.set_signer(signer.address());
+        assert!(limiter.charge_sender_quotas(&other_request).is_ok());
+
+        // Quotas are tracked per slot
+        request.slot = 11;
+        assert!(limiter.charge_sender_quotas(&request).is_ok());
+
+        // The gas quota is enforced as well
+        let limits = LimitsOpts {
+            max_committed_gas_per_sender_per_slot: NonZero::new(request.gas_limit() - 1),
+            ..Default::default()
+        };
+        let limiter = RateLimiter::new(limits);
+        assert!(matches!(
+            limiter.charge_sender_quotas(&request),
+            Err(RateLimitError::GasQuotaExceeded(_, 11))
+        ));
+
+        // Quotas are disabled by default
+        let limiter = RateLimiter::default();
+        for _ in 0..100 {
+            assert!(limiter.charge_sender_quotas(&request).is_ok());
+        }
+
+        Ok(())
+    }
+}
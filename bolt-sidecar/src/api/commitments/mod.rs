@@ -6,3 +6,6 @@ pub mod firewall;
 
 /// The Commitments-API specification and errors.
 pub mod spec;
+
+/// The Commitments-API rate limits and quotas.
+pub mod limiter;
@@ -13,16 +13,16 @@ use tracing::{debug, error, info, instrument};
 
 use crate::{
     api::commitments::{
-        server::headers::auth_from_headers,
+        server::headers::{auth_from_headers, authenticate_request},
         spec::{
-            CommitmentError, CommitmentsApi, MetadataResponse, GET_METADATA_METHOD,
-            GET_VERSION_METHOD, REQUEST_INCLUSION_METHOD,
+            parse_commitment_hash, CommitmentError, CommitmentsApi, MetadataResponse,
+            GET_COMMITMENT_METHOD, GET_COMMITMENT_STATUS_METHOD, GET_FULFILLMENT_REPORT_METHOD,
+            GET_METADATA_METHOD, GET_VERSION_METHOD, REQUEST_INCLUSION_METHOD,
         },
     },
     common::BOLT_SIDECAR_VERSION,
     primitives::{
         jsonrpc::{JsonRpcRequest, JsonRpcResponse, JsonRpcSuccessResponse},
-        signature::SignatureError,
         InclusionRequest,
     },
 };
@@ -80,36 +80,67 @@ pub async fn rpc_entrypoint(
 
             debug!(?inclusion_request, "New inclusion request");
 
-            // Set the signature here for later processing
-            inclusion_request.set_signature(signature.into());
+            // Verify the signature and set it on the request for later processing
+            authenticate_request(&mut inclusion_request, signer, signature)?;
 
             let digest = inclusion_request.digest();
-            let recovered_signer = signature.recover_address_from_prehash(&digest)?;
+            info!(%signer, %digest, "New valid inclusion request received");
 
-            if recovered_signer != signer {
-                error!(
-                    %recovered_signer,
-                    %signer,
-                    "Recovered signer does not match the provided signer"
-                );
+            // Charge the quotas of the transaction senders before the request reaches the
+            // driver, and refund them if it gets rejected
+            inclusion_request.recover_signers()?;
+            let charge = api.limiter().charge_sender_quotas(&inclusion_request)?;
+            let inclusion_commitment = api
+                .request_inclusion(inclusion_request)
+                .await
+                .inspect_err(|_| api.limiter().refund_sender_quotas(charge))?;
 
-                return Err(CommitmentError::InvalidSignature(SignatureError));
+            // Create the JSON-RPC response
+            let response = JsonRpcSuccessResponse {
+                id: payload.id,
+                result: json!(inclusion_commitment),
+                ..Default::default()
             }
+            .into();
 
-            // Set the request signer
-            inclusion_request.set_signer(recovered_signer);
+            Ok(Json(response))
+        }
 
-            info!(signer = ?recovered_signer, %digest, "New valid inclusion request received");
-            let inclusion_commitment = api.request_inclusion(inclusion_request).await?;
+        GET_COMMITMENT_METHOD => {
+            let hash = parse_commitment_hash(&payload.params)?;
+            let commitment = api.get_commitment(hash).await?;
 
-            // Create the JSON-RPC response
             let response = JsonRpcSuccessResponse {
                 id: payload.id,
-                result: json!(inclusion_commitment),
+                result: json!(commitment),
                 ..Default::default()
             }
             .into();
+            Ok(Json(response))
+        }
+
+        GET_COMMITMENT_STATUS_METHOD => {
+            let hash = parse_commitment_hash(&payload.params)?;
+            let status = api.get_commitment_status(hash).await?;
 
+            let response = JsonRpcSuccessResponse {
+                id: payload.id,
+                result: json!(status),
+                ..Default::default()
+            }
+            .into();
+            Ok(Json(response))
+        }
+
+        GET_FULFILLMENT_REPORT_METHOD => {
+            let report = api.get_fulfillment_report().await?;
+
+            let response = JsonRpcSuccessResponse {
+                id: payload.id,
+                result: json!(report),
+                ..Default::default()
+            }
+            .into();
             Ok(Json(response))
         }
         other => {
@@ -2,10 +2,11 @@ use std::str::FromStr;
 
 use alloy::primitives::{Address, PrimitiveSignature as Signature};
 use axum::http::HeaderMap;
+use tracing::error;
 
 use crate::{
     api::commitments::spec::{CommitmentError, SIGNATURE_HEADER},
-    primitives::signature::SignatureError,
+    primitives::{signature::SignatureError, InclusionRequest},
 };
 
 /// Extracts the signature ([SIGNATURE_HEADER]) from the HTTP headers.
@@ -16,6 +17,12 @@ pub fn auth_from_headers(headers: &HeaderMap) -> Result<(Address, Signature), Co
     // Remove the "0x" prefix
     let auth = auth.to_str().map_err(|_| CommitmentError::MalformedHeader)?;
 
+    auth_from_str(auth)
+}
+
+/// Parses a signer and its signature from a [SIGNATURE_HEADER] value, formatted as
+/// `<signer>:<signature>`.
+pub fn auth_from_str(auth: &str) -> Result<(Address, Signature), CommitmentError> {
     let mut split = auth.split(':');
 
     let address = split.next().ok_or(CommitmentError::MalformedHeader)?;
@@ -28,14 +35,37 @@ pub fn auth_from_headers(headers: &HeaderMap) -> Result<(Address, Signature), Co
     Ok((address, sig))
 }
 
+/// Verifies that the inclusion request has been signed by the given signer, then sets the
+/// signature and the signer on the request.
+pub fn authenticate_request(
+    request: &mut InclusionRequest,
+    signer: Address,
+    signature: Signature,
+) -> Result<(), CommitmentError> {
+    let digest = request.digest();
+    let recovered_signer = signature.recover_address_from_prehash(&digest)?;
+
+    if recovered_signer != signer {
+        error!(%recovered_signer, %signer, "Recovered signer does not match the provided signer");
+        return Err(CommitmentError::InvalidSignature(SignatureError));
+    }
+
+    request.set_signature(signature.into());
+    request.set_signer(recovered_signer);
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use alloy::{
         hex::ToHexExt,
         primitives::TxHash,
-        signers::{local::PrivateKeySigner, Signer},
+        signers::{k256::SecretKey, local::PrivateKeySigner, Signer},
     };
 
+    use crate::test_util::{create_signed_inclusion_request, default_test_transaction};
+
     use super::*;
 
     #[tokio::test]
@@ -55,4 +85,32 @@ mod test {
         assert_eq!(signature, Signature::try_from(expected_sig.as_bytes().as_ref()).unwrap());
         assert_eq!(address, addr);
     }
+
+    #[tokio::test]
+    async fn test_authenticate_request() -> eyre::Result<()> {
+        let sk = SecretKey::random(&mut rand::thread_rng());
+        let signer = PrivateKeySigner::from(sk.clone());
+        let tx = default_test_transaction(signer.address(), None);
+
+        let mut request = create_signed_inclusion_request(&[tx], &sk, 10).await?;
+        let signature = request.signature.take().unwrap().0;
+        request.signer = None;
+
+        let auth = format!("{}:{}", signer.address(), signature.as_bytes().encode_hex());
+        let (address, signature) = auth_from_str(&auth)?;
+
+        // A different signer is rejected
+        let other = PrivateKeySigner::random().address();
+        assert!(matches!(
+            authenticate_request(&mut request, other, signature),
+            Err(CommitmentError::InvalidSignature(_))
+        ));
+        assert!(request.signer().is_none());
+
+        authenticate_request(&mut request, address, signature)?;
+        assert_eq!(request.signer(), Some(signer.address()));
+        assert!(request.signature.is_some());
+
+        Ok(())
+    }
 }
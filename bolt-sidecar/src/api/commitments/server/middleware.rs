@@ -1,6 +1,12 @@
-use crate::telemetry::ApiMetrics;
-use axum::{extract::Request, middleware::Next, response::IntoResponse};
-use std::time::Instant;
+use crate::{api::commitments::spec::CommitmentError, telemetry::ApiMetrics};
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::{net::SocketAddr, sync::Arc, time::Instant};
+
+use super::CommitmentsApiInner;
 
 /// Middleware to track server metrics for each request.
 pub async fn track_server_metrics(req: Request, next: Next) -> impl IntoResponse {
@@ -16,3 +22,27 @@ pub async fn track_server_metrics(req: Request, next: Next) -> impl IntoResponse
 
     response
 }
+
+/// Middleware to enforce the per-IP rate limits and the max number of requests in flight.
+///
+/// NOTE: the per-IP rate limits are only enforced if the peer address is known, i.e. if
+/// the router is served with [`ConnectInfo`].
+pub async fn rate_limit(
+    State(api): State<Arc<CommitmentsApiInner>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if let Some(ConnectInfo(addr)) = req.extensions().get::<ConnectInfo<SocketAddr>>() {
+        if let Err(err) = api.limiter().check_ip(addr.ip()) {
+            return CommitmentError::from(err).into_response();
+        }
+    }
+
+    // Hold the permit until the response is ready
+    let _permit = match api.limiter().try_acquire_in_flight() {
+        Ok(permit) => permit,
+        Err(err) => return CommitmentError::from(err).into_response(),
+    };
+
+    next.run(req).await
+}
@@ -2,12 +2,12 @@
 mod handlers;
 
 /// The commitments-API headers and constants.
-mod headers;
+pub(crate) mod headers;
 
 /// The commitments-API middleware.
 mod middleware;
 
-use middleware::track_server_metrics;
+use middleware::{rate_limit, track_server_metrics};
 
 use std::{
     fmt,
@@ -17,6 +17,7 @@ use std::{
     sync::Arc,
 };
 
+use alloy::primitives::B256;
 use axum::{
     routing::{get, post},
     Router,
@@ -34,9 +35,13 @@ use crate::{
         commitment::{InclusionCommitment, SignedCommitment},
         CommitmentRequest, InclusionRequest,
     },
+    state::{auditor::FulfillmentReport, commitment_index::CommitmentStatus, CommitmentIndex},
 };
 
-use super::spec::{CommitmentError, CommitmentsApi, MAX_REQUEST_TIMEOUT};
+use super::{
+    limiter::RateLimiter,
+    spec::{CommitmentError, CommitmentsApi, MAX_REQUEST_TIMEOUT},
+};
 
 /// Event type emitted by the commitments API.
 #[derive(Debug)]
@@ -55,18 +60,31 @@ pub struct CommitmentsApiInner {
     events: mpsc::Sender<CommitmentEvent>,
     /// The sidecar's operating limits that should be exposed in a metadata endpoint
     limits: LimitsOpts,
+    /// The rate limits and quotas enforced on incoming requests
+    limiter: RateLimiter,
+    /// The index of the issued commitments, to answer commitment queries
+    commitment_index: Arc<CommitmentIndex>,
 }
 
 impl CommitmentsApiInner {
     /// Creates a new instance of the commitments API handler.
-    pub fn new(events: mpsc::Sender<CommitmentEvent>, limits: LimitsOpts) -> Self {
-        Self { events, limits }
+    pub fn new(
+        events: mpsc::Sender<CommitmentEvent>,
+        limits: LimitsOpts,
+        commitment_index: Arc<CommitmentIndex>,
+    ) -> Self {
+        Self { events, limits, limiter: RateLimiter::new(limits), commitment_index }
     }
 
     /// Returns the operating limits for the sidecar.
     pub fn limits(&self) -> LimitsOpts {
         self.limits
     }
+
+    /// Returns the rate limiter of the commitments API.
+    pub fn limiter(&self) -> &RateLimiter {
+        &self.limiter
+    }
 }
 
 #[async_trait::async_trait]
@@ -86,6 +104,18 @@ impl CommitmentsApi for CommitmentsApiInner {
 
         response_rx.await.map_err(|_| CommitmentError::Internal)?.map(|c| c.into())
     }
+
+    async fn get_commitment(&self, hash: B256) -> Result<InclusionCommitment, CommitmentError> {
+        self.commitment_index.get_commitment(&hash).ok_or(CommitmentError::CommitmentNotFound(hash))
+    }
+
+    async fn get_commitment_status(&self, hash: B256) -> Result<CommitmentStatus, CommitmentError> {
+        self.commitment_index.get_status(&hash).ok_or(CommitmentError::CommitmentNotFound(hash))
+    }
+
+    async fn get_fulfillment_report(&self) -> Result<FulfillmentReport, CommitmentError> {
+        Ok(self.commitment_index.fulfillment_report())
+    }
 }
 
 /// The outer commitments-API JSON-RPC server that wraps the [CommitmentsApiInner] handler.
@@ -95,6 +125,8 @@ pub struct CommitmentsApiServer {
     addr: SocketAddr,
     /// The shutdown signal.
     signal: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+    /// The index of the issued commitments, to answer commitment queries.
+    commitment_index: Arc<CommitmentIndex>,
 }
 
 impl fmt::Debug for CommitmentsApiServer {
@@ -111,6 +143,7 @@ impl CommitmentsApiServer {
             signal: Some(Box::pin(async {
                 let _ = tokio::signal::ctrl_c().await;
             })),
+            commitment_index: Arc::new(CommitmentIndex::default()),
         }
     }
 
@@ -123,12 +156,20 @@ impl CommitmentsApiServer {
         Self {
             addr: addr.to_socket_addrs().unwrap().next().unwrap(),
             signal: Some(Box::pin(signal)),
+            commitment_index: self.commitment_index,
         }
     }
 
+    /// Sets the index of the issued commitments, used to answer commitment queries.
+    pub fn with_commitment_index(mut self, commitment_index: Arc<CommitmentIndex>) -> Self {
+        self.commitment_index = commitment_index;
+        self
+    }
+
     /// Runs the JSON-RPC server, sending events to the provided channel.
     pub async fn run(&mut self, events_tx: mpsc::Sender<CommitmentEvent>, limits: LimitsOpts) {
-        let api = Arc::new(CommitmentsApiInner::new(events_tx, limits));
+        let api =
+            Arc::new(CommitmentsApiInner::new(events_tx, limits, self.commitment_index.clone()));
 
         let router = make_router(api);
 
@@ -148,7 +189,9 @@ impl CommitmentsApiServer {
         let signal = self.signal.take().expect("Signal not set");
 
         tokio::spawn(async move {
-            if let Err(err) = axum::serve(listener, router).with_graceful_shutdown(signal).await {
+            // The peer address is needed to enforce the per-IP rate limits
+            let service = router.into_make_service_with_connect_info::<SocketAddr>();
+            if let Err(err) = axum::serve(listener, service).with_graceful_shutdown(signal).await {
                 error!(?err, "Commitments API Server error");
             }
         });
@@ -171,6 +214,7 @@ fn make_router(state: Arc<CommitmentsApiInner>) -> Router {
         .route("/status", get(handlers::status))
         .fallback(handlers::not_found)
         .layer(TimeoutLayer::new(MAX_REQUEST_TIMEOUT))
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), rate_limit))
         .route_layer(axum::middleware::from_fn(track_server_metrics))
         .with_state(state)
 }
@@ -181,9 +225,12 @@ mod test {
         api::commitments::spec::{MetadataResponse, SIGNATURE_HEADER},
         common::BOLT_SIDECAR_VERSION,
         primitives::jsonrpc::JsonRpcError,
+        state::commitment_index::CommitmentState,
     };
     use alloy::signers::{k256::SecretKey, local::PrivateKeySigner};
+    use axum::http::StatusCode;
     use serde_json::json;
+    use std::num::NonZero;
 
     use crate::{
         primitives::{jsonrpc::JsonRpcResponse, signature::ECDSASignatureExt},
@@ -338,4 +385,87 @@ mod test {
         );
         assert_eq!(metadata.version, BOLT_SIDECAR_VERSION.to_string());
     }
+
+    #[tokio::test]
+    async fn test_request_rate_limited() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let mut server = CommitmentsApiServer::new("0.0.0.0:0");
+        let (events_tx, _) = mpsc::channel(1);
+
+        let limits = LimitsOpts {
+            max_requests_per_second_per_ip: NonZero::new(1).unwrap(),
+            ..Default::default()
+        };
+        server.run(events_tx, limits).await;
+        let addr = server.local_addr();
+
+        let payload = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "bolt_getVersion",
+            "params": []
+        });
+
+        let url = format!("http://{addr}");
+        let client = reqwest::Client::new();
+
+        let response = client.post(&url).json(&payload).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = client.post(&url).json(&payload).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let response = response.json::<JsonRpcResponse>().await.unwrap();
+        assert_eq!(response.into_error().unwrap().code(), -32008);
+    }
+
+    #[tokio::test]
+    async fn test_get_commitment_status() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let commitment_index = Arc::new(CommitmentIndex::default());
+        let mut server =
+            CommitmentsApiServer::new("0.0.0.0:0").with_commitment_index(commitment_index.clone());
+        let (events_tx, _) = mpsc::channel(1);
+
+        server.run(events_tx, LimitsOpts::default()).await;
+        let addr = server.local_addr();
+
+        let sk = SecretKey::random(&mut rand::thread_rng());
+        let signer = PrivateKeySigner::from(sk.clone());
+        let tx = default_test_transaction(signer.address(), None);
+        let req = create_signed_inclusion_request(&[tx], &sk, 12).await.unwrap();
+        let tx_hash = *req.txs[0].hash();
+
+        let url = format!("http://{addr}");
+        let client = reqwest::Client::new();
+        let payload = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "bolt_getCommitmentStatus",
+            "params": [tx_hash]
+        });
+
+        // The commitment hasn't been issued yet
+        let response = client.post(&url).json(&payload).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let commitment = req.commit_and_sign(&PrivateKeySigner::random()).await.unwrap();
+        commitment_index.on_accepted(commitment);
+
+        let response = client
+            .post(&url)
+            .json(&payload)
+            .send()
+            .await
+            .unwrap()
+            .json::<JsonRpcResponse>()
+            .await
+            .unwrap();
+
+        let status: CommitmentStatus =
+            serde_json::from_value(response.into_success().unwrap().result).unwrap();
+
+        assert_eq!(status.tx_hashes, vec![tx_hash]);
+        assert_eq!(status.state, CommitmentState::Accepted);
+    }
 }
@@ -1,4 +1,4 @@
-use alloy::primitives::SignatureError as AlloySignatureError;
+use alloy::primitives::{SignatureError as AlloySignatureError, B256};
 use axum::{
     body::Body,
     extract::rejection::JsonRejection,
@@ -7,10 +7,11 @@ use axum::{
     Json,
 };
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use serde_json::{json, Value};
 use thiserror::Error;
 
 use crate::{
+    api::commitments::limiter::RateLimitError,
     config::limits::LimitsOpts,
     primitives::{
         commitment::InclusionCommitment,
@@ -18,7 +19,10 @@ use crate::{
         signature::SignatureError,
         BlsPublicKey, InclusionRequest,
     },
-    state::{consensus::ConsensusError, ValidationError},
+    state::{
+        auditor::FulfillmentReport, commitment_index::CommitmentStatus, consensus::ConsensusError,
+        ValidationError,
+    },
 };
 
 pub(super) const SIGNATURE_HEADER: &str = "x-bolt-signature";
@@ -29,6 +33,12 @@ pub(super) const REQUEST_INCLUSION_METHOD: &str = "bolt_requestInclusion";
 
 pub(super) const GET_METADATA_METHOD: &str = "bolt_metadata";
 
+pub(super) const GET_COMMITMENT_METHOD: &str = "bolt_getCommitment";
+
+pub(super) const GET_COMMITMENT_STATUS_METHOD: &str = "bolt_getCommitmentStatus";
+
+pub(super) const GET_FULFILLMENT_REPORT_METHOD: &str = "bolt_getFulfillmentReport";
+
 pub(super) const MAX_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(6);
 
 /// Error type for the commitments API.
@@ -70,6 +80,12 @@ pub enum CommitmentError {
     /// The requested validator is not available on this sidecar.
     #[error("Validator not available on this sidecar")]
     ValidatorNotAvailable(BlsPublicKey),
+    /// The request was rejected by the rate limits or quotas.
+    #[error("Rate limited: {0}")]
+    RateLimited(#[from] RateLimitError),
+    /// No commitment was found for the given request digest or transaction hash.
+    #[error("Commitment not found: {0}")]
+    CommitmentNotFound(B256),
     /// Invalid JSON.
     /// FIXME: (thedevbirb, 2025-13-01) this should be removed because it is dead code,
     /// but it allows Rust to pull the correct axum version and not older ones from
@@ -90,6 +106,8 @@ impl From<CommitmentError> for JsonRpcError {
             CommitmentError::Consensus(err) => Self::new(-32005, err.to_string()),
             CommitmentError::Validation(err) => Self::new(-32006, err.to_string()),
             CommitmentError::MalformedHeader => Self::new(-32007, err.to_string()),
+            CommitmentError::RateLimited(_) => Self::new(-32008, err.to_string()),
+            CommitmentError::CommitmentNotFound(_) => Self::new(-32009, err.to_string()),
             CommitmentError::InvalidJson(err) => {
                 Self::new(-32600, format!("Invalid request: {err}"))
             }
@@ -119,7 +137,10 @@ impl From<&CommitmentError> for StatusCode {
             | CommitmentError::RejectedJson(_)
             | CommitmentError::InvalidJson(_) => Self::BAD_REQUEST,
             CommitmentError::Internal => Self::INTERNAL_SERVER_ERROR,
-            CommitmentError::ValidatorNotAvailable(_) => Self::NOT_FOUND,
+            CommitmentError::ValidatorNotAvailable(_) | CommitmentError::CommitmentNotFound(_) => {
+                Self::NOT_FOUND
+            }
+            CommitmentError::RateLimited(_) => Self::TOO_MANY_REQUESTS,
         }
     }
 }
@@ -153,4 +174,25 @@ pub trait CommitmentsApi {
         &self,
         inclusion_request: InclusionRequest,
     ) -> Result<InclusionCommitment, CommitmentError>;
+
+    /// Returns the commitment issued for the given request digest or transaction hash.
+    async fn get_commitment(&self, hash: B256) -> Result<InclusionCommitment, CommitmentError>;
+
+    /// Returns the lifecycle status of the commitment issued for the given request digest or
+    /// transaction hash.
+    async fn get_commitment_status(&self, hash: B256) -> Result<CommitmentStatus, CommitmentError>;
+
+    /// Returns the report of the fulfillment audits of the last slots served by the sidecar.
+    async fn get_fulfillment_report(&self) -> Result<FulfillmentReport, CommitmentError>;
+}
+
+/// Parses the request digest or transaction hash from the params of a commitment query.
+pub(super) fn parse_commitment_hash(params: &[Value]) -> Result<B256, CommitmentError> {
+    let Some(param) = params.first() else {
+        return Err(CommitmentError::InvalidParams("missing request digest or tx hash".into()));
+    };
+
+    serde_json::from_value(param.clone()).map_err(|e| {
+        CommitmentError::InvalidParams(format!("invalid request digest or tx hash: {e}"))
+    })
 }
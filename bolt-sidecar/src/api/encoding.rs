@@ -0,0 +1,425 @@
+use axum::{
+    body::Body,
+    http::{
+        header::{ACCEPT, CONTENT_TYPE},
+        HeaderMap, HeaderValue, StatusCode,
+    },
+    response::{IntoResponse, Response},
+    Json,
+};
+use beacon_api_client::VersionedValue;
+use bytes::Bytes;
+use ethereum_consensus::{
+    builder::SignedValidatorRegistration,
+    deneb::mainnet::ExecutionPayloadHeader,
+    serde::as_str,
+    ssz::prelude::{ssz_rs, List},
+    types::mainnet::ExecutionPayload,
+    Fork,
+};
+use serde::{Deserialize, Serialize};
+
+use super::spec::BuilderApiError;
+use crate::{
+    config::ChainConfig,
+    primitives::{
+        ExecutionPayloadAndBlobsBundle, GetPayloadResponse, SszBlobsBundle,
+        VersionedSignedBuilderBid,
+    },
+};
+
+/// The media type for JSON encoded bodies.
+pub const JSON_CONTENT_TYPE: &str = "application/json";
+/// The media type for SSZ encoded bodies.
+pub const SSZ_CONTENT_TYPE: &str = "application/octet-stream";
+/// The header used to communicate the fork version of SSZ encoded bodies.
+pub const CONSENSUS_VERSION_HEADER: &str = "Eth-Consensus-Version";
+
+/// The maximum number of validator registrations that can be decoded from a single SSZ
+/// request. Matches `VALIDATOR_REGISTRY_LIMIT` from the consensus specs.
+const MAX_VALIDATOR_REGISTRATIONS: usize = 1 << 40;
+
+/// The position of the execution payload header offset in the SSZ encoding of a blinded
+/// beacon block body: it follows the randao reveal (96 bytes), the eth1 data (72 bytes), the
+/// graffiti (32 bytes), the offsets of the 5 operation lists (20 bytes) and the sync aggregate
+/// (160 bytes). It is the same for Deneb and Electra blocks.
+const EXECUTION_PAYLOAD_HEADER_OFFSET_POSITION: usize = 380;
+
+/// The encoding of a builder API request or response body.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Encoding {
+    /// JSON encoding, the default for the builder API.
+    #[default]
+    Json,
+    /// SSZ encoding, negotiated with `application/octet-stream`.
+    Ssz,
+}
+
+impl Encoding {
+    /// Returns the encoding of a request body from its `Content-Type` header.
+    /// Defaults to JSON if the header is missing or unknown.
+    pub fn from_content_type(headers: &HeaderMap) -> Self {
+        headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| Self::from_media_type(value.split(';').next().unwrap_or_default()))
+            .unwrap_or_default()
+    }
+
+    /// Returns the preferred encoding for a response from the `Accept` header, taking
+    /// quality values into account. On ties, the first listed media type wins.
+    /// Defaults to JSON if the header is missing or doesn't contain supported media types.
+    pub fn from_accept(headers: &HeaderMap) -> Self {
+        let Some(accept) = headers.get(ACCEPT).and_then(|value| value.to_str().ok()) else {
+            return Self::default();
+        };
+
+        let mut preferred = None;
+        let mut preferred_quality = 0.0;
+
+        for media_range in accept.split(',') {
+            let mut params = media_range.split(';');
+            let Some(encoding) = Self::from_media_type(params.next().unwrap_or_default()) else {
+                continue;
+            };
+
+            let quality = params
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            if quality > preferred_quality {
+                preferred = Some(encoding);
+                preferred_quality = quality;
+            }
+        }
+
+        preferred.unwrap_or_default()
+    }
+
+    /// Returns the media type of this encoding.
+    pub const fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => JSON_CONTENT_TYPE,
+            Self::Ssz => SSZ_CONTENT_TYPE,
+        }
+    }
+
+    fn from_media_type(media_type: &str) -> Option<Self> {
+        match media_type.trim() {
+            JSON_CONTENT_TYPE | "*/*" | "application/*" => Some(Self::Json),
+            SSZ_CONTENT_TYPE => Some(Self::Ssz),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the fork of a request body from its `Eth-Consensus-Version` header, if present.
+pub fn consensus_version(headers: &HeaderMap) -> Option<Fork> {
+    let version = headers.get(CONSENSUS_VERSION_HEADER)?.to_str().ok()?;
+    serde_json::from_value(serde_json::Value::String(version.to_lowercase())).ok()
+}
+
+/// A signed blinded beacon block submitted to the `submitBlindedBlock` endpoint.
+///
+/// Only the slot and the execution payload header of the block are decoded, as they are the
+/// only fields needed by the sidecar and have the same layout in Deneb and Electra blocks.
+/// The block is kept in its original encoding to be forwarded to relays as is.
+#[derive(Debug, Clone)]
+pub struct SignedBlindedBlock {
+    /// The fork of the block.
+    pub version: Fork,
+    /// The slot of the block.
+    pub slot: u64,
+    /// The execution payload header signed by the proposer.
+    pub execution_payload_header: ExecutionPayloadHeader,
+    /// The encoding of the original request body.
+    pub encoding: Encoding,
+    /// The original request body.
+    pub body: Bytes,
+}
+
+/// The fields of a JSON encoded signed blinded beacon block needed by the sidecar.
+#[derive(Debug, Deserialize)]
+struct PartialSignedBlindedBlock {
+    message: PartialBlindedBlock,
+}
+
+#[derive(Debug, Deserialize)]
+struct PartialBlindedBlock {
+    #[serde(with = "as_str")]
+    slot: u64,
+    body: PartialBlindedBlockBody,
+}
+
+#[derive(Debug, Deserialize)]
+struct PartialBlindedBlockBody {
+    execution_payload_header: ExecutionPayloadHeader,
+}
+
+/// Decodes a signed blinded beacon block from a request body with the given encoding.
+///
+/// The fork of the block is taken from the `Eth-Consensus-Version` header if provided,
+/// or from the fork schedule of the chain otherwise.
+pub fn decode_signed_blinded_block(
+    encoding: Encoding,
+    version: Option<Fork>,
+    body: Bytes,
+    chain: &ChainConfig,
+) -> Result<SignedBlindedBlock, BuilderApiError> {
+    let (slot, execution_payload_header) = match encoding {
+        Encoding::Json => {
+            let block: PartialSignedBlindedBlock = serde_json::from_slice(&body)?;
+            (block.message.slot, block.message.body.execution_payload_header)
+        }
+        Encoding::Ssz => decode_ssz_blinded_block_header(&body)?,
+    };
+
+    let version = match version {
+        Some(version) => version,
+        None => {
+            chain.fork_at_slot(slot).map_err(|err| BuilderApiError::InvalidFork(err.to_string()))?
+        }
+    };
+    if !matches!(version, Fork::Deneb | Fork::Electra) {
+        return Err(BuilderApiError::InvalidFork(version.to_string()));
+    }
+
+    Ok(SignedBlindedBlock { version, slot, execution_payload_header, encoding, body })
+}
+
+/// Decodes the slot and the execution payload header of an SSZ encoded signed blinded
+/// beacon block, by following the offsets of its variable-size fields.
+fn decode_ssz_blinded_block_header(
+    bytes: &[u8],
+) -> Result<(u64, ExecutionPayloadHeader), BuilderApiError> {
+    // SignedBlindedBeaconBlock: message offset, signature
+    let message = bytes.get(read_ssz_offset(bytes, 0)?..).unwrap_or_default();
+
+    // BlindedBeaconBlock: slot, proposer index, parent root, state root, body offset
+    let slot = message
+        .get(..8)
+        .map(|slot| u64::from_le_bytes(slot.try_into().expect("8 bytes")))
+        .ok_or_else(|| BuilderApiError::InvalidBlindedBlock("missing slot".to_string()))?;
+    let body = message.get(read_ssz_offset(message, 80)?..).unwrap_or_default();
+
+    // BlindedBeaconBlockBody: the execution payload header is followed by the BLS to
+    // execution changes list, whose offset marks the end of the header.
+    let start = read_ssz_offset(body, EXECUTION_PAYLOAD_HEADER_OFFSET_POSITION)?;
+    let end = read_ssz_offset(body, EXECUTION_PAYLOAD_HEADER_OFFSET_POSITION + 4)?;
+    let header = body.get(start..end).ok_or_else(|| {
+        BuilderApiError::InvalidBlindedBlock("invalid execution payload header offsets".to_string())
+    })?;
+
+    Ok((slot, ssz_rs::deserialize(header)?))
+}
+
+/// Reads an SSZ offset at the given position.
+fn read_ssz_offset(bytes: &[u8], position: usize) -> Result<usize, BuilderApiError> {
+    bytes
+        .get(position..position + 4)
+        .map(|offset| u32::from_le_bytes(offset.try_into().expect("4 bytes")) as usize)
+        .ok_or_else(|| {
+            BuilderApiError::InvalidBlindedBlock(format!("missing offset at position {position}"))
+        })
+}
+
+/// Decodes a list of validator registrations from a request body with the given encoding.
+pub fn decode_validator_registrations(
+    encoding: Encoding,
+    body: &[u8],
+) -> Result<Vec<SignedValidatorRegistration>, BuilderApiError> {
+    match encoding {
+        Encoding::Json => Ok(serde_json::from_slice(body)?),
+        Encoding::Ssz => {
+            let list: List<SignedValidatorRegistration, MAX_VALIDATOR_REGISTRATIONS> =
+                ssz_rs::deserialize(body)?;
+            Ok(list.to_vec())
+        }
+    }
+}
+
+/// Encodes a signed builder bid response with the given encoding.
+pub fn encode_builder_bid(
+    encoding: Encoding,
+    bid: VersionedValue<VersionedSignedBuilderBid>,
+) -> Result<Response, BuilderApiError> {
+    let version = bid.version;
+    match encoding {
+        Encoding::Json => Ok(json_response(&bid, version)),
+        Encoding::Ssz => Ok(ssz_response(bid.data.ssz_bytes()?, version)),
+    }
+}
+
+/// Encodes a get payload response with the given encoding.
+///
+/// For SSZ, Bellatrix and Capella payloads are encoded as a bare `ExecutionPayload`, while
+/// Deneb and Electra payloads are encoded as an `ExecutionPayloadAndBlobsBundle` container.
+pub fn encode_get_payload_response(
+    encoding: Encoding,
+    response: GetPayloadResponse,
+) -> Result<Response, BuilderApiError> {
+    if encoding == Encoding::Json {
+        return Ok(json_response(&response, response.version()));
+    }
+
+    let version = response.version();
+    let bytes = match response {
+        GetPayloadResponse::Bellatrix(ExecutionPayload::Bellatrix(payload)) => {
+            ssz_rs::serialize(&payload)?
+        }
+        GetPayloadResponse::Capella(ExecutionPayload::Capella(payload)) => {
+            ssz_rs::serialize(&payload)?
+        }
+        GetPayloadResponse::Deneb(payload_and_blobs)
+        | GetPayloadResponse::Electra(payload_and_blobs) => {
+            let ExecutionPayload::Deneb(execution_payload) = payload_and_blobs.execution_payload
+            else {
+                return Err(BuilderApiError::InvalidFork(version.to_string()));
+            };
+
+            let blobs_bundle = SszBlobsBundle::try_from(&payload_and_blobs.blobs_bundle)?;
+            ssz_rs::serialize(&ExecutionPayloadAndBlobsBundle { execution_payload, blobs_bundle })?
+        }
+        _ => return Err(BuilderApiError::InvalidFork(version.to_string())),
+    };
+
+    Ok(ssz_response(bytes, version))
+}
+
+/// Builds a JSON response with the consensus version header.
+fn json_response<T: Serialize>(body: &T, version: Fork) -> Response {
+    let mut response = Json(body).into_response();
+    insert_version_header(response.headers_mut(), version);
+    response
+}
+
+/// Builds an SSZ response with the consensus version header.
+fn ssz_response(bytes: Vec<u8>, version: Fork) -> Response {
+    let mut response = (StatusCode::OK, Body::from(bytes)).into_response();
+    let headers = response.headers_mut();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static(SSZ_CONTENT_TYPE));
+    insert_version_header(headers, version);
+    response
+}
+
+fn insert_version_header(headers: &mut HeaderMap, version: Fork) {
+    if let Ok(value) = HeaderValue::from_str(&version.to_string()) {
+        headers.insert(CONSENSUS_VERSION_HEADER, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderName;
+    use ethereum_consensus::deneb::mainnet::SignedBlindedBeaconBlock;
+
+    use super::*;
+    use crate::primitives::PayloadAndBlobs;
+
+    fn headers_with(name: HeaderName, value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn test_encoding_from_accept() {
+        assert_eq!(Encoding::from_accept(&HeaderMap::new()), Encoding::Json);
+        assert_eq!(
+            Encoding::from_accept(&headers_with(ACCEPT, "application/json")),
+            Encoding::Json
+        );
+        assert_eq!(
+            Encoding::from_accept(&headers_with(ACCEPT, "application/octet-stream")),
+            Encoding::Ssz
+        );
+        assert_eq!(
+            Encoding::from_accept(&headers_with(
+                ACCEPT,
+                "application/octet-stream;q=1.0,application/json;q=0.9"
+            )),
+            Encoding::Ssz
+        );
+        assert_eq!(
+            Encoding::from_accept(&headers_with(
+                ACCEPT,
+                "application/octet-stream;q=0.5, application/json"
+            )),
+            Encoding::Json
+        );
+        assert_eq!(Encoding::from_accept(&headers_with(ACCEPT, "text/html")), Encoding::Json);
+        assert_eq!(Encoding::from_accept(&headers_with(ACCEPT, "*/*")), Encoding::Json);
+    }
+
+    #[test]
+    fn test_encoding_from_content_type() {
+        assert_eq!(Encoding::from_content_type(&HeaderMap::new()), Encoding::Json);
+        assert_eq!(
+            Encoding::from_content_type(&headers_with(
+                CONTENT_TYPE,
+                "application/json; charset=utf-8"
+            )),
+            Encoding::Json
+        );
+        assert_eq!(
+            Encoding::from_content_type(&headers_with(CONTENT_TYPE, "application/octet-stream")),
+            Encoding::Ssz
+        );
+    }
+
+    #[tokio::test]
+    async fn test_encode_deneb_payload_ssz() {
+        let response = GetPayloadResponse::from(PayloadAndBlobs::default());
+        let encoded = encode_get_payload_response(Encoding::Ssz, response).unwrap();
+
+        assert_eq!(encoded.headers().get(CONTENT_TYPE).unwrap(), SSZ_CONTENT_TYPE);
+        assert_eq!(encoded.headers().get(CONSENSUS_VERSION_HEADER).unwrap(), "deneb");
+
+        let bytes = axum::body::to_bytes(encoded.into_body(), usize::MAX).await.unwrap();
+        let decoded: ExecutionPayloadAndBlobsBundle = ssz_rs::deserialize(&bytes).unwrap();
+        assert_eq!(decoded.execution_payload.block_number, 0);
+        assert!(decoded.blobs_bundle.blobs.is_empty());
+    }
+
+    #[test]
+    fn test_decode_blinded_block_roundtrip() {
+        let mut block = SignedBlindedBeaconBlock::default();
+        block.message.slot = 42;
+        block.message.body.execution_payload_header.block_number = 7;
+        block.message.body.execution_payload_header.gas_used = 21_000;
+
+        let ssz = Bytes::from(ssz_rs::serialize(&block).unwrap());
+        let json = Bytes::from(serde_json::to_vec(&block).unwrap());
+        // Slot 42 is in the Deneb fork with a schedule starting from genesis, but not on Mainnet
+        let chain = ChainConfig::kurtosis(0, 0);
+        let mainnet = ChainConfig::mainnet();
+        assert!(decode_signed_blinded_block(Encoding::Ssz, None, ssz.clone(), &mainnet).is_err());
+
+        let from_ssz = decode_signed_blinded_block(Encoding::Ssz, None, ssz, &chain).unwrap();
+        let from_json =
+            decode_signed_blinded_block(Encoding::Json, Some(Fork::Deneb), json, &chain).unwrap();
+
+        assert_eq!(from_ssz.slot, 42);
+        assert_eq!(from_ssz.version, Fork::Deneb);
+        assert_eq!(from_ssz.slot, from_json.slot);
+        assert_eq!(from_ssz.execution_payload_header, block.message.body.execution_payload_header);
+        assert_eq!(from_ssz.execution_payload_header, from_json.execution_payload_header);
+
+        // Truncated blocks are rejected
+        let truncated = Bytes::from(ssz_rs::serialize(&block).unwrap()[..200].to_vec());
+        assert!(decode_signed_blinded_block(Encoding::Ssz, None, truncated, &chain).is_err());
+    }
+
+    #[test]
+    fn test_consensus_version() {
+        assert_eq!(consensus_version(&HeaderMap::new()), None);
+        assert_eq!(
+            consensus_version(&headers_with(
+                HeaderName::from_static("eth-consensus-version"),
+                "electra"
+            )),
+            Some(Fork::Electra)
+        );
+    }
+}
@@ -2,6 +2,9 @@
 /// Reference: <https://ethereum.github.io/builder-specs/#/>
 pub mod builder;
 
+/// Content negotiation and SSZ encoding for the builder API
+pub mod encoding;
+
 /// Sidecar API spec and error handling
 pub mod spec;
 
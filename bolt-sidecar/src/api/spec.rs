@@ -5,16 +5,17 @@ use axum::{
 };
 use beacon_api_client::VersionedValue;
 use ethereum_consensus::{
-    builder::SignedValidatorRegistration, deneb::mainnet::SignedBlindedBeaconBlock,
+    builder::SignedValidatorRegistration,
+    ssz::prelude::{DeserializeError, SerializeError, SimpleSerializeError},
 };
 use serde::{Deserialize, Serialize, Serializer};
 
 use crate::primitives::{
-    BatchedSignedConstraints, GetPayloadResponse, SignedBuilderBid, SignedDelegation,
-    SignedRevocation,
+    proofs::ProofError, BatchedSignedConstraints, GetPayloadResponse, SignedBuilderBidWithProofs,
+    SignedDelegation, SignedRevocation, VersionedSignedBuilderBid,
 };
 
-use super::builder::GetHeaderParams;
+use super::{builder::GetHeaderParams, encoding::SignedBlindedBlock};
 
 /// The path to the builder API status endpoint.
 pub const STATUS_PATH: &str = "/eth/v1/builder/status";
@@ -71,12 +72,24 @@ pub enum BuilderApiError {
     AxumError(#[from] axum::Error),
     #[error("Json error: {0:?}")]
     JsonError(#[from] serde_json::Error),
+    #[error("SSZ decoding error: {0:?}")]
+    SszDeserialize(#[from] DeserializeError),
+    #[error("SSZ encoding error: {0:?}")]
+    SszSerialize(#[from] SerializeError),
+    #[error("SSZ error: {0:?}")]
+    Ssz(#[from] SimpleSerializeError),
     #[error("Reqwest error: {0:?}")]
     ReqwestError(#[from] reqwest::Error),
     #[error("API request timed out : {0:?}")]
     Timeout(#[from] tokio::time::error::Elapsed),
     #[error("Invalid fork: {0}")]
     InvalidFork(String),
+    #[error("Invalid signed blinded block: {0}")]
+    InvalidBlindedBlock(String),
+    #[error("Invalid inclusion proofs: {0}")]
+    InvalidProofs(#[from] ProofError),
+    #[error("Failed to fetch constraints for slot {0}")]
+    FailedToFetchConstraints(u64),
     #[error("Locally-built payload does not match expected signed header")]
     LocalPayloadIntegrity(#[from] super::builder::LocalPayloadIntegrityError),
     #[error("Generic error: {0}")]
@@ -97,6 +110,15 @@ impl IntoResponse for BuilderApiError {
             Self::NoBids(_) => (StatusCode::NO_CONTENT, self.to_string()).into_response(),
             Self::AxumError(err) => (StatusCode::BAD_REQUEST, err.to_string()).into_response(),
             Self::JsonError(err) => (StatusCode::BAD_REQUEST, err.to_string()).into_response(),
+            Self::SszDeserialize(err) => {
+                (StatusCode::BAD_REQUEST, format!("{err:?}")).into_response()
+            }
+            Self::SszSerialize(err) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, format!("{err:?}")).into_response()
+            }
+            Self::Ssz(err) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, format!("{err:?}")).into_response()
+            }
             Self::FailedToFetchLocalPayload(_) => {
                 (StatusCode::NO_CONTENT, self.to_string()).into_response()
             }
@@ -111,6 +133,13 @@ impl IntoResponse for BuilderApiError {
             )
                 .into_response(),
             Self::InvalidFork(err) => (StatusCode::BAD_REQUEST, Json(err)).into_response(),
+            Self::InvalidBlindedBlock(_) => {
+                (StatusCode::BAD_REQUEST, self.to_string()).into_response()
+            }
+            Self::InvalidProofs(_) => (StatusCode::BAD_GATEWAY, self.to_string()).into_response(),
+            Self::FailedToFetchConstraints(_) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()).into_response()
+            }
             Self::LocalPayloadIntegrity(err) => {
                 (StatusCode::BAD_REQUEST, err.to_string()).into_response()
             }
@@ -138,12 +167,12 @@ pub trait BuilderApi {
     async fn get_header(
         &self,
         params: GetHeaderParams,
-    ) -> Result<SignedBuilderBid, BuilderApiError>;
+    ) -> Result<VersionedValue<VersionedSignedBuilderBid>, BuilderApiError>;
 
     /// Implements: <https://ethereum.github.io/builder-specs/#/Builder/submitBlindedBlock>
     async fn get_payload(
         &self,
-        signed_block: SignedBlindedBeaconBlock,
+        signed_block: SignedBlindedBlock,
     ) -> Result<GetPayloadResponse, BuilderApiError>;
 }
 
@@ -163,7 +192,7 @@ pub trait ConstraintsApi: BuilderApi {
     async fn get_header_with_proofs(
         &self,
         params: GetHeaderParams,
-    ) -> Result<VersionedValue<SignedBuilderBid>, BuilderApiError>;
+    ) -> Result<VersionedValue<SignedBuilderBidWithProofs>, BuilderApiError>;
 
     /// Implements: <https://docs.boltprotocol.xyz/technical-docs/api/builder#delegate>
     async fn delegate(&self, signed_data: &[SignedDelegation]) -> Result<(), BuilderApiError>;
@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+
+use alloy::primitives::TxHash;
+use parking_lot::RwLock;
+
+use crate::primitives::PayloadAndBid;
+
+/// The number of slots for which built payloads are kept in the cache.
+const CACHED_SLOTS: u64 = 4;
+
+/// A payload built by the local builder, along with the constrained transactions it was
+/// built with.
+#[derive(Debug, Clone)]
+pub struct CachedPayload {
+    /// The hashes of the constrained transactions of the template the payload was built from.
+    pub transaction_hashes: Vec<TxHash>,
+    /// The payload and the signed bid for it.
+    pub payload_and_bid: PayloadAndBid,
+}
+
+/// A cache of the payloads built by the local builder, indexed by target slot.
+///
+/// Payloads are built against the current head of the chain, so every new head bumps the
+/// cache generation and evicts the payloads that were not finalized yet: builds started with
+/// an older generation are discarded when they complete. Once the payload of a slot is
+/// finalized at the commitment deadline, it can't be replaced by background builds anymore.
+#[derive(Debug, Default)]
+pub struct PayloadCache {
+    inner: RwLock<CacheInner>,
+}
+
+#[derive(Debug, Default)]
+struct CacheInner {
+    /// The generation of the cache, incremented on every new head.
+    generation: u64,
+    /// The cached payloads per slot, with whether they were finalized.
+    payloads: BTreeMap<u64, (CachedPayload, bool)>,
+}
+
+impl PayloadCache {
+    /// Returns the current generation of the cache. It must be read before starting a build,
+    /// and passed to [PayloadCache::insert] when the build completes.
+    pub fn generation(&self) -> u64 {
+        self.inner.read().generation
+    }
+
+    /// Inserts a payload built for the given slot, unless the cache generation changed since the
+    /// build started or the payload of the slot was already finalized.
+    ///
+    /// Returns `true` if the payload was inserted.
+    pub fn insert(
+        &self,
+        slot: u64,
+        generation: u64,
+        payload: CachedPayload,
+        finalized: bool,
+    ) -> bool {
+        let mut inner = self.inner.write();
+
+        if inner.generation != generation {
+            return false;
+        }
+
+        if inner.payloads.get(&slot).is_some_and(|(_, finalized)| *finalized) {
+            return false;
+        }
+
+        inner.payloads.insert(slot, (payload, finalized));
+        inner.payloads.retain(|cached_slot, _| *cached_slot + CACHED_SLOTS > slot);
+
+        true
+    }
+
+    /// Marks the payload of the given slot as finalized if it was built with exactly the given
+    /// constrained transactions.
+    ///
+    /// Returns `true` if the payload is up to date and was finalized.
+    pub fn finalize(&self, slot: u64, transaction_hashes: &[TxHash]) -> bool {
+        let mut inner = self.inner.write();
+
+        match inner.payloads.get_mut(&slot) {
+            Some((payload, finalized)) if payload.transaction_hashes == transaction_hashes => {
+                *finalized = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Invalidates the payloads built on top of a previous head, bumping the cache generation.
+    /// Finalized payloads are kept, as they may still be requested by the beacon node.
+    pub fn invalidate(&self) {
+        let mut inner = self.inner.write();
+
+        inner.generation += 1;
+        inner.payloads.retain(|_, (_, finalized)| *finalized);
+    }
+
+    /// Returns a copy of the payload and bid built for the given slot, if it was finalized.
+    ///
+    /// Payloads that were not finalized may have been built from outdated constraints, so they
+    /// are never served.
+    pub fn get(&self, slot: u64) -> Option<PayloadAndBid> {
+        match self.inner.read().payloads.get(&slot) {
+            Some((payload, true)) => Some(payload.payload_and_bid.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::B256;
+
+    use super::*;
+    use crate::primitives::{GetPayloadResponse, PayloadAndBlobs, VersionedSignedBuilderBid};
+
+    fn payload(hashes: &[TxHash]) -> CachedPayload {
+        CachedPayload {
+            transaction_hashes: hashes.to_vec(),
+            payload_and_bid: PayloadAndBid {
+                bid: VersionedSignedBuilderBid::default(),
+                payload: GetPayloadResponse::from(PayloadAndBlobs::default()),
+            },
+        }
+    }
+
+    #[test]
+    fn test_payload_cache_generations() {
+        let cache = PayloadCache::default();
+        let hashes = [B256::repeat_byte(1)];
+
+        let generation = cache.generation();
+        assert!(cache.insert(10, generation, payload(&hashes), false));
+
+        // A new head evicts the payload, and builds started before it are discarded
+        cache.invalidate();
+        assert!(!cache.finalize(10, &hashes));
+        assert!(!cache.insert(10, generation, payload(&hashes), true));
+
+        assert!(cache.insert(10, cache.generation(), payload(&hashes), false));
+        assert!(cache.finalize(10, &hashes));
+    }
+
+    #[test]
+    fn test_payload_cache_finalize() {
+        let cache = PayloadCache::default();
+        let hashes = [B256::repeat_byte(1), B256::repeat_byte(2)];
+
+        assert!(cache.insert(10, cache.generation(), payload(&hashes[..1]), false));
+
+        // Payloads are only served once finalized
+        assert!(cache.get(10).is_none());
+
+        // The cached payload doesn't include the latest constraint
+        assert!(!cache.finalize(10, &hashes));
+        assert!(cache.get(10).is_none());
+
+        assert!(cache.insert(10, cache.generation(), payload(&hashes), false));
+        assert!(cache.finalize(10, &hashes));
+
+        // Finalized payloads can't be replaced, and survive new heads
+        assert!(!cache.insert(10, cache.generation(), payload(&hashes[..1]), false));
+        cache.invalidate();
+        assert!(cache.get(10).is_some());
+
+        // Old slots are evicted
+        assert!(cache.insert(10 + CACHED_SLOTS, cache.generation(), payload(&hashes), true));
+        assert!(cache.get(10).is_none());
+    }
+}
@@ -1,16 +1,20 @@
 use alloy::{
-    consensus::BlockHeader,
-    eips::{eip2718::Encodable2718, eip4895::Withdrawal},
-    primitives::{Address, Bloom, B256, U256},
+    consensus::{proofs, BlobTransactionSidecar, BlockHeader, Header, EMPTY_OMMER_ROOT_HASH},
+    eips::{
+        eip2718::{Decodable2718, Encodable2718},
+        eip4895::Withdrawal,
+    },
+    primitives::{Address, Bloom, B256, B64, U256},
     rpc::types::Withdrawals,
 };
 use alloy_rpc_types_engine::{ExecutionPayloadV1, ExecutionPayloadV2, ExecutionPayloadV3};
 use ethereum_consensus::{
     bellatrix::mainnet::Transaction,
     capella::spec,
+    crypto::{KzgCommitment, KzgProof},
     deneb::{
         mainnet::{
-            ExecutionPayloadHeader as ConsensusExecutionPayloadHeader,
+            Blob, BlobsBundle, ExecutionPayloadHeader as ConsensusExecutionPayloadHeader,
             Withdrawal as ConsensusWithdrawal, MAX_TRANSACTIONS_PER_PAYLOAD,
             MAX_WITHDRAWALS_PER_PAYLOAD,
         },
@@ -19,9 +23,11 @@ use ethereum_consensus::{
     ssz::prelude::{ssz_rs, ByteList, ByteVector, HashTreeRoot, List},
     types::mainnet::ExecutionPayload as ConsensusExecutionPayload,
 };
-use reth_primitives::{SealedBlock, TransactionSigned};
+use reth_primitives::{SealedBlock, SealedHeader, TransactionSigned};
 use reth_primitives_traits::BlockBody;
 
+use super::BuilderError;
+
 /// Compatibility: convert a sealed header into an ethereum-consensus execution payload header.
 /// This requires recalculating the withdrals and transactions roots as SSZ instead of MPT roots.
 pub(crate) fn to_execution_payload_header(
@@ -164,6 +170,66 @@ pub(crate) fn to_consensus_execution_payload(value: &SealedBlock) -> ConsensusEx
     ConsensusExecutionPayload::Deneb(payload)
 }
 
+/// Compatibility: convert an Alloy execution payload returned by the engine API into a
+/// sealed block. The block hash is recomputed from the header and must match the payload's.
+///
+/// The requests hash must be provided for payloads from the Electra fork onwards.
+pub(crate) fn to_reth_sealed_block(
+    payload: ExecutionPayloadV3,
+    parent_beacon_block_root: B256,
+    requests_hash: Option<B256>,
+) -> Result<SealedBlock, BuilderError> {
+    let ExecutionPayloadV3 { payload_inner, blob_gas_used, excess_blob_gas } = payload;
+    let ExecutionPayloadV2 { payload_inner: inner, withdrawals } = payload_inner;
+
+    let transactions = inner
+        .transactions
+        .iter()
+        .map(|raw| TransactionSigned::decode_2718(&mut raw.as_ref()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| BuilderError::InvalidEnginePayload(e.to_string()))?;
+
+    let header = Header {
+        parent_hash: inner.parent_hash,
+        ommers_hash: EMPTY_OMMER_ROOT_HASH,
+        beneficiary: inner.fee_recipient,
+        state_root: inner.state_root,
+        transactions_root: proofs::calculate_transaction_root(&transactions),
+        receipts_root: inner.receipts_root,
+        withdrawals_root: Some(proofs::calculate_withdrawals_root(&withdrawals)),
+        logs_bloom: inner.logs_bloom,
+        difficulty: U256::ZERO,
+        number: inner.block_number,
+        gas_limit: inner.gas_limit,
+        gas_used: inner.gas_used,
+        timestamp: inner.timestamp,
+        mix_hash: inner.prev_randao,
+        nonce: B64::ZERO,
+        base_fee_per_gas: Some(inner.base_fee_per_gas.saturating_to()),
+        blob_gas_used: Some(blob_gas_used),
+        excess_blob_gas: Some(excess_blob_gas),
+        parent_beacon_block_root: Some(parent_beacon_block_root),
+        extra_data: inner.extra_data,
+        requests_hash,
+    };
+
+    let hash = header.hash_slow();
+    if hash != inner.block_hash {
+        return Err(BuilderError::InvalidEnginePayload(format!(
+            "block hash mismatch: computed {hash}, payload {}",
+            inner.block_hash
+        )));
+    }
+
+    let body = reth_primitives::BlockBody {
+        transactions,
+        ommers: Vec::new(),
+        withdrawals: Some(Withdrawals::new(withdrawals)),
+    };
+
+    Ok(SealedBlock::new(SealedHeader::new(header, hash), body))
+}
+
 /// Compatibility: convert a withdrawal from alloy::primitives to ethereum-consensus
 pub(crate) fn to_consensus_withdrawal(
     value: &Withdrawal,
@@ -176,6 +242,36 @@ pub(crate) fn to_consensus_withdrawal(
     }
 }
 
+/// Compatibility: convert a list of alloy blob sidecars into an ethereum-consensus blobs bundle,
+/// keeping the order of the blobs.
+pub(crate) fn to_consensus_blobs_bundle<'a>(
+    sidecars: impl IntoIterator<Item = &'a BlobTransactionSidecar>,
+) -> BlobsBundle {
+    let (mut commitments, mut proofs, mut blobs) = (Vec::new(), Vec::new(), Vec::new());
+    for sidecar in sidecars {
+        commitments.extend(
+            sidecar
+                .commitments
+                .iter()
+                .map(|c| KzgCommitment::try_from(c.as_slice()).expect("both are 48 bytes")),
+        );
+        proofs.extend(
+            sidecar
+                .proofs
+                .iter()
+                .map(|p| KzgProof::try_from(p.as_slice()).expect("both are 48 bytes")),
+        );
+        blobs.extend(
+            sidecar
+                .blobs
+                .iter()
+                .map(|b| Blob::try_from(b.as_slice()).expect("both are 131_072 bytes")),
+        );
+    }
+
+    BlobsBundle { commitments, proofs, blobs }
+}
+
 pub(crate) fn to_bytes32(value: B256) -> spec::Bytes32 {
     spec::Bytes32::try_from(value.as_ref()).unwrap()
 }
@@ -187,3 +283,54 @@ pub(crate) fn to_bytes20(value: Address) -> spec::ExecutionAddress {
 pub(crate) fn to_byte_vector(value: Bloom) -> ByteVector<256> {
     ByteVector::<256>::try_from(value.as_ref()).unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::Bytes;
+
+    use super::*;
+
+    #[test]
+    fn test_alloy_payload_roundtrip() {
+        let parent_beacon_block_root = B256::repeat_byte(0x42);
+        let header = Header {
+            ommers_hash: EMPTY_OMMER_ROOT_HASH,
+            transactions_root: proofs::calculate_transaction_root::<TransactionSigned>(&[]),
+            withdrawals_root: Some(proofs::calculate_withdrawals_root(&[])),
+            number: 10,
+            gas_limit: 30_000_000,
+            timestamp: 1_700_000_000,
+            base_fee_per_gas: Some(7),
+            blob_gas_used: Some(0),
+            excess_blob_gas: Some(0),
+            parent_beacon_block_root: Some(parent_beacon_block_root),
+            extra_data: Bytes::from_static(b"bolt"),
+            ..Default::default()
+        };
+        let hash = header.hash_slow();
+        let body = reth_primitives::BlockBody {
+            transactions: Vec::new(),
+            ommers: Vec::new(),
+            withdrawals: Some(Withdrawals::default()),
+        };
+        let block = SealedBlock::new(SealedHeader::new(header, hash), body);
+
+        let payload = to_alloy_execution_payload(&block, hash);
+        let sealed = to_reth_sealed_block(payload.clone(), parent_beacon_block_root, None).unwrap();
+        assert_eq!(sealed.hash(), hash);
+
+        // The requests hash is part of the block hash from the Electra fork onwards
+        assert!(matches!(
+            to_reth_sealed_block(payload.clone(), parent_beacon_block_root, Some(B256::ZERO)),
+            Err(BuilderError::InvalidEnginePayload(_))
+        ));
+
+        // A payload with a different block hash must be rejected
+        let mut invalid = payload;
+        invalid.payload_inner.payload_inner.block_hash = B256::ZERO;
+        assert!(matches!(
+            to_reth_sealed_block(invalid, parent_beacon_block_root, None),
+            Err(BuilderError::InvalidEnginePayload(_))
+        ));
+    }
+}
@@ -0,0 +1,230 @@
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+use alloy::{
+    consensus::Transaction,
+    eips::eip2718::Encodable2718,
+    primitives::{keccak256, Address, B256},
+    providers::Provider,
+    rpc::types::{Block, Withdrawal},
+};
+use alloy_provider::ext::EngineApi;
+use alloy_rpc_types_engine::{ForkchoiceState, PayloadAttributes, PayloadId};
+use ethereum_consensus::Fork;
+use reth_primitives::TransactionSigned;
+use tracing::debug;
+
+use super::FallbackPayload;
+use crate::{
+    builder::{compat::to_reth_sealed_block, BuilderError},
+    client::EngineClient,
+    primitives::ExecutionRequests,
+};
+
+/// The maximum time given to the execution client to build a payload containing all the
+/// constrained transactions, from the `engine_forkchoiceUpdatedV3` call that starts the build.
+const PAYLOAD_BUILD_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// The interval between two `engine_getPayload` calls while the payload is being built.
+const PAYLOAD_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The [EngineBuilder] asks the execution client to build a payload on top of the head
+/// of the chain, instead of assembling one from hints.
+///
+/// The constrained transactions are first injected in the execution client's txpool with
+/// `eth_sendRawTransaction`, then a payload build is started with `engine_forkchoiceUpdatedV3`
+/// and fetched with `engine_getPayloadV3`, or `engine_getPayloadV4` from the Electra fork
+/// onwards. The resulting payload must contain all the constrained transactions to be used.
+///
+/// Since the execution client selects transactions from its own txpool, the payload may also
+/// contain other pending transactions. Blob transactions are not supported, as the signed
+/// transactions don't carry the sidecars needed to inject them.
+///
+/// The payload is polled with `engine_getPayload` until it contains all the constrained
+/// transactions in the order of the constraints, or until [PAYLOAD_BUILD_TIMEOUT] is reached.
+///
+/// NOTE: the execution client gossips the injected transactions to its peers like any other
+/// transaction submitted with `eth_sendRawTransaction`, so the constrained transactions are
+/// leaked to the public mempool before the slot. This builder is therefore only used when
+/// selected explicitly, and only at the commitment deadline.
+#[derive(Debug)]
+pub struct EngineBuilder {
+    engine_client: EngineClient,
+}
+
+impl EngineBuilder {
+    /// Create a new [EngineBuilder] instance with the given engine client.
+    pub fn new(engine_client: EngineClient) -> Self {
+        Self { engine_client }
+    }
+
+    /// Build a payload containing the given transactions with the execution client's
+    /// payload builder.
+    pub async fn build_payload(
+        &self,
+        ctx: EngineBuilderContext,
+        transactions: &[TransactionSigned],
+    ) -> Result<FallbackPayload, BuilderError> {
+        if transactions.iter().any(|tx| tx.blob_versioned_hashes().is_some()) {
+            return Err(BuilderError::UnsupportedBlobTransactions);
+        }
+
+        // 1. inject the constrained transactions in the txpool. Errors are not fatal, as the
+        // transactions may already be known: missing ones are detected in the built payload.
+        for tx in transactions {
+            if let Err(err) = self.engine_client.send_raw_transaction(&tx.encoded_2718()).await {
+                debug!(?err, "Failed to inject transaction in the execution client txpool");
+            }
+        }
+
+        // 2. start a payload build on top of the current head. The safe and finalized hashes
+        // are the current ones of the execution client, so that the forkchoice update doesn't
+        // move them.
+        let fork_choice_state = ForkchoiceState {
+            head_block_hash: ctx.head_block.header.hash,
+            safe_block_hash: ctx.safe_block_hash,
+            finalized_block_hash: ctx.finalized_block_hash,
+        };
+        let attributes = PayloadAttributes {
+            timestamp: ctx.block_timestamp,
+            prev_randao: ctx.prev_randao,
+            suggested_fee_recipient: ctx.fee_recipient,
+            withdrawals: Some(ctx.withdrawals),
+            parent_beacon_block_root: Some(ctx.parent_beacon_block_root),
+            ..Default::default()
+        };
+
+        let updated =
+            self.engine_client.fork_choice_updated_v3(fork_choice_state, Some(attributes)).await?;
+
+        let Some(payload_id) = updated.payload_id else {
+            return Err(BuilderError::MissingPayloadId(updated.payload_status.status));
+        };
+        debug!(?payload_id, "Started payload build on the execution client");
+
+        // 3. poll the payload until it contains all the constrained transactions, in order
+        let constrained =
+            transactions.iter().map(|tx| keccak256(tx.encoded_2718())).collect::<Vec<_>>();
+        let deadline = Instant::now() + PAYLOAD_BUILD_TIMEOUT;
+
+        loop {
+            let res = self
+                .get_payload(payload_id, ctx.fork, ctx.parent_beacon_block_root)
+                .await
+                .and_then(|payload| {
+                    let included = payload
+                        .block
+                        .body()
+                        .transactions
+                        .iter()
+                        .map(|tx| keccak256(tx.encoded_2718()))
+                        .collect::<Vec<_>>();
+                    check_constraints_order(&included, &constrained).map(|_| payload)
+                });
+
+            match res {
+                Ok(payload) => return Ok(payload),
+                Err(err) if Instant::now() + PAYLOAD_POLL_INTERVAL >= deadline => return Err(err),
+                Err(err) => {
+                    debug!(?err, "Payload not ready yet, polling again");
+                    tokio::time::sleep(PAYLOAD_POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    /// Fetch the payload being built with the given ID, with the version of the engine API
+    /// matching the fork of the target slot.
+    async fn get_payload(
+        &self,
+        payload_id: PayloadId,
+        fork: Fork,
+        parent_beacon_block_root: B256,
+    ) -> Result<FallbackPayload, BuilderError> {
+        // The value of the payload is computed by the execution client from the gas used by
+        // its transactions, as returned in the `blockValue` field of the response.
+        let (payload, value, requests) = match fork {
+            Fork::Electra => {
+                let envelope = self.engine_client.get_payload_v4(payload_id).await?;
+                let inner = envelope.envelope_inner;
+                (inner.execution_payload, inner.block_value, Some(envelope.execution_requests))
+            }
+            _ => {
+                let envelope = self.engine_client.get_payload_v3(payload_id).await?;
+                (envelope.execution_payload, envelope.block_value, None)
+            }
+        };
+
+        let requests_hash = requests.as_ref().map(|requests| requests.requests_hash());
+        let block = to_reth_sealed_block(payload, parent_beacon_block_root, requests_hash)?;
+
+        let execution_requests = requests
+            .map(|requests| ExecutionRequests::from_eip7685(&requests))
+            .transpose()
+            .map_err(|e| BuilderError::InvalidEnginePayload(e.to_string()))?;
+
+        Ok(FallbackPayload { block, execution_requests, value })
+    }
+}
+
+/// Check that all the constrained transactions are included in the payload, in the same order
+/// as the constraints. Other transactions may be interleaved between them.
+fn check_constraints_order(included: &[B256], constrained: &[B256]) -> Result<(), BuilderError> {
+    let included_set = included.iter().collect::<HashSet<_>>();
+    let missing = constrained.iter().filter(|hash| !included_set.contains(hash)).count();
+    if missing > 0 {
+        return Err(BuilderError::MissingConstrainedTransactions(missing));
+    }
+
+    // The constrained transactions must be found one after the other in the payload
+    let mut remaining = included.iter();
+    for hash in constrained {
+        if !remaining.any(|included_hash| included_hash == hash) {
+            return Err(BuilderError::UnorderedConstrainedTransactions);
+        }
+    }
+
+    Ok(())
+}
+
+/// Context holding the payload attributes needed to start a payload build
+/// with the [EngineBuilder].
+#[derive(Debug)]
+pub struct EngineBuilderContext {
+    pub fork: Fork,
+    pub head_block: Block,
+    pub safe_block_hash: B256,
+    pub finalized_block_hash: B256,
+    pub block_timestamp: u64,
+    pub prev_randao: B256,
+    pub fee_recipient: Address,
+    pub withdrawals: Vec<Withdrawal>,
+    pub parent_beacon_block_root: B256,
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::B256;
+
+    use super::check_constraints_order;
+    use crate::builder::BuilderError;
+
+    #[test]
+    fn test_check_constraints_order() {
+        let [a, b, c, d] = [1, 2, 3, 4].map(B256::with_last_byte);
+
+        // Other transactions may be interleaved with the constrained ones
+        assert!(check_constraints_order(&[a, b, c, d], &[b, d]).is_ok());
+
+        assert!(matches!(
+            check_constraints_order(&[a, b, c], &[b, d]),
+            Err(BuilderError::MissingConstrainedTransactions(1))
+        ));
+        assert!(matches!(
+            check_constraints_order(&[a, d, c, b], &[b, d]),
+            Err(BuilderError::UnorderedConstrainedTransactions)
+        ));
+    }
+}
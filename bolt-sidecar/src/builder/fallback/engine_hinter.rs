@@ -2,6 +2,7 @@ use std::ops::Deref;
 
 use alloy::{
     consensus::{Header, EMPTY_OMMER_ROOT_HASH},
+    eips::eip7685::Requests,
     primitives::{Address, Bloom, Bytes, B256, B64, U256},
     rpc::types::{Block, Withdrawal, Withdrawals},
 };
@@ -92,8 +93,9 @@ impl EngineHinter {
         }
     }
 
-    /// Yield the next hint from the engine API by calling `engine_newPayloadV3`
-    /// and parsing the response to extract the hint.
+    /// Yield the next hint from the engine API by calling `engine_newPayloadV3`, or
+    /// `engine_newPayloadV4` if the context has execution requests (from the Electra fork
+    /// onwards), and parsing the response to extract the hint.
     ///
     /// Returns Ok([EngineApiHint::ValidPayload]) if the payload is valid.
     async fn next_hint(
@@ -101,24 +103,40 @@ impl EngineHinter {
         exec_payload: ExecutionPayloadV3,
         ctx: &EngineHinterContext,
     ) -> Result<EngineApiHint, BuilderError> {
-        let payload_status = self
-            .engine_client
-            .new_payload_v3(
-                exec_payload,
-                ctx.blob_versioned_hashes.clone(),
-                ctx.parent_beacon_block_root,
-            )
-            .await?;
+        let versioned_hashes = ctx.blob_versioned_hashes.clone();
+        let payload_status = match ctx.execution_requests {
+            Some(ref requests) => {
+                self.engine_client
+                    .new_payload_v4(
+                        exec_payload,
+                        versioned_hashes,
+                        ctx.parent_beacon_block_root,
+                        requests.clone(),
+                    )
+                    .await?
+            }
+            None => {
+                self.engine_client
+                    .new_payload_v3(exec_payload, versioned_hashes, ctx.parent_beacon_block_root)
+                    .await?
+            }
+        };
 
         let validation_error = match payload_status.status {
             PayloadStatusEnum::Valid => return Ok(EngineApiHint::ValidPayload),
             PayloadStatusEnum::Invalid { validation_error } => validation_error,
             PayloadStatusEnum::Syncing | PayloadStatusEnum::Accepted => {
                 error!(status = ?payload_status.status, "Unexpected payload status from engine API");
-                return Err(BuilderError::UnexpectedPayloadStatus(payload_status.status))
+                return Err(BuilderError::UnexpectedPayloadStatus(payload_status.status));
             }
         };
 
+        // The execution requests of the block can't be hinted: fail closed if the execution
+        // client rejected the empty ones we assumed, whatever the client-specific message.
+        if ctx.execution_requests.is_some() && validation_error.to_lowercase().contains("request") {
+            return Err(BuilderError::UnsupportedExecutionRequests(validation_error));
+        }
+
         // Parse the hint from the engine API response, based on the EL client code
         let Some(hint) = parse_hint_from_engine_response(ctx.el_client_code, &validation_error)?
         else {
@@ -211,6 +229,8 @@ pub struct EngineHinterContext {
     pub head_block: Block,
     pub hints: Hints,
     pub el_client_code: ClientCode,
+    /// The EIP-7685 execution requests of the block, from the Electra fork onwards.
+    pub execution_requests: Option<Requests>,
 }
 
 impl EngineHinterContext {
@@ -253,8 +273,7 @@ impl EngineHinterContext {
             excess_blob_gas: Some(self.excess_blob_gas),
             parent_beacon_block_root: Some(self.parent_beacon_block_root),
             extra_data: self.extra_data.clone(),
-            // TODO: handle the Pectra-related fields
-            requests_hash: None,
+            requests_hash: self.execution_requests.as_ref().map(Requests::requests_hash),
         }
     }
 }
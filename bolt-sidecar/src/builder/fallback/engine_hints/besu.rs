@@ -0,0 +1,122 @@
+use alloy::primitives::{Bloom, B256};
+use hex::FromHex;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::builder::{fallback::engine_hinter::EngineApiHint, BuilderError};
+
+lazy_static! {
+    /// Capture the "actual" value from the error message
+    static ref REGEX: Regex = Regex::new(r"actual=([0-9a-zA-Z]+)").expect("valid regex");
+
+    /// Capture the computed block hash from the error message
+    static ref BLOCK_HASH_REGEX: Regex =
+        Regex::new(r"Computed block hash ([0-9a-zA-Z]+)").expect("valid regex");
+}
+
+/// Parse a hinted value from the engine response.
+/// An example error message from the engine API looks like this:
+///
+/// ```json
+/// {
+///     "jsonrpc": "2.0",
+///     "id": 1,
+///     "error": {
+///         "code":-32000,
+///          "message": "Invalid block 1 (0x...): gas used mismatch (expected=0, actual=21000)"
+///     }
+/// }
+/// ```
+///
+/// Besu Reference:
+/// - [MainnetBlockBodyValidator](<https://github.com/hyperledger/besu/blob/24.12.2/ethereum/core/src/main/java/org/hyperledger/besu/ethereum/mainnet/MainnetBlockBodyValidator.java>)
+/// - [AbstractEngineNewPayload](<https://github.com/hyperledger/besu/blob/24.12.2/ethereum/api/src/main/java/org/hyperledger/besu/ethereum/api/jsonrpc/internal/methods/engine/AbstractEngineNewPayload.java>)
+pub fn parse_besu_engine_error_hint(error: &str) -> Result<Option<EngineApiHint>, BuilderError> {
+    // The block hash mismatch is reported with a different format than the other hints
+    if let Some(matched) = BLOCK_HASH_REGEX.captures(error).and_then(|cap| cap.get(1)) {
+        return Ok(Some(EngineApiHint::BlockHash(B256::from_hex(matched.as_str())?)));
+    }
+
+    let raw_hint_value = match REGEX.captures(error).and_then(|cap| cap.get(1)) {
+        Some(matched) => matched.as_str().to_string(),
+        None => return Ok(None),
+    };
+
+    // Match the hint value to the corresponding hint type based on other parts of the error message
+    if error.contains("gas used mismatch") {
+        return Ok(Some(EngineApiHint::GasUsed(raw_hint_value.parse()?)));
+    } else if error.contains("world state root mismatch") {
+        return Ok(Some(EngineApiHint::StateRoot(B256::from_hex(raw_hint_value)?)));
+    } else if error.contains("receipts root mismatch") {
+        return Ok(Some(EngineApiHint::ReceiptsRoot(B256::from_hex(raw_hint_value)?)));
+    } else if error.contains("logs bloom filter mismatch") {
+        return Ok(Some(EngineApiHint::LogsBloom(Bloom::from_hex(&raw_hint_value)?)));
+    };
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::b256;
+
+    use super::*;
+
+    // The fixtures follow the format strings of the messages producing them, where blocks are
+    // displayed with `BlockHeader::toLogString` as "{number} ({hash}").
+
+    /// `AbstractEngineNewPayload::syncResponse`:
+    /// "Computed block hash %s does not match block hash parameter %s"
+    /// <https://github.com/hyperledger/besu/blob/24.12.2/ethereum/api/src/main/java/org/hyperledger/besu/ethereum/api/jsonrpc/internal/methods/engine/AbstractEngineNewPayload.java>
+    const BLOCK_HASH: &str = "Computed block hash 0x8f38ee6f9ef6ab8b4b8dcd50f3cd3bf2f0bb1e40c49ff5b3e8c05de31e1d4f0a does not match block hash parameter 0x0000000000000000000000000000000000000000000000000000000000000000";
+    /// `MainnetBlockBodyValidator::validateGasUsed`:
+    /// "Invalid block {}: gas used mismatch (expected={}, actual={})"
+    /// <https://github.com/hyperledger/besu/blob/24.12.2/ethereum/core/src/main/java/org/hyperledger/besu/ethereum/mainnet/MainnetBlockBodyValidator.java>
+    const GAS_USED: &str = "Invalid block 20 (0x8f38ee6f9ef6ab8b4b8dcd50f3cd3bf2f0bb1e40c49ff5b3e8c05de31e1d4f0a): gas used mismatch (expected=0, actual=42000)";
+    /// `MainnetBlockBodyValidator::validateStateRoot`:
+    /// "Invalid block {}: world state root mismatch (expected={}, actual={})"
+    /// <https://github.com/hyperledger/besu/blob/24.12.2/ethereum/core/src/main/java/org/hyperledger/besu/ethereum/mainnet/MainnetBlockBodyValidator.java>
+    const STATE_ROOT: &str = "Invalid block 20 (0x8f38ee6f9ef6ab8b4b8dcd50f3cd3bf2f0bb1e40c49ff5b3e8c05de31e1d4f0a): world state root mismatch (expected=0x0000000000000000000000000000000000000000000000000000000000000000, actual=0x6f4c2a4bbd5e7e1c6b6d0f4d1e9c6ab5f1e5a1e0c2f5d9e8b7a6c5d4e3f2a1b0)";
+    /// `MainnetBlockBodyValidator::validateReceiptsRoot`:
+    /// "Invalid block {}: receipts root mismatch (expected={}, actual={})"
+    /// <https://github.com/hyperledger/besu/blob/24.12.2/ethereum/core/src/main/java/org/hyperledger/besu/ethereum/mainnet/MainnetBlockBodyValidator.java>
+    const RECEIPTS_ROOT: &str = "Invalid block 20 (0x8f38ee6f9ef6ab8b4b8dcd50f3cd3bf2f0bb1e40c49ff5b3e8c05de31e1d4f0a): receipts root mismatch (expected=0x0000000000000000000000000000000000000000000000000000000000000000, actual=0xeaa8c40899a61ae59615cf9985f5e2194f8fd2b57d273be63bde6733e89b12ab)";
+
+    #[test]
+    fn test_parse_besu_hints() {
+        let hint = parse_besu_engine_error_hint(BLOCK_HASH).unwrap();
+        assert!(matches!(
+            hint,
+            Some(EngineApiHint::BlockHash(hash)) if hash == b256!("8f38ee6f9ef6ab8b4b8dcd50f3cd3bf2f0bb1e40c49ff5b3e8c05de31e1d4f0a")
+        ));
+
+        let hint = parse_besu_engine_error_hint(GAS_USED).unwrap();
+        assert!(matches!(hint, Some(EngineApiHint::GasUsed(42000))));
+
+        let hint = parse_besu_engine_error_hint(STATE_ROOT).unwrap();
+        assert!(matches!(
+            hint,
+            Some(EngineApiHint::StateRoot(root)) if root == b256!("6f4c2a4bbd5e7e1c6b6d0f4d1e9c6ab5f1e5a1e0c2f5d9e8b7a6c5d4e3f2a1b0")
+        ));
+
+        let hint = parse_besu_engine_error_hint(RECEIPTS_ROOT).unwrap();
+        assert!(matches!(
+            hint,
+            Some(EngineApiHint::ReceiptsRoot(root)) if root == b256!("eaa8c40899a61ae59615cf9985f5e2194f8fd2b57d273be63bde6733e89b12ab")
+        ));
+
+        // `MainnetBlockBodyValidator::validateLogsBloom`:
+        // "Invalid block {}: logs bloom filter mismatch (expected={}, actual={})"
+        let bloom = Bloom::repeat_byte(0x11);
+        let logs_bloom = format!(
+            "Invalid block 20 (0x00): logs bloom filter mismatch (expected={}, actual={bloom})",
+            Bloom::ZERO
+        );
+        let hint = parse_besu_engine_error_hint(&logs_bloom).unwrap();
+        assert!(matches!(hint, Some(EngineApiHint::LogsBloom(b)) if b == bloom));
+
+        // Errors without a hint are ignored
+        let hint = parse_besu_engine_error_hint("Failed to process block").unwrap();
+        assert!(hint.is_none());
+    }
+}
@@ -11,10 +11,22 @@ mod geth;
 /// Parse engine hints from Nethermind execution clients.
 mod nethermind;
 
+/// Parse engine hints from Reth execution clients.
+mod reth;
+
+/// Parse engine hints from Besu execution clients.
+mod besu;
+
 /// Tries to parse engine hints from the given execution client and error response.
 ///
 /// * Returns Ok(None) if no hint could be parsed.
 /// * Returns an error if the execution client is not supported.
+///
+/// Erigon is not supported: its block hash validation error is only "invalid block hash",
+/// without the actual hash of the payload, so a hinted block can never be sealed.
+///
+/// Erigon Reference:
+/// - [EngineServer::newPayload](<https://github.com/erigontech/erigon/blob/v2.61.0/turbo/engineapi/engine_server.go>)
 pub fn parse_hint_from_engine_response(
     client: ClientCode,
     error: &str,
@@ -22,6 +34,8 @@ pub fn parse_hint_from_engine_response(
     match client {
         ClientCode::GE => geth::parse_geth_engine_error_hint(error),
         ClientCode::NM => nethermind::parse_nethermind_engine_error_hint(error),
+        ClientCode::RH => reth::parse_reth_engine_error_hint(error),
+        ClientCode::BU => besu::parse_besu_engine_error_hint(error),
 
         _ => {
             error!("Unsupported fallback execution client: {}", client.client_name());
@@ -29,3 +43,19 @@ pub fn parse_hint_from_engine_response(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy_rpc_types_engine::ClientCode;
+
+    use super::parse_hint_from_engine_response;
+    use crate::builder::BuilderError;
+
+    #[test]
+    fn test_erigon_hints_unsupported() {
+        // The block hash validation error of `EngineServer::newPayload`: the actual block hash
+        // of the payload is only logged.
+        let res = parse_hint_from_engine_response(ClientCode::EG, "invalid block hash");
+        assert!(matches!(res, Err(BuilderError::UnsupportedEngineClient(ClientCode::EG))));
+    }
+}
@@ -0,0 +1,114 @@
+use alloy::primitives::{Bloom, B256};
+use hex::FromHex;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::builder::{fallback::engine_hinter::EngineApiHint, BuilderError};
+
+lazy_static! {
+    /// Capture the "got" value from the error message
+    static ref REGEX: Regex = Regex::new(r"got ([0-9a-zA-Z]+)").expect("valid regex");
+}
+
+/// Parse a hinted value from the engine response.
+/// An example error message from the engine API looks like this:
+///
+/// ```json
+/// {
+///     "jsonrpc": "2.0",
+///     "id": 1,
+///     "error": {
+///         "code":-32000,
+///          "message": "mismatched block state root: got 0x..., expected 0x..."
+///     }
+/// }
+/// ```
+///
+/// Reth Reference:
+/// - [ConsensusError](<https://github.com/paradigmxyz/reth/blob/v1.1.5/crates/consensus/consensus/src/lib.rs>)
+/// - [PayloadError](<https://github.com/alloy-rs/alloy/blob/v0.9.2/crates/rpc-types-engine/src/payload.rs>)
+pub fn parse_reth_engine_error_hint(error: &str) -> Result<Option<EngineApiHint>, BuilderError> {
+    let raw_hint_value = match REGEX.captures(error).and_then(|cap| cap.get(1)) {
+        Some(matched) => matched.as_str().to_string(),
+        None => return Ok(None),
+    };
+
+    // Match the hint value to the corresponding hint type based on other parts of the error message
+    if error.contains("block hash mismatch") {
+        return Ok(Some(EngineApiHint::BlockHash(B256::from_hex(raw_hint_value)?)));
+    } else if error.contains("block gas used mismatch") {
+        return Ok(Some(EngineApiHint::GasUsed(raw_hint_value.parse()?)));
+    } else if error.contains("mismatched block state root") {
+        return Ok(Some(EngineApiHint::StateRoot(B256::from_hex(raw_hint_value)?)));
+    } else if error.contains("mismatched block receipt root") {
+        return Ok(Some(EngineApiHint::ReceiptsRoot(B256::from_hex(raw_hint_value)?)));
+    } else if error.contains("mismatched block logs bloom") {
+        return Ok(Some(EngineApiHint::LogsBloom(Bloom::from_hex(&raw_hint_value)?)));
+    };
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::b256;
+
+    use super::*;
+
+    // The fixtures follow the `#[error]` formats of the variants producing them, with
+    // `GotExpected` displayed as "got {got}, expected {expected}":
+    // <https://github.com/paradigmxyz/reth/blob/v1.1.5/crates/primitives-traits/src/error.rs>
+
+    /// `PayloadError::BlockHash`: "block hash mismatch: want {consensus}, got {execution}"
+    /// <https://github.com/alloy-rs/alloy/blob/v0.9.2/crates/rpc-types-engine/src/payload.rs>
+    const BLOCK_HASH: &str = "block hash mismatch: want 0x0000000000000000000000000000000000000000000000000000000000000000, got 0x8f38ee6f9ef6ab8b4b8dcd50f3cd3bf2f0bb1e40c49ff5b3e8c05de31e1d4f0a";
+    /// `ConsensusError::BlockGasUsed`:
+    /// "block gas used mismatch: {gas}; gas spent by each transaction: {gas_spent_by_tx:?}"
+    /// <https://github.com/paradigmxyz/reth/blob/v1.1.5/crates/consensus/consensus/src/lib.rs>
+    const GAS_USED: &str = "block gas used mismatch: got 42000, expected 0; gas spent by each transaction: [(0, 21000), (1, 42000)]";
+    /// `ConsensusError::BodyStateRootDiff`: "mismatched block state root: {0}"
+    /// <https://github.com/paradigmxyz/reth/blob/v1.1.5/crates/consensus/consensus/src/lib.rs>
+    const STATE_ROOT: &str = "mismatched block state root: got 0x6f4c2a4bbd5e7e1c6b6d0f4d1e9c6ab5f1e5a1e0c2f5d9e8b7a6c5d4e3f2a1b0, expected 0x0000000000000000000000000000000000000000000000000000000000000000";
+    /// `ConsensusError::BodyReceiptRootDiff`: "mismatched block receipt root: {0}"
+    /// <https://github.com/paradigmxyz/reth/blob/v1.1.5/crates/consensus/consensus/src/lib.rs>
+    const RECEIPTS_ROOT: &str = "mismatched block receipt root: got 0xeaa8c40899a61ae59615cf9985f5e2194f8fd2b57d273be63bde6733e89b12ab, expected 0x0000000000000000000000000000000000000000000000000000000000000000";
+
+    #[test]
+    fn test_parse_reth_hints() {
+        let hint = parse_reth_engine_error_hint(BLOCK_HASH).unwrap();
+        assert!(matches!(
+            hint,
+            Some(EngineApiHint::BlockHash(hash)) if hash == b256!("8f38ee6f9ef6ab8b4b8dcd50f3cd3bf2f0bb1e40c49ff5b3e8c05de31e1d4f0a")
+        ));
+
+        let hint = parse_reth_engine_error_hint(GAS_USED).unwrap();
+        assert!(matches!(hint, Some(EngineApiHint::GasUsed(42000))));
+
+        let hint = parse_reth_engine_error_hint(STATE_ROOT).unwrap();
+        assert!(matches!(
+            hint,
+            Some(EngineApiHint::StateRoot(root)) if root == b256!("6f4c2a4bbd5e7e1c6b6d0f4d1e9c6ab5f1e5a1e0c2f5d9e8b7a6c5d4e3f2a1b0")
+        ));
+
+        let hint = parse_reth_engine_error_hint(RECEIPTS_ROOT).unwrap();
+        assert!(matches!(
+            hint,
+            Some(EngineApiHint::ReceiptsRoot(root)) if root == b256!("eaa8c40899a61ae59615cf9985f5e2194f8fd2b57d273be63bde6733e89b12ab")
+        ));
+
+        // `ConsensusError::BodyBloomLogDiff`: "mismatched block logs bloom: {0}"
+        let bloom = Bloom::repeat_byte(0x11);
+        let logs_bloom =
+            format!("mismatched block logs bloom: got {bloom}, expected {}", Bloom::ZERO);
+        let hint = parse_reth_engine_error_hint(&logs_bloom).unwrap();
+        assert!(matches!(hint, Some(EngineApiHint::LogsBloom(b)) if b == bloom));
+
+        // Errors without a hint are ignored
+        let hint = parse_reth_engine_error_hint("transaction nonce is not consistent").unwrap();
+        assert!(hint.is_none());
+
+        // Errors that don't match any known hint are ignored
+        let unknown = format!("mismatched block requests hash: got {}", B256::ZERO);
+        assert!(parse_reth_engine_error_hint(&unknown).unwrap().is_none());
+    }
+}
@@ -1,3 +1,10 @@
+use alloy::primitives::U256;
+use clap::ValueEnum;
+use reth_primitives::SealedBlock;
+use serde::Deserialize;
+
+use crate::primitives::ExecutionRequests;
+
 /// Fallback block builder for when the PBS stack doesn't yield a valid block.
 pub mod payload_builder;
 pub use payload_builder::FallbackPayloadBuilder;
@@ -9,6 +16,46 @@ pub use engine_hinter::EngineHinter;
 /// Utilities for parsing engine hints from different execution clients types.
 mod engine_hints;
 
+/// Payload building through the execution client's own payload builder.
+mod engine_builder;
+pub use engine_builder::EngineBuilder;
+
+/// Selection of txpool transactions to fill the fallback block with.
+mod txpool;
+
+/// A payload built by the [FallbackPayloadBuilder].
+#[derive(Debug)]
+pub struct FallbackPayload {
+    /// The sealed block of the payload.
+    pub block: SealedBlock,
+    /// The execution requests of the block, from the Electra fork onwards.
+    pub execution_requests: Option<ExecutionRequests>,
+    /// The value of the payload for its fee recipient, i.e. the sum of the priority fees
+    /// paid by its transactions.
+    pub value: U256,
+}
+
+/// The strategy used to build fallback payloads. Only the selected strategy is used: the engine
+/// strategy leaks the constrained transactions to the public mempool, so it is never used as
+/// a fallback of the hints strategy and must be selected explicitly.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[clap(rename_all = "kebab_case")]
+#[serde(rename_all = "kebab-case")]
+pub enum FallbackBuildStrategy {
+    /// Assemble the payload locally, gathering the missing header values from the
+    /// `engine_newPayloadV3` (or `engine_newPayloadV4` from Electra) validation errors
+    /// of the execution client. Not supported with Erigon, whose validation errors don't
+    /// contain the actual block hash of the payload.
+    #[default]
+    Hints,
+    /// Let the execution client build the payload with `engine_forkchoiceUpdatedV3` and
+    /// `engine_getPayloadV3` (or `engine_getPayloadV4` from Electra), after injecting the
+    /// constrained transactions in its txpool. The constrained transactions are gossiped to
+    /// the public mempool as a result, so payloads are only built at the commitment deadline
+    /// with this strategy, and never pre-built.
+    Engine,
+}
+
 /// Extra-data payload field used for locally built blocks, decoded in UTF-8.
 ///
 /// Corresponds to the string "Self-built with Bolt". It can be max 32 bytes
@@ -1,19 +1,38 @@
+use std::collections::HashMap;
+
 use alloy::{
-    consensus::{proofs, Transaction},
-    eips::{calc_excess_blob_gas, calc_next_block_base_fee, eip1559::BaseFeeParams},
-    primitives::{Address, Bytes},
+    consensus::{proofs, BlobTransactionSidecar, Transaction},
+    eips::{
+        calc_next_block_base_fee,
+        eip1559::BaseFeeParams,
+        eip2718::Encodable2718,
+        eip4844::{DATA_GAS_PER_BLOB, TARGET_BLOBS_PER_BLOCK},
+        eip7685::Requests,
+        eip7691::TARGET_BLOBS_PER_BLOCK_ELECTRA,
+        BlockNumberOrTag,
+    },
+    primitives::{keccak256, Address, Bytes, TxHash, B256, U256},
+    rpc::types::{Block, TransactionRequest, Withdrawal},
 };
+use alloy_provider::ext::EngineApi;
+use ethereum_consensus::Fork;
+use futures::future::join_all;
 use reth_primitives::{SealedBlock, TransactionSigned};
-use tracing::debug;
+use reth_primitives_traits::SignedTransaction;
+use tracing::{debug, warn};
 
 use super::{
+    engine_builder::{EngineBuilder, EngineBuilderContext},
     engine_hinter::{EngineHinter, EngineHinterContext},
-    DEFAULT_EXTRA_DATA,
+    txpool::{from_txpool_transaction, select_fill_transactions, FillLimits},
+    FallbackBuildStrategy, FallbackPayload, DEFAULT_EXTRA_DATA,
 };
 use crate::{
-    builder::BuilderError,
-    client::{BeaconClient, ExecutionClient},
-    config::Opts,
+    builder::{priority_fees, BlockTemplate, BuilderError},
+    client::{BeaconClient, EngineClient},
+    config::{ChainConfig, Opts},
+    primitives::ExecutionRequests,
+    state::FailoverStateClient,
 };
 
 /// The fallback payload builder is responsible for assembling a valid
@@ -25,6 +44,9 @@ use crate::{
 /// necessary values to seal the block. This is a temporary solution
 /// until the engine API is able to provide a full sealed block.
 ///
+/// Alternatively, the payload can be built by the execution client itself
+/// with the [EngineBuilder], depending on the configured [FallbackBuildStrategy].
+///
 /// Find more information about this process & its reasoning here:
 /// <https://github.com/chainbound/bolt/discussions/59>
 #[derive(Debug)]
@@ -32,26 +54,36 @@ pub struct FallbackPayloadBuilder {
     extra_data: Bytes,
     fee_recipient: Address,
     beacon_api: BeaconClient,
-    execution_api: ExecutionClient,
+    execution_api: FailoverStateClient,
+    engine_api: EngineClient,
     engine_hinter: EngineHinter,
-    slot_time: u64,
+    engine_builder: EngineBuilder,
+    strategy: FallbackBuildStrategy,
+    chain: ChainConfig,
     genesis_time: u64,
 }
 
 impl FallbackPayloadBuilder {
     /// Create a new fallback payload builder
-    pub fn new(opts: &Opts, genesis_time: u64) -> Self {
+    pub fn new(
+        opts: &Opts,
+        beacon_api: BeaconClient,
+        execution_api: FailoverStateClient,
+        genesis_time: u64,
+    ) -> Self {
         let engine_hinter = EngineHinter::new(opts.engine_jwt_hex.0, opts.engine_api_url.clone());
-
-        let beacon_api = BeaconClient::new(opts.beacon_api_url.clone());
-        let execution_api = ExecutionClient::new(opts.execution_api_url.clone());
+        let engine_api = EngineClient::new_http(opts.engine_api_url.clone(), opts.engine_jwt_hex.0);
+        let engine_builder = EngineBuilder::new(engine_api.clone());
 
         Self {
             extra_data: DEFAULT_EXTRA_DATA.into(),
             fee_recipient: opts.fee_recipient,
-            slot_time: opts.chain.slot_time(),
+            chain: opts.chain,
+            strategy: opts.fallback_build_strategy,
             engine_hinter,
+            engine_builder,
             execution_api,
+            engine_api,
             genesis_time,
             beacon_api,
         }
@@ -59,25 +91,31 @@ impl FallbackPayloadBuilder {
 
     /// Build a minimal payload to be used as a fallback in case PBS relays fail
     /// to provide a valid payload that fulfills the commitments made by Bolt.
+    ///
+    /// The payload is built with the configured [FallbackBuildStrategy] only: if it fails,
+    /// the error is returned without trying the other strategy.
     pub async fn build_fallback_payload(
         &self,
         target_slot: u64,
         transactions: &[TransactionSigned],
-    ) -> Result<SealedBlock, BuilderError> {
+    ) -> Result<FallbackPayload, BuilderError> {
+        let head = self.fetch_head_context(target_slot).await?;
+        self.build_with_strategy(self.strategy, head, transactions).await
+    }
+
+    /// Returns the configured [FallbackBuildStrategy].
+    pub const fn strategy(&self) -> FallbackBuildStrategy {
+        self.strategy
+    }
+
+    /// Fetch the values of the head of the chain needed by both building strategies.
+    async fn fetch_head_context(&self, target_slot: u64) -> Result<HeadContext, BuilderError> {
         // Fetch the latest block to get the necessary parent values for the new block.
         // For the timestamp, we must use the one expected by the beacon chain instead, to
         // prevent edge cases where the proposer before us has missed their slot and therefore
         // the timestamp of the previous block is too far in the past.
-        let head_block_fut = self.execution_api.get_block(None, true);
-
-        // Fetch the execution client info from the engine API in order to know what hint
-        // types the engine hinter can parse from the engine API responses.
-        let el_client_info_fut = self.engine_hinter.engine_client_version();
-
-        let (head_block, el_client_info) = tokio::try_join!(head_block_fut, el_client_info_fut)?;
-
-        let el_client_code = el_client_info[0].code;
-        debug!(client = %el_client_code.client_name(), "Fetched execution client info");
+        let head_block =
+            self.execution_api.with_execution_client(|client| client.get_block(None, true)).await?;
 
         // Fetch required head info from the beacon client
         let parent_beacon_block_root_fut = self.beacon_api.get_parent_beacon_block_root();
@@ -90,7 +128,80 @@ impl FallbackPayloadBuilder {
         // The next block timestamp must be calculated manually rather than relying on the
         // previous execution block, to cover the edge case where any previous slots have
         // been missed by the proposers immediately before us.
-        let block_timestamp = self.genesis_time + (target_slot * self.slot_time);
+        let block_timestamp = self.genesis_time + (target_slot * self.chain.slot_time());
+
+        Ok(HeadContext {
+            fork: self.chain.fork_at_slot(target_slot)?,
+            head_block,
+            parent_beacon_block_root,
+            withdrawals,
+            prev_randao,
+            block_timestamp,
+        })
+    }
+
+    /// Build the fallback payload with the given strategy.
+    async fn build_with_strategy(
+        &self,
+        strategy: FallbackBuildStrategy,
+        head: HeadContext,
+        transactions: &[TransactionSigned],
+    ) -> Result<FallbackPayload, BuilderError> {
+        match strategy {
+            FallbackBuildStrategy::Hints => self.build_with_hints(head, transactions).await,
+            FallbackBuildStrategy::Engine => {
+                let (safe_block_hash, finalized_block_hash) = tokio::try_join!(
+                    self.execution_api.with_execution_client(|client| {
+                        client.get_block_hash(BlockNumberOrTag::Safe)
+                    }),
+                    self.execution_api.with_execution_client(|client| {
+                        client.get_block_hash(BlockNumberOrTag::Finalized)
+                    })
+                )?;
+
+                let ctx = EngineBuilderContext {
+                    fork: head.fork,
+                    head_block: head.head_block,
+                    safe_block_hash,
+                    finalized_block_hash,
+                    block_timestamp: head.block_timestamp,
+                    prev_randao: head.prev_randao,
+                    fee_recipient: self.fee_recipient,
+                    withdrawals: head.withdrawals,
+                    parent_beacon_block_root: head.parent_beacon_block_root,
+                };
+
+                self.engine_builder.build_payload(ctx, transactions).await
+            }
+        }
+    }
+
+    /// Build the fallback payload by gathering hints from the engine API.
+    ///
+    /// From the Electra fork onwards, the block is assumed to have no execution requests,
+    /// since they are produced by its execution and can't be hinted. If the execution client
+    /// expects any, the build fails with [BuilderError::UnsupportedExecutionRequests] rather
+    /// than returning a payload with the wrong requests.
+    async fn build_with_hints(
+        &self,
+        head: HeadContext,
+        transactions: &[TransactionSigned],
+    ) -> Result<FallbackPayload, BuilderError> {
+        let HeadContext {
+            fork,
+            head_block,
+            parent_beacon_block_root,
+            withdrawals,
+            prev_randao,
+            block_timestamp,
+        } = head;
+
+        // Fetch the execution client info from the engine API in order to know what hint
+        // types the engine hinter can parse from the engine API responses.
+        let el_client_info = self.engine_hinter.engine_client_version().await?;
+
+        let el_client_code = el_client_info[0].code;
+        debug!(client = %el_client_code.client_name(), "Fetched execution client info");
 
         let blob_versioned_hashes = transactions
             .iter()
@@ -107,10 +218,13 @@ impl FallbackPayloadBuilder {
         );
 
         let excess_blob_gas = calc_excess_blob_gas(
+            fork,
             head_block.header.excess_blob_gas.unwrap_or_default(),
             head_block.header.blob_gas_used.unwrap_or_default(),
         );
 
+        let execution_requests = matches!(fork, Fork::Electra).then(ExecutionRequests::default);
+
         let blob_gas_used =
             transactions.iter().fold(0, |acc, tx| acc + tx.blob_gas_used().unwrap_or_default());
 
@@ -130,14 +244,246 @@ impl FallbackPayloadBuilder {
             withdrawals,
             head_block,
             el_client_code,
+            execution_requests: execution_requests.as_ref().map(|_| Requests::default()),
             // start the context with empty hints
             hints: Default::default(),
         };
 
         // Use the engine API to fetch the missing value for the payload, until we have
         // all the necessary data to consider it valid and seal the block.
-        self.engine_hinter.fetch_payload_from_hints(ctx).await
+        let block = self.engine_hinter.fetch_payload_from_hints(ctx).await?;
+        let value = self.simulate_block_value(&block).await;
+
+        Ok(FallbackPayload { block, execution_requests, value })
+    }
+
+    /// Returns the value of a sealed block for its fee recipient, i.e. the sum of the priority
+    /// fees paid by its transactions, from the gas used by each of them.
+    ///
+    /// The hinted payload is not part of the chain, so its receipts can't be fetched: the gas
+    /// used by its transactions is obtained by simulating them with `eth_simulateV1` instead.
+    /// If the simulation fails, the value is zero so that valid relay bids are always preferred.
+    async fn simulate_block_value(&self, block: &SealedBlock) -> U256 {
+        let transactions = &block.body().transactions;
+
+        let mut calls = Vec::with_capacity(transactions.len());
+        for tx in transactions {
+            let Some(sender) = tx.recover_signer() else {
+                warn!(hash = %tx.hash(), "Failed to recover sender of payload transaction");
+                return U256::ZERO;
+            };
+            calls.push(TransactionRequest::from_transaction_with_sender(tx.clone(), sender));
+        }
+
+        let simulation = self
+            .execution_api
+            .with_execution_client(|client| client.simulate_gas_used(block.header(), calls.clone()))
+            .await;
+
+        match simulation {
+            Ok(gas_used) if gas_used.len() == transactions.len() => {
+                let base_fee = block.header().base_fee_per_gas.unwrap_or_default();
+                priority_fees(transactions, base_fee, &gas_used)
+            }
+            Ok(gas_used) => {
+                warn!(
+                    expected = transactions.len(),
+                    got = gas_used.len(),
+                    "Unexpected number of simulated transactions"
+                );
+                U256::ZERO
+            }
+            Err(err) => {
+                warn!(?err, "Failed to simulate payload transactions to compute its value");
+                U256::ZERO
+            }
+        }
     }
+
+    /// Fetch the pending transactions from the execution client's txpool that can be appended
+    /// after the constrained transactions of the template, to fill the rest of the block and
+    /// of its blob space.
+    ///
+    /// The sidecars of blob transactions are not returned by the txpool, so their blobs are
+    /// fetched from the blob pool of the execution client with `engine_getBlobsV1`. Blob
+    /// transactions whose blobs are not available are never selected.
+    pub async fn fetch_fill_transactions(
+        &self,
+        slot: u64,
+        template: &BlockTemplate,
+    ) -> Result<FillTransactions, BuilderError> {
+        let (head_block, pending, blob_basefee) = tokio::try_join!(
+            self.execution_api.with_execution_client(|client| client.get_block(None, false)),
+            self.execution_api.with_execution_client(|client| client.get_txpool_pending()),
+            self.execution_api.with_execution_client(|client| client.get_blob_basefee(None))
+        )?;
+
+        let base_fee = calc_next_block_base_fee(
+            head_block.header.gas_used,
+            head_block.header.gas_limit,
+            head_block.header.base_fee_per_gas.unwrap_or_default(),
+            BaseFeeParams::ethereum(),
+        );
+
+        let max_blobs = self.chain.max_blobs_per_block(slot)?;
+        let committed_gas = template.committed_gas();
+        let limits = FillLimits {
+            base_fee,
+            blob_basefee,
+            gas_left: head_block.header.gas_limit.saturating_sub(committed_gas),
+            blobs_left: max_blobs.saturating_sub(template.blob_count()),
+        };
+
+        // The account states of senders with constrained transactions are needed to check
+        // that their pending transactions don't conflict with the constrained ones.
+        let constrained_senders =
+            pending.keys().filter(|sender| template.get_diff(sender).is_some()).collect::<Vec<_>>();
+        let states = join_all(constrained_senders.iter().map(|sender| {
+            self.execution_api
+                .with_execution_client(move |client| client.get_account_state(sender, None))
+        }))
+        .await;
+
+        let mut account_states = HashMap::with_capacity(constrained_senders.len());
+        for (sender, state) in constrained_senders.into_iter().zip(states) {
+            match state {
+                Ok(state) => {
+                    account_states.insert(*sender, state);
+                }
+                Err(err) => warn!(%sender, ?err, "Failed to fetch account state of sender"),
+            }
+        }
+
+        let mut pending = pending
+            .into_iter()
+            .map(|(sender, txs)| {
+                (sender, txs.into_values().filter_map(from_txpool_transaction).collect::<Vec<_>>())
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut sidecars = if limits.blobs_left > 0 {
+            self.fetch_blob_sidecars(pending.values().flatten()).await
+        } else {
+            HashMap::new()
+        };
+
+        // Blob transactions can only be included with their sidecars. The following
+        // transactions of their senders are dropped as well, as they leave a nonce gap.
+        for txs in pending.values_mut() {
+            txs.retain(|tx| {
+                tx.blob_versioned_hashes().is_none() ||
+                    sidecars.contains_key(&keccak256(tx.encoded_2718()))
+            });
+        }
+
+        let transactions = select_fill_transactions(pending, template, &account_states, limits);
+        let sidecars = transactions
+            .iter()
+            .filter_map(|tx| sidecars.remove(&keccak256(tx.encoded_2718())))
+            .collect::<Vec<_>>();
+
+        debug!(
+            count = transactions.len(),
+            blob_count = sidecars.len(),
+            ?limits,
+            "Selected txpool transactions to fill block"
+        );
+
+        Ok(FillTransactions { transactions, sidecars })
+    }
+
+    /// Fetch the blobs of the given blob transactions from the blob pool of the execution
+    /// client with `engine_getBlobsV1`, and rebuild their sidecars.
+    ///
+    /// The KZG commitments are not returned by the execution client, so they are computed
+    /// from the blobs and checked against the versioned hashes of the transactions. Transactions
+    /// with missing or mismatched blobs are not returned.
+    async fn fetch_blob_sidecars<'a>(
+        &self,
+        transactions: impl Iterator<Item = &'a TransactionSigned>,
+    ) -> HashMap<TxHash, BlobTransactionSidecar> {
+        let blob_transactions = transactions
+            .filter_map(|tx| {
+                let versioned_hashes = tx.blob_versioned_hashes()?.to_vec();
+                Some((keccak256(tx.encoded_2718()), versioned_hashes))
+            })
+            .collect::<Vec<_>>();
+
+        if blob_transactions.is_empty() {
+            return HashMap::new();
+        }
+
+        let versioned_hashes = blob_transactions
+            .iter()
+            .flat_map(|(_, hashes)| hashes.iter().copied())
+            .collect::<Vec<_>>();
+
+        let blobs = match self.engine_api.get_blobs_v1(versioned_hashes.clone()).await {
+            Ok(blobs) => blobs,
+            Err(err) => {
+                warn!(?err, "Failed to fetch the blobs of txpool transactions");
+                return HashMap::new();
+            }
+        };
+
+        let blobs = versioned_hashes
+            .into_iter()
+            .zip(blobs)
+            .filter_map(|(hash, blob)| Some((hash, blob?)))
+            .collect::<HashMap<_, _>>();
+
+        let mut sidecars = HashMap::with_capacity(blob_transactions.len());
+        for (hash, versioned_hashes) in blob_transactions {
+            let Some(tx_blobs) = versioned_hashes
+                .iter()
+                .map(|versioned_hash| blobs.get(versioned_hash).map(|b| b.blob.as_slice()))
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+
+            match BlobTransactionSidecar::try_from_blobs_bytes(tx_blobs) {
+                Ok(sidecar) if sidecar.versioned_hashes().eq(versioned_hashes.iter().copied()) => {
+                    sidecars.insert(hash, sidecar);
+                }
+                Ok(_) => warn!(%hash, "Blobs don't match the versioned hashes of the transaction"),
+                Err(err) => warn!(%hash, ?err, "Failed to compute the sidecar of the transaction"),
+            }
+        }
+
+        sidecars
+    }
+}
+
+/// Calculate the excess blob gas of the next block, with the blob target of its fork.
+fn calc_excess_blob_gas(fork: Fork, parent_excess_blob_gas: u64, parent_blob_gas_used: u64) -> u64 {
+    let target_blobs = match fork {
+        Fork::Electra => TARGET_BLOBS_PER_BLOCK_ELECTRA,
+        _ => TARGET_BLOBS_PER_BLOCK,
+    };
+
+    (parent_excess_blob_gas + parent_blob_gas_used).saturating_sub(target_blobs * DATA_GAS_PER_BLOB)
+}
+
+/// The txpool transactions selected to fill a fallback block, with the sidecars of the
+/// selected blob transactions in the same order.
+#[derive(Debug, Default)]
+pub struct FillTransactions {
+    /// The transactions to append after the constrained ones.
+    pub transactions: Vec<TransactionSigned>,
+    /// The sidecars of the blob transactions among them.
+    pub sidecars: Vec<BlobTransactionSidecar>,
+}
+
+/// The values of the head of the chain needed to build a payload on top of it.
+#[derive(Debug, Clone)]
+struct HeadContext {
+    fork: Fork,
+    head_block: Block,
+    parent_beacon_block_root: B256,
+    withdrawals: Vec<Withdrawal>,
+    prev_randao: B256,
+    block_timestamp: u64,
 }
 
 #[cfg(test)]
@@ -152,12 +498,13 @@ mod tests {
         providers::{Provider, ProviderBuilder},
         signers::{k256::ecdsa::SigningKey, local::PrivateKeySigner},
     };
-    use beacon_api_client::mainnet::Client as BeaconClient;
     use reth_primitives::TransactionSigned;
     use tracing::warn;
 
     use crate::{
         builder::FallbackPayloadBuilder,
+        client::BeaconClient,
+        state::FailoverStateClient,
         test_util::{default_test_transaction, get_test_config},
     };
 
@@ -178,10 +525,11 @@ mod tests {
 
         let raw_sk = std::env::var("PRIVATE_KEY")?;
 
-        let provider = ProviderBuilder::new().on_http(cfg.execution_api_url.clone());
-        let beacon_client = BeaconClient::new(cfg.beacon_api_url.clone());
+        let provider = ProviderBuilder::new().on_http(cfg.execution_api_urls[0].clone());
+        let beacon_client = BeaconClient::from_urls(cfg.beacon_api_urls.clone());
+        let execution_api = FailoverStateClient::from_urls(cfg.execution_api_urls.clone());
         let genesis_time = beacon_client.get_genesis_details().await?.genesis_time;
-        let builder = FallbackPayloadBuilder::new(&cfg, genesis_time);
+        let builder = FallbackPayloadBuilder::new(&cfg, beacon_client, execution_api, genesis_time);
 
         let sk = SigningKey::from_slice(hex::decode(raw_sk)?.as_slice())?;
         let signer = PrivateKeySigner::from_signing_key(sk.clone());
@@ -198,13 +546,26 @@ mod tests {
             (SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() / cfg.chain.slot_time()) +
             1;
 
-        let block = builder.build_fallback_payload(slot, &[tx_signed_reth]).await?;
+        let payload = builder.build_fallback_payload(slot, &[tx_signed_reth]).await?;
 
-        assert_eq!(block.body().transactions.len(), 1);
+        assert_eq!(payload.block.body().transactions.len(), 1);
 
         Ok(())
     }
 
+    #[test]
+    fn test_calc_excess_blob_gas() {
+        use alloy::eips::eip4844::DATA_GAS_PER_BLOB;
+        use ethereum_consensus::Fork;
+
+        use super::calc_excess_blob_gas;
+
+        // A parent block with 6 blobs is above the Deneb target, but at the Electra one
+        let blob_gas_used = 6 * DATA_GAS_PER_BLOB;
+        assert_eq!(calc_excess_blob_gas(Fork::Deneb, 0, blob_gas_used), 3 * DATA_GAS_PER_BLOB);
+        assert_eq!(calc_excess_blob_gas(Fork::Electra, 0, blob_gas_used), 0);
+    }
+
     #[test]
     fn test_empty_el_withdrawals_root() {
         // Withdrawal root in the execution layer header is MPT.
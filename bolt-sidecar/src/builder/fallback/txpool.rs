@@ -0,0 +1,338 @@
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+use alloy::{
+    consensus::Transaction,
+    eips::eip2718::{Decodable2718, Encodable2718},
+    primitives::{keccak256, Address, U256},
+    rpc::types::Transaction as RpcTransaction,
+};
+use reth_primitives::TransactionSigned;
+use tracing::trace;
+
+use crate::{builder::BlockTemplate, primitives::AccountState};
+
+/// Converts a transaction returned by the `txpool_content` method into a signed transaction
+/// that can be included in a block.
+///
+/// The sidecars of blob transactions are not returned by the txpool: they must be fetched
+/// separately before the transactions can be included in a block.
+pub(crate) fn from_txpool_transaction(tx: RpcTransaction) -> Option<TransactionSigned> {
+    let raw = tx.inner.encoded_2718();
+    TransactionSigned::decode_2718(&mut raw.as_slice()).ok()
+}
+
+/// The constraints on the transactions that can be used to fill a fallback block.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FillLimits {
+    /// The base fee of the block being built.
+    pub base_fee: u64,
+    /// The blob base fee of the block being built.
+    pub blob_basefee: u128,
+    /// The gas left in the block after the constrained transactions.
+    pub gas_left: u64,
+    /// The number of blobs left in the block after the constrained transactions.
+    pub blobs_left: usize,
+}
+
+/// Selects the pending transactions to append to a fallback block after its constrained
+/// transactions, ordered by effective tip per gas.
+///
+/// Candidates are grouped by sender, and every sender queue must be included in nonce order.
+/// Blob transactions must fit in the blobs left in the block, and pay its blob base fee.
+/// Senders with constrained transactions in the template are checked against its state diffs:
+/// their pending transactions must continue the nonce sequence after the constrained ones,
+/// and be covered by the balance left after them. If the account state of such a sender is not
+/// available, its pending transactions are ignored.
+pub(crate) fn select_fill_transactions(
+    pending: HashMap<Address, Vec<TransactionSigned>>,
+    template: &BlockTemplate,
+    account_states: &HashMap<Address, AccountState>,
+    limits: FillLimits,
+) -> Vec<TransactionSigned> {
+    let constrained = template.transaction_hashes().into_iter().collect::<HashSet<_>>();
+
+    let mut queues = Vec::with_capacity(pending.len());
+    for (sender, mut txs) in pending {
+        txs.retain(|tx| !constrained.contains(&keccak256(tx.encoded_2718())));
+        txs.sort_by_key(|tx| tx.nonce());
+
+        let Some(first_nonce) = txs.first().map(|tx| tx.nonce()) else {
+            continue;
+        };
+
+        let (next_nonce, balance) = match template.get_diff(&sender) {
+            None => (first_nonce, U256::MAX),
+            Some((nonce_diff, balance_diff)) => {
+                let Some(state) = account_states.get(&sender) else {
+                    trace!(%sender, "Skipping pending transactions of sender without account state");
+                    continue;
+                };
+
+                let next_nonce = state.transaction_count + nonce_diff;
+                let balance = state.balance.saturating_sub(balance_diff);
+
+                // Pending transactions with a nonce taken by constrained transactions would
+                // conflict with them.
+                txs.retain(|tx| tx.nonce() >= next_nonce);
+                (next_nonce, balance)
+            }
+        };
+
+        // Only keep the contiguous nonce sequence starting from the next nonce.
+        let contiguous =
+            txs.iter().zip(next_nonce..).take_while(|(tx, nonce)| tx.nonce() == *nonce).count();
+        txs.truncate(contiguous);
+
+        if !txs.is_empty() {
+            queues.push(SenderQueue { txs: txs.into(), balance });
+        }
+    }
+
+    let mut heap = BinaryHeap::new();
+    for (index, queue) in queues.iter().enumerate() {
+        if let Some(candidate) = queue.candidate(index, limits) {
+            heap.push(candidate);
+        }
+    }
+
+    let mut gas_left = limits.gas_left;
+    let mut blobs_left = limits.blobs_left;
+    let mut selected = Vec::new();
+
+    while let Some(Candidate { index, .. }) = heap.pop() {
+        let queue = &mut queues[index];
+        let Some(tx) = queue.txs.pop_front() else { continue };
+
+        let cost = max_cost(&tx);
+        let blobs = tx.blob_versioned_hashes().map_or(0, |hashes| hashes.len());
+        if tx.gas_limit() > gas_left || blobs > blobs_left || cost > queue.balance {
+            // The following transactions of this sender can't be included without this one.
+            continue;
+        }
+
+        gas_left -= tx.gas_limit();
+        blobs_left -= blobs;
+        queue.balance -= cost;
+        selected.push(tx);
+
+        if let Some(candidate) = queue.candidate(index, limits) {
+            heap.push(candidate);
+        }
+    }
+
+    selected
+}
+
+/// The maximum cost of a transaction: gas limit * max fee per gas + value, plus
+/// blob gas * max fee per blob gas for blob transactions.
+fn max_cost(tx: &TransactionSigned) -> U256 {
+    let blob_cost = U256::from(tx.blob_gas_used().unwrap_or_default()) *
+        U256::from(tx.max_fee_per_blob_gas().unwrap_or_default());
+
+    U256::from(tx.gas_limit()) * U256::from(tx.max_fee_per_gas()) + tx.value() + blob_cost
+}
+
+/// The pending transactions of a single sender, in nonce order.
+#[derive(Debug)]
+struct SenderQueue {
+    txs: VecDeque<TransactionSigned>,
+    /// The balance of the sender left to pay for its transactions.
+    balance: U256,
+}
+
+impl SenderQueue {
+    /// Returns the next transaction of the queue as a candidate, if it can pay the base fee
+    /// and the blob base fee.
+    fn candidate(&self, index: usize, limits: FillLimits) -> Option<Candidate> {
+        let tx = self.txs.front()?;
+        if tx.max_fee_per_blob_gas().is_some_and(|fee| fee < limits.blob_basefee) {
+            return None;
+        }
+
+        let tip = tx.effective_tip_per_gas(limits.base_fee)?;
+        Some(Candidate { tip, index })
+    }
+}
+
+/// A candidate transaction for inclusion, ordered by effective tip per gas.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Candidate {
+    tip: u128,
+    /// The index of the sender queue of the transaction.
+    index: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        consensus::{Signed, TxEip1559, TxEip4844},
+        primitives::{address, PrimitiveSignature, TxKind, B256},
+    };
+
+    use super::*;
+
+    const ALICE: Address = address!("0000000000000000000000000000000000000001");
+    const BOB: Address = address!("0000000000000000000000000000000000000002");
+
+    fn tx_with_fee(nonce: u64, gas_limit: u64, max_fee: u128, tip: u128) -> TransactionSigned {
+        let tx = TxEip1559 {
+            nonce,
+            gas_limit,
+            max_fee_per_gas: max_fee,
+            max_priority_fee_per_gas: tip,
+            to: TxKind::Call(Address::ZERO),
+            ..Default::default()
+        };
+        Signed::new_unchecked(tx, PrimitiveSignature::test_signature(), Default::default()).into()
+    }
+
+    /// A transaction paying exactly `tip` per gas at the base fee of 100 used in the tests.
+    fn tx(nonce: u64, gas_limit: u64, tip: u128) -> TransactionSigned {
+        tx_with_fee(nonce, gas_limit, 100 + tip, tip)
+    }
+
+    /// A blob transaction with the given number of blobs, paying `tip` per gas at the base fee
+    /// of 100 and a max fee per blob gas of 10.
+    fn blob_tx(nonce: u64, blobs: usize, tip: u128) -> TransactionSigned {
+        let tx = TxEip4844 {
+            nonce,
+            gas_limit: 21_000,
+            max_fee_per_gas: 100 + tip,
+            max_priority_fee_per_gas: tip,
+            max_fee_per_blob_gas: 10,
+            blob_versioned_hashes: vec![B256::ZERO; blobs],
+            ..Default::default()
+        };
+        Signed::new_unchecked(tx, PrimitiveSignature::test_signature(), Default::default()).into()
+    }
+
+    fn limits(gas_left: u64) -> FillLimits {
+        FillLimits { base_fee: 100, blob_basefee: 10, gas_left, blobs_left: 6 }
+    }
+
+    #[test]
+    fn test_select_by_tip_in_nonce_order() {
+        let pending = HashMap::from([
+            (ALICE, vec![tx(1, 21_000, 5), tx(0, 21_000, 1)]),
+            (BOB, vec![tx(0, 21_000, 3)]),
+        ]);
+
+        let selected = select_fill_transactions(
+            pending,
+            &BlockTemplate::default(),
+            &HashMap::new(),
+            limits(1_000_000),
+        );
+
+        // Alice's tx with nonce 1 has the highest tip, but must wait for nonce 0
+        let order = selected
+            .iter()
+            .map(|tx| (tx.nonce(), tx.max_priority_fee_per_gas()))
+            .collect::<Vec<_>>();
+        assert_eq!(order, vec![(0, Some(3)), (0, Some(1)), (1, Some(5))]);
+    }
+
+    #[test]
+    fn test_select_respects_gas_and_base_fee() {
+        let pending = HashMap::from([
+            (ALICE, vec![tx(0, 50_000, 10), tx(1, 21_000, 10)]),
+            (BOB, vec![tx(0, 21_000, 3)]),
+        ]);
+
+        let selected = select_fill_transactions(
+            pending,
+            &BlockTemplate::default(),
+            &HashMap::new(),
+            limits(30_000),
+        );
+
+        // Alice's first tx doesn't fit, so her second one can't be included either
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].max_priority_fee_per_gas(), Some(3));
+
+        // Transactions that can't pay the base fee are never included
+        let pending = HashMap::from([(ALICE, vec![tx_with_fee(0, 21_000, 50, 1)])]);
+        let selected = select_fill_transactions(
+            pending,
+            &BlockTemplate::default(),
+            &HashMap::new(),
+            limits(1_000_000),
+        );
+        assert!(selected.is_empty());
+    }
+
+    #[test]
+    fn test_select_checks_state_diffs() {
+        let mut template = BlockTemplate::default();
+        // Alice has two constrained transactions, costing 1 ETH in total
+        template.state_diff.diffs.insert(ALICE, (2, U256::from(10).pow(U256::from(18))));
+        // Bob has constrained transactions too, but his account state is unknown
+        template.state_diff.diffs.insert(BOB, (1, U256::ZERO));
+
+        let alice_state = AccountState {
+            transaction_count: 5,
+            balance: U256::from(10).pow(U256::from(18)) + U256::from(21_000 * 111),
+            has_code: false,
+        };
+        let account_states = HashMap::from([(ALICE, alice_state)]);
+
+        let pending = HashMap::from([
+            // Nonces 5 and 6 conflict with the constrained transactions, and the balance left
+            // after nonce 7 doesn't cover nonce 8
+            (ALICE, vec![tx(5, 21_000, 1), tx(6, 21_000, 1), tx(7, 21_000, 11), tx(8, 21_000, 11)]),
+            (BOB, vec![tx(0, 21_000, 1)]),
+        ]);
+
+        let selected =
+            select_fill_transactions(pending, &template, &account_states, limits(1_000_000));
+
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].nonce(), 7);
+    }
+
+    #[test]
+    fn test_select_respects_blob_limits() {
+        let pending = HashMap::from([
+            (ALICE, vec![blob_tx(0, 4, 10), blob_tx(1, 1, 10)]),
+            (BOB, vec![blob_tx(0, 3, 5), tx(1, 21_000, 1)]),
+        ]);
+
+        let selected = select_fill_transactions(
+            pending,
+            &BlockTemplate::default(),
+            &HashMap::new(),
+            limits(1_000_000),
+        );
+
+        // Bob's blob transaction doesn't fit in the 1 blob left after Alice's ones, so his
+        // following transaction can't be included either
+        let order = selected.iter().map(|tx| tx.nonce()).collect::<Vec<_>>();
+        assert_eq!(order, vec![0, 1]);
+        assert!(selected.iter().all(|tx| tx.blob_versioned_hashes().is_some()));
+
+        // Blob transactions that can't pay the blob base fee are never included
+        let pending = HashMap::from([(ALICE, vec![blob_tx(0, 1, 10)])]);
+        let selected = select_fill_transactions(
+            pending,
+            &BlockTemplate::default(),
+            &HashMap::new(),
+            FillLimits { blob_basefee: 11, ..limits(1_000_000) },
+        );
+        assert!(selected.is_empty());
+    }
+
+    #[test]
+    fn test_select_skips_nonce_gaps() {
+        let pending = HashMap::from([(ALICE, vec![tx(0, 21_000, 1), tx(2, 21_000, 1)])]);
+
+        let selected = select_fill_transactions(
+            pending,
+            &BlockTemplate::default(),
+            &HashMap::new(),
+            limits(1_000_000),
+        );
+
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].nonce(), 0);
+    }
+}
@@ -1,23 +1,39 @@
-use alloy::primitives::U256;
+use std::sync::Arc;
+
+use alloy::{consensus::Transaction, primitives::U256};
 use alloy_rpc_types_engine::{ClientCode, PayloadStatusEnum};
 use ethereum_consensus::{
     crypto::{KzgCommitment, PublicKey},
     deneb::mainnet::ExecutionPayloadHeader,
     ssz::prelude::{List, MerkleizationError},
+    Fork,
 };
+use reth_primitives::TransactionSigned;
+use tracing::{debug, warn};
 
 use crate::{
+    client::BeaconClient,
     common::secrets::BlsSecretKeyWrapper,
-    config::{ChainConfig, Opts},
+    config::{chain::UnsupportedForkError, ChainConfig, Opts},
     primitives::{
-        BuilderBid, GetPayloadResponse, PayloadAndBid, PayloadAndBlobs, SignedBuilderBid,
+        BuilderBid, BuilderBidElectra, ExecutionRequests, GetPayloadResponse, PayloadAndBid,
+        PayloadAndBlobs, SignedBuilderBid, SignedBuilderBidElectra, VersionedSignedBuilderBid,
     },
+    state::FailoverStateClient,
 };
 
+/// Per-slot cache of the payloads built by the local builder.
+pub mod cache;
+use cache::{CachedPayload, PayloadCache};
+
+/// Background pre-building of local payloads as block templates change.
+pub mod prebuild;
+pub use prebuild::PayloadPrebuilder;
+
 /// Fallback payload building logic that (ab)uses the engine API's
 /// `engine_newPayloadV3` response error to produce a valid payload.
 pub mod fallback;
-pub use fallback::FallbackPayloadBuilder;
+pub use fallback::{FallbackBuildStrategy, FallbackPayload, FallbackPayloadBuilder};
 
 /// Basic block template handler that can keep track of
 /// the local commitments according to protocol validity rules.
@@ -74,11 +90,30 @@ pub enum BuilderError {
     UnsupportedEngineClient(ClientCode),
     #[error("Failed to gather hints after {0} iterations")]
     ExceededMaxHintIterations(u64),
+    #[error("Engine API did not start a payload build (status: {0})")]
+    MissingPayloadId(PayloadStatusEnum),
+    #[error("Invalid payload from engine API: {0}")]
+    InvalidEnginePayload(String),
+    #[error("Payload built by the engine API is missing {0} constrained transactions")]
+    MissingConstrainedTransactions(usize),
+    #[error("Payload built by the engine API doesn't respect the order of the constraints")]
+    UnorderedConstrainedTransactions,
+    #[error("Blob transactions can't be injected in the engine API payload build")]
+    UnsupportedBlobTransactions,
+    #[error("Local payload for slot {0} was discarded by the payload cache")]
+    DiscardedPayload(u64),
+    #[error("Execution requests can't be hinted by the engine API: {0}")]
+    UnsupportedExecutionRequests(String),
+    #[error(transparent)]
+    UnsupportedFork(#[from] UnsupportedForkError),
 }
 
 /// Local builder instance that can ingest a sealed header and
 /// create the corresponding builder bid ready for the Builder API.
-#[derive(Debug)]
+///
+/// The builder is cheap to clone, and all clones share the same payload cache, so that
+/// payloads can be built in the background by the [PayloadPrebuilder].
+#[derive(Debug, Clone)]
 pub struct LocalBuilder {
     /// BLS credentials for the local builder. We use this to sign the
     /// payload bid submissions built by the sidecar.
@@ -88,86 +123,286 @@ pub struct LocalBuilder {
     chain: ChainConfig,
     /// Async fallback payload builder to generate valid payloads with
     /// the engine API's `engine_newPayloadV3` response error.
-    fallback_builder: FallbackPayloadBuilder,
-    /// The last payload and bid that was built by the local builder.
-    payload_and_bid: Option<PayloadAndBid>,
+    fallback_builder: Arc<FallbackPayloadBuilder>,
+    /// The payloads and bids built by the local builder, per target slot.
+    payloads: Arc<PayloadCache>,
+    /// Whether to fill the rest of the block with txpool transactions after the constraints.
+    fill_from_txpool: bool,
 }
 
 impl LocalBuilder {
     /// Create a new local builder with the given secret key.
-    pub fn new(opts: &Opts, genesis_time: u64) -> Self {
+    pub fn new(
+        opts: &Opts,
+        beacon_api: BeaconClient,
+        execution_api: FailoverStateClient,
+        genesis_time: u64,
+    ) -> Self {
+        let fallback_builder =
+            FallbackPayloadBuilder::new(opts, beacon_api, execution_api, genesis_time);
+
         Self {
-            payload_and_bid: None,
-            fallback_builder: FallbackPayloadBuilder::new(opts, genesis_time),
+            payloads: Arc::new(PayloadCache::default()),
+            fallback_builder: Arc::new(fallback_builder),
             secret_key: opts.builder_private_key.clone(),
             chain: opts.chain,
+            fill_from_txpool: opts.fill_fallback_block,
         }
     }
 
     /// Build a new payload with the given transactions. This method will
     /// cache the payload in the local builder instance, and make it available
+    /// until it is replaced by a build of a newer template for the same slot.
+    ///
+    /// The payload is discarded if a new head was received while it was being built.
     pub async fn build_new_local_payload(
-        &mut self,
+        &self,
         slot: u64,
         template: &BlockTemplate,
     ) -> Result<(), BuilderError> {
-        let transactions = template.as_signed_transactions();
-        let blobs_bundle = template.as_blobs_bundle();
-        let kzg_commitments = blobs_bundle.commitments.clone();
+        // The engine strategy injects the constrained transactions in the txpool of the
+        // execution client, so they are only built at the deadline to leak them as late
+        // as possible.
+        if self.fallback_builder.strategy() == FallbackBuildStrategy::Engine {
+            debug!(slot, "Skipping local payload pre-build with the engine strategy");
+            return Ok(());
+        }
+
+        let generation = self.payloads.generation();
+        let payload = self.build_payload(slot, template).await?;
+
+        if !self.payloads.insert(slot, generation, payload, false) {
+            debug!(slot, "Discarded stale local payload");
+        }
+
+        Ok(())
+    }
+
+    /// Finalize the local payload for the given slot at the commitment deadline.
+    ///
+    /// If the latest cached payload was built from the same constraints as the given template,
+    /// it is used as is. Otherwise, a new payload is built on the spot. Either way, the payload
+    /// can't be replaced by background builds afterwards. If no payload could be finalized,
+    /// none is served for the slot.
+    pub async fn finalize_local_payload(
+        &self,
+        slot: u64,
+        template: &BlockTemplate,
+    ) -> Result<(), BuilderError> {
+        if self.payloads.finalize(slot, &template.transaction_hashes()) {
+            debug!(slot, "Finalized pre-built local payload");
+            return Ok(());
+        }
+
+        debug!(slot, "No up-to-date pre-built local payload, building one");
+        let generation = self.payloads.generation();
+        let payload = self.build_payload(slot, template).await?;
+
+        if !self.payloads.insert(slot, generation, payload, true) {
+            warn!(slot, "Discarded local payload built at the deadline");
+            return Err(BuilderError::DiscardedPayload(slot));
+        }
+
+        Ok(())
+    }
+
+    /// Invalidate the payloads that were built on top of a previous head of the chain,
+    /// and that were not finalized yet.
+    pub fn invalidate_payloads(&self) {
+        self.payloads.invalidate();
+    }
+
+    /// Build a new payload and signed bid with the constrained transactions of the template.
+    async fn build_payload(
+        &self,
+        slot: u64,
+        template: &BlockTemplate,
+    ) -> Result<CachedPayload, BuilderError> {
+        let mut transactions = template.as_signed_transactions();
+        let constrained_count = transactions.len();
+        let mut fill_sidecars = Vec::new();
+
+        // 0. optionally append pending transactions from the txpool after the constraints
+        if self.fill_from_txpool {
+            match self.fallback_builder.fetch_fill_transactions(slot, template).await {
+                Ok(fill) => {
+                    transactions.extend(fill.transactions);
+                    fill_sidecars = fill.sidecars;
+                }
+                Err(err) => warn!(slot, ?err, "Failed to fetch txpool transactions to fill block"),
+            }
+        }
 
         // 1. build a fallback payload with the given transactions, on top of
         // the current head of the chain
-        let block = self.fallback_builder.build_fallback_payload(slot, &transactions).await?;
+        let payload = match self.fallback_builder.build_fallback_payload(slot, &transactions).await
+        {
+            Ok(payload) => payload,
+            Err(err) if transactions.len() > constrained_count => {
+                // The txpool transactions may have become invalid in the meantime: retry with
+                // the constrained transactions only.
+                warn!(slot, ?err, "Failed to build filled fallback block, retrying without fill");
+                transactions.truncate(constrained_count);
+                fill_sidecars.clear();
+                self.fallback_builder.build_fallback_payload(slot, &transactions).await?
+            }
+            Err(err) => return Err(err),
+        };
+        let FallbackPayload { block, execution_requests, value } = payload;
+
+        // The blobs of the txpool transactions follow the ones of the constraints, in the
+        // order of their transactions in the block.
+        let mut blobs_bundle = template.as_blobs_bundle();
+        let fill_bundle = compat::to_consensus_blobs_bundle(&fill_sidecars);
+        blobs_bundle.commitments.extend(fill_bundle.commitments);
+        blobs_bundle.proofs.extend(fill_bundle.proofs);
+        blobs_bundle.blobs.extend(fill_bundle.blobs);
+        let kzg_commitments = blobs_bundle.commitments.clone();
 
-        // NOTE: we use a big value for the bid to ensure it gets chosen by constraints client.
-        // the client has no way to actually verify this, and we don't need to trust
-        // an external relay as this block is self-built, so the fake bid value is fine.
-        //
-        // NOTE: we don't strictly need this. The validator & beacon nodes have options
-        // to ALWAYS prefer PBS blocks. This is a safety measure that doesn't hurt to keep.
-        let value = U256::from(100_000_000_000_000_000_000u128);
+        // The value of the local block is the sum of the priority fees paid to the fee
+        // recipient, so that it can be compared with the value of relay bids.
+        debug!(slot, %value, "Computed local payload value");
 
         let eth_payload = compat::to_consensus_execution_payload(&block);
         let payload_and_blobs = PayloadAndBlobs { execution_payload: eth_payload, blobs_bundle };
 
         // 2. create a signed builder bid with the sealed block header we just created
-        let eth_header = compat::to_execution_payload_header(&block, transactions);
+        let eth_header =
+            compat::to_execution_payload_header(&block, block.body().transactions.clone());
 
-        // 3. sign the bid with the local builder's BLS key
-        let signed_bid = self.create_signed_builder_bid(value, eth_header, kzg_commitments)?;
+        // 3. sign the bid with the local builder's BLS key, with the version of the fork
+        // of the target slot
+        let fork = self.chain.fork_at_slot(slot)?;
+        let signed_bid = self.create_signed_builder_bid(
+            fork,
+            value,
+            eth_header,
+            kzg_commitments,
+            execution_requests,
+        )?;
 
         // 4. prepare a get_payload response for when the beacon node will ask for it
-        let get_payload_response = GetPayloadResponse::from(payload_and_blobs);
-
-        self.payload_and_bid =
-            Some(PayloadAndBid { bid: signed_bid, payload: get_payload_response });
+        let get_payload_response = GetPayloadResponse::new(fork, payload_and_blobs);
 
-        Ok(())
+        Ok(CachedPayload {
+            transaction_hashes: template.transaction_hashes(),
+            payload_and_bid: PayloadAndBid { bid: signed_bid, payload: get_payload_response },
+        })
     }
 
-    /// Get the cached payload and bid from the local builder, consuming the value.
+    /// Get a copy of the cached payload and bid from the local builder, if it was built
+    /// for the given slot.
+    ///
+    /// The payload is not consumed, as it may be requested again to be compared
+    /// against relay bids for the same slot.
     #[inline]
-    pub fn get_cached_payload(&mut self) -> Option<PayloadAndBid> {
-        self.payload_and_bid.take()
+    pub fn get_cached_payload(&self, slot: u64) -> Option<PayloadAndBid> {
+        self.payloads.get(slot)
     }
 
-    /// transform a sealed header into a signed builder bid using
+    /// transform a sealed header into a signed builder bid for the given fork using
     /// the local builder's BLS key.
+    ///
+    /// Electra bids also commit to the execution requests of the payload, which default
+    /// to empty ones if missing.
     fn create_signed_builder_bid(
         &self,
+        fork: Fork,
         value: U256,
         header: ExecutionPayloadHeader,
         blob_kzg_commitments: Vec<KzgCommitment>,
-    ) -> Result<SignedBuilderBid, BuilderError> {
+        execution_requests: Option<ExecutionRequests>,
+    ) -> Result<VersionedSignedBuilderBid, BuilderError> {
         // compat: convert from blst to ethereum consensus types
         let public_key = self.secret_key.sk_to_pk().to_bytes();
         let public_key = PublicKey::try_from(public_key.as_ref()).expect("valid public key");
         let blob_kzg_commitments = List::try_from(blob_kzg_commitments).expect("valid list");
 
+        if fork == Fork::Electra {
+            let message = BuilderBidElectra {
+                header,
+                blob_kzg_commitments,
+                execution_requests: execution_requests.unwrap_or_default(),
+                public_key,
+                value,
+            };
+            let signature = sign_builder_message(&self.chain, &self.secret_key, &message)?;
+
+            return Ok(VersionedSignedBuilderBid::Electra(SignedBuilderBidElectra {
+                message,
+                signature,
+            }));
+        }
+
         let message = BuilderBid { header, blob_kzg_commitments, public_key, value };
 
         let signature = sign_builder_message(&self.chain, &self.secret_key, &message)?;
 
-        Ok(SignedBuilderBid { message, signature })
+        Ok(VersionedSignedBuilderBid::Deneb(SignedBuilderBid { message, signature }))
+    }
+}
+
+/// Returns the sum of the priority fees paid by the given transactions at the given base fee,
+/// from the gas used by each of them.
+fn priority_fees(transactions: &[TransactionSigned], base_fee: u64, gas_used: &[u64]) -> U256 {
+    transactions.iter().zip(gas_used).fold(U256::ZERO, |acc, (tx, gas)| {
+        let tip = tx.effective_tip_per_gas(base_fee).unwrap_or_default();
+        acc + U256::from(tip) * U256::from(*gas)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        consensus::{Signed, TxEip1559, TxLegacy},
+        primitives::{Address, PrimitiveSignature, TxKind},
+    };
+    use reth_primitives::TransactionSigned;
+
+    use super::*;
+
+    fn eip1559_tx(gas_limit: u64, max_fee_per_gas: u128, tip: u128) -> TransactionSigned {
+        let tx = TxEip1559 {
+            gas_limit,
+            max_fee_per_gas,
+            max_priority_fee_per_gas: tip,
+            to: TxKind::Call(Address::ZERO),
+            ..Default::default()
+        };
+        Signed::new_unchecked(tx, PrimitiveSignature::test_signature(), Default::default()).into()
+    }
+
+    fn legacy_tx(gas_limit: u64, gas_price: u128) -> TransactionSigned {
+        let tx = TxLegacy {
+            gas_limit,
+            gas_price,
+            to: TxKind::Call(Address::ZERO),
+            ..Default::default()
+        };
+        Signed::new_unchecked(tx, PrimitiveSignature::test_signature(), Default::default()).into()
+    }
+
+    #[test]
+    fn test_priority_fees() {
+        let base_fee = 10;
+        let txs = vec![
+            // tip capped by the max priority fee: 2 * 21_000
+            eip1559_tx(21_000, 100, 2),
+            // tip capped by the max fee: (15 - 10) * 50_000
+            eip1559_tx(50_000, 15, 10),
+            // the whole gas price above the base fee: (12 - 10) * 21_000
+            legacy_tx(21_000, 12),
+        ];
+
+        let value = priority_fees(&txs, base_fee, &[21_000, 50_000, 21_000]);
+        assert_eq!(value, U256::from(2 * 21_000 + 5 * 50_000 + 2 * 21_000));
+
+        // Only the gas used by each transaction is paid, not its whole gas limit
+        let value = priority_fees(&txs, base_fee, &[21_000, 32_000, 21_000]);
+        assert_eq!(value, U256::from(2 * 21_000 + 5 * 32_000 + 2 * 21_000));
+
+        // Transactions that can't pay the base fee don't add any value
+        let value = priority_fees(&txs, 200, &[21_000, 50_000, 21_000]);
+        assert_eq!(value, U256::ZERO);
     }
 }
@@ -1,19 +1,25 @@
 use tokio::sync::{mpsc, oneshot};
 use tracing::error;
 
-use crate::primitives::{FetchPayloadRequest, PayloadAndBid};
+use crate::primitives::{
+    FetchConstraintsRequest, FetchPayloadRequest, PayloadAndBid, SignedConstraints,
+};
 
 /// A local payload fetcher that sends requests to a channel
 /// and waits for a response on a oneshot channel.
 #[derive(Debug, Clone)]
 pub struct LocalPayloadFetcher {
     tx: mpsc::Sender<FetchPayloadRequest>,
+    constraints_tx: mpsc::Sender<FetchConstraintsRequest>,
 }
 
 impl LocalPayloadFetcher {
-    /// Create a new `LocalPayloadFetcher` with the given channel to send fetch requests.
-    pub fn new(tx: mpsc::Sender<FetchPayloadRequest>) -> Self {
-        Self { tx }
+    /// Create a new `LocalPayloadFetcher` with the given channels to send fetch requests.
+    pub fn new(
+        tx: mpsc::Sender<FetchPayloadRequest>,
+        constraints_tx: mpsc::Sender<FetchConstraintsRequest>,
+    ) -> Self {
+        Self { tx, constraints_tx }
     }
 }
 
@@ -33,6 +39,21 @@ impl PayloadFetcher for LocalPayloadFetcher {
             }
         }
     }
+
+    async fn fetch_constraints(&self, slot: u64) -> Option<Vec<SignedConstraints>> {
+        let (response_tx, response_rx) = oneshot::channel();
+
+        let fetch_params = FetchConstraintsRequest { response_tx, slot };
+        self.constraints_tx.send(fetch_params).await.ok()?;
+
+        match response_rx.await {
+            Ok(res) => Some(res),
+            Err(e) => {
+                error!(err = ?e, "Failed to fetch constraints");
+                None
+            }
+        }
+    }
 }
 
 /// Interface for fetching payloads for the builder.
@@ -40,6 +61,10 @@ impl PayloadFetcher for LocalPayloadFetcher {
 pub trait PayloadFetcher {
     /// Fetch a payload for the given slot.
     async fn fetch_payload(&self, slot: u64) -> Option<PayloadAndBid>;
+
+    /// Fetch the signed constraints for the given slot, which remote bids are checked against.
+    /// Returns `None` if the constraints could not be fetched.
+    async fn fetch_constraints(&self, slot: u64) -> Option<Vec<SignedConstraints>>;
 }
 
 /// A payload fetcher that does nothing, used for testing.
@@ -54,4 +79,9 @@ impl PayloadFetcher for NoopPayloadFetcher {
         tracing::info!(slot, "Fetch payload called");
         None
     }
+
+    async fn fetch_constraints(&self, slot: u64) -> Option<Vec<SignedConstraints>> {
+        tracing::info!(slot, "Fetch constraints called");
+        Some(Vec::new())
+    }
 }
@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use tokio::{sync::mpsc, time::timeout};
+use tracing::{debug, error};
+
+use super::{BlockTemplate, LocalBuilder};
+
+/// The time to wait for the block template of a slot to stop changing before building
+/// a payload for it.
+const PREBUILD_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// The size of the channel of block template updates.
+const PREBUILD_BUFFER_SIZE: usize = 64;
+
+/// The [PayloadPrebuilder] builds local payloads in the background whenever the block template
+/// of a slot changes, so that a payload is already available when the commitment deadline is
+/// reached.
+///
+/// Template updates are debounced: a build is only started once no new update has been
+/// received for [PREBUILD_DEBOUNCE], and only the latest template is built. Updates received
+/// while a build is in progress are handled once it completes.
+#[derive(Debug, Clone)]
+pub struct PayloadPrebuilder {
+    updates_tx: mpsc::Sender<(u64, BlockTemplate)>,
+}
+
+impl PayloadPrebuilder {
+    /// Spawn the background task building payloads with the given local builder.
+    pub fn spawn(builder: LocalBuilder) -> Self {
+        let (updates_tx, updates_rx) = mpsc::channel(PREBUILD_BUFFER_SIZE);
+        tokio::spawn(run_prebuilder(builder, updates_rx));
+
+        Self { updates_tx }
+    }
+
+    /// Schedule a payload build for the given slot with the latest version of its template.
+    pub fn schedule(&self, slot: u64, template: BlockTemplate) {
+        if let Err(err) = self.updates_tx.try_send((slot, template)) {
+            error!(slot, ?err, "Failed to schedule local payload pre-build");
+        }
+    }
+}
+
+/// Receive template updates, debounce them and build the latest template.
+async fn run_prebuilder(
+    builder: LocalBuilder,
+    mut updates_rx: mpsc::Receiver<(u64, BlockTemplate)>,
+) {
+    while let Some(mut latest) = updates_rx.recv().await {
+        // Wait until the template stops changing, keeping only its latest version. If an
+        // update for another slot is received, the previous one is superseded as well: the
+        // commitment deadline will build it on the spot if needed.
+        loop {
+            match timeout(PREBUILD_DEBOUNCE, updates_rx.recv()).await {
+                Ok(Some(update)) => latest = update,
+                Ok(None) => return,
+                Err(_) => break,
+            }
+        }
+
+        let (slot, template) = latest;
+        debug!(
+            slot,
+            constraints = template.signed_constraints_list.len(),
+            "Pre-building local payload"
+        );
+
+        if let Err(err) = builder.build_new_local_payload(slot, &template).await {
+            error!(slot, ?err, "Failed to pre-build local payload");
+        }
+    }
+}
@@ -2,14 +2,12 @@ use alloy::{
     consensus::Transaction,
     primitives::{Address, TxHash, U256},
 };
-use ethereum_consensus::{
-    crypto::{KzgCommitment, KzgProof},
-    deneb::mainnet::{Blob, BlobsBundle},
-};
+use ethereum_consensus::deneb::mainnet::BlobsBundle;
 use reth_primitives::TransactionSigned;
 use std::collections::HashMap;
 use tracing::warn;
 
+use super::compat::to_consensus_blobs_bundle;
 use crate::{
     common::transactions::max_transaction_cost,
     primitives::{AccountState, FullTransaction, SignedConstraints, TransactionExt},
@@ -24,7 +22,7 @@ use crate::{
 /// - Simulate new commitment requests.
 /// - Update state every block, to invalidate old commitments.
 /// - Make sure we DO NOT accept invalid commitments in any circumstances.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct BlockTemplate {
     /// The state diffs per address given the list of commitments.
     pub(crate) state_diff: StateDiff,
@@ -67,30 +65,12 @@ impl BlockTemplate {
     /// in the constraints. Use this when building a local execution payload.
     #[inline]
     pub fn as_blobs_bundle(&self) -> BlobsBundle {
-        let (commitments, proofs, blobs) =
+        to_consensus_blobs_bundle(
             self.signed_constraints_list
                 .iter()
                 .flat_map(|sc| sc.message.transactions.iter())
-                .filter_map(|c| c.blob_sidecar())
-                .fold(
-                    (Vec::new(), Vec::new(), Vec::new()),
-                    |(mut commitments, mut proofs, mut blobs), bs| {
-                        commitments.extend(bs.commitments.iter().map(|c| {
-                            KzgCommitment::try_from(c.as_slice()).expect("both are 48 bytes")
-                        }));
-                        proofs.extend(
-                            bs.proofs.iter().map(|p| {
-                                KzgProof::try_from(p.as_slice()).expect("both are 48 bytes")
-                            }),
-                        );
-                        blobs.extend(bs.blobs.iter().map(|b| {
-                            Blob::try_from(b.as_slice()).expect("both are 131_072 bytes")
-                        }));
-                        (commitments, proofs, blobs)
-                    },
-                );
-
-        BlobsBundle { commitments, proofs, blobs }
+                .filter_map(|c| c.blob_sidecar()),
+        )
     }
 
     /// Returns the length of the transactions in the block template.
@@ -136,8 +116,9 @@ impl BlockTemplate {
         self.signed_constraints_list.push(constraints);
     }
 
-    /// Remove all signed constraints at the specified index and updates the state diff
-    fn remove_constraints_at_index(&mut self, index: usize) {
+    /// Remove all signed constraints at the specified index and updates the state diff.
+    /// Returns the removed signed constraints.
+    fn remove_constraints_at_index(&mut self, index: usize) -> SignedConstraints {
         let constraints = self.signed_constraints_list.remove(index);
 
         for constraint in &constraints.message.transactions {
@@ -149,10 +130,13 @@ impl BlockTemplate {
                     *balance -= max_transaction_cost(constraint);
                 });
         }
+
+        constraints
     }
 
-    /// Retain removes any transactions that conflict with the given account state.
-    pub fn retain(&mut self, address: Address, state: AccountState) {
+    /// Retain removes any transactions that conflict with the given account state, and returns
+    /// the hashes of the removed transactions.
+    pub fn retain(&mut self, address: Address, state: AccountState) -> Vec<TxHash> {
         let mut indexes: Vec<usize> = Vec::new();
 
         // The preconfirmations made by such address, and the indexes of the signed constraints
@@ -191,14 +175,18 @@ impl BlockTemplate {
             indexes = constraints_with_address.iter().map(|(i, _)| *i).collect();
         }
 
+        let mut removed = Vec::new();
         for index in indexes.into_iter().rev() {
-            self.remove_constraints_at_index(index);
+            let constraints = self.remove_constraints_at_index(index);
+            removed.extend(constraints.message.transactions.iter().map(|tx| *tx.hash()));
         }
+
+        removed
     }
 }
 
 /// StateDiff tracks the intermediate changes to the state according to the block template.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct StateDiff {
     /// Map of diffs per address. Each diff is a tuple of the nonce and balance diff
     /// that should be applied to the current state.
@@ -1,24 +1,16 @@
 use std::time::Duration;
 
-use alloy::{
-    contract::Error,
-    primitives::Address,
-    providers::{ProviderBuilder, RootProvider},
-    sol,
-    transports::{http::Http, RpcError},
-};
+use alloy::{contract::Error, primitives::Address, sol, transports::RpcError};
 use ethereum_consensus::primitives::BlsPublicKey;
 use eyre::{bail, Context};
-use reqwest::{Client, Url};
+use reqwest::Url;
 use serde::Serialize;
 use tracing::{debug, warn};
 
-use BoltManagerContract::{
-    BoltManagerContractErrors, BoltManagerContractInstance, ProposerStatus, ValidatorDoesNotExist,
-};
+use BoltManagerContract::{BoltManagerContractErrors, ProposerStatus, ValidatorDoesNotExist};
 
 use super::utils::{self, CompressedHash};
-use crate::config::chain::Chain;
+use crate::{config::chain::Chain, state::FailoverStateClient};
 
 /// Maximum number of keys to fetch from the EL node in a single query.
 const MAX_CHUNK_SIZE: usize = 100;
@@ -26,8 +18,14 @@ const MAX_CHUNK_SIZE: usize = 100;
 const MAX_RETRIES: usize = 20;
 
 /// A wrapper over a BoltManagerContract that exposes various utility methods.
+///
+/// Calls are sent through a [FailoverStateClient], so that they fail over between the
+/// execution clients like the other execution API requests of the sidecar.
 #[derive(Debug, Clone)]
-pub struct BoltManager(BoltManagerContractInstance<Http<Client>, RootProvider<Http<Client>>>);
+pub struct BoltManager {
+    execution_api: FailoverStateClient,
+    address: Address,
+}
 
 impl BoltManager {
     /// Creates a new BoltRegistry instance. Returns `None` if a canonical BoltManager contract is
@@ -36,15 +34,13 @@ impl BoltManager {
     /// TODO: change after https://github.com/chainbound/bolt/issues/343 is completed
     pub fn from_chain<U: Into<Url>>(execution_client_url: U, chain: Chain) -> Option<Self> {
         let address = chain.manager_address()?;
-        Some(Self::from_address(execution_client_url, address))
+        let execution_api = FailoverStateClient::from_urls(vec![execution_client_url.into()]);
+        Some(Self::from_address(execution_api, address))
     }
 
     /// Creates a new BoltRegistry instance.
-    pub fn from_address<U: Into<Url>>(execution_client_url: U, manager_address: Address) -> Self {
-        let provider = ProviderBuilder::new().on_http(execution_client_url.into());
-        let registry = BoltManagerContract::new(manager_address, provider);
-
-        Self(registry)
+    pub fn from_address(execution_api: FailoverStateClient, manager_address: Address) -> Self {
+        Self { execution_api, address: manager_address }
     }
 
     /// Verify the provided validator public keys are registered in Bolt and are active
@@ -81,7 +77,16 @@ impl BoltManager {
                     bail!("Max retries reached when fetching proposer statuses from EL client");
                 }
 
-                match self.0.getProposerStatuses(hashes_chunk.clone()).call().await {
+                let res = self
+                    .execution_api
+                    .with_execution_client(|client| {
+                        let registry = BoltManagerContract::new(self.address, (**client).clone());
+                        let hashes = hashes_chunk.clone();
+                        async move { registry.getProposerStatuses(hashes).call().await }
+                    })
+                    .await;
+
+                match res {
                     Ok(data) => break data,
                     Err(Error::TransportError(RpcError::Transport(transport_err))) => {
                         // `retry_with_backoff_if` is not used here because we need to check
@@ -1,12 +1,24 @@
-use std::{fmt::Debug, ops::Deref};
+use std::{fmt::Debug, ops::Deref, sync::Arc, time::Duration};
 
 use alloy::{
     primitives::{Address, B256},
     rpc::types::Withdrawal,
 };
 use beacon_api_client::{BlockId, StateId};
+use ethereum_consensus::serde::as_str;
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
+use tokio::{sync::watch, task::JoinHandle, time::interval};
+use tracing::{debug, error, info, warn};
+
+use crate::telemetry::ApiMetrics;
+
+/// The interval between health checks of the beacon nodes.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The maximum number of slots a beacon node can lag behind the most advanced one
+/// before being considered unhealthy.
+const MAX_HEAD_SLOT_LAG: u64 = 2;
 
 /// Errors that can occur while interacting with the beacon API.
 #[derive(Debug, thiserror::Error)]
@@ -38,11 +50,21 @@ pub type BeaconClientResult<T> = Result<T, BeaconClientError>;
 ///
 /// For this reason, this struct is essentially a wrapper around [beacon_api_client::Client]
 /// with added custom error handling and methods.
+///
+/// Multiple beacon nodes can be provided, in order of priority: requests are sent to the
+/// active one, which is replaced by the first healthy node when it becomes unhealthy.
+/// See [BeaconClient::spawn_health_checks].
 #[derive(Clone)]
 pub struct BeaconClient {
     client: reqwest::Client,
-    beacon_rpc_url: Url,
+    nodes: Arc<[BeaconNode]>,
+    /// The index of the active node in `nodes`.
+    active: Arc<watch::Sender<usize>>,
+}
 
+/// A beacon node the [BeaconClient] can send requests to.
+struct BeaconNode {
+    url: Url,
     // Inner client re-exported from the beacon_api_client crate.
     // By wrapping this, we can automatically use its existing methods
     // by dereferencing it. This allows us to extend its API.
@@ -53,15 +75,108 @@ impl Deref for BeaconClient {
     type Target = beacon_api_client::mainnet::Client;
 
     fn deref(&self) -> &Self::Target {
-        &self.inner
+        &self.nodes[self.active_index()].inner
     }
 }
 
 impl BeaconClient {
     /// Create a new [BeaconClient] instance with the given beacon RPC URL.
     pub fn new(beacon_rpc_url: Url) -> Self {
-        let inner = beacon_api_client::mainnet::Client::new(beacon_rpc_url.clone());
-        Self { client: reqwest::Client::new(), beacon_rpc_url, inner }
+        Self::from_urls(vec![beacon_rpc_url])
+    }
+
+    /// Create a new [BeaconClient] instance with the given beacon RPC URLs, in order of
+    /// priority. The first one is the active node until it becomes unhealthy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no URL is provided.
+    pub fn from_urls(beacon_rpc_urls: Vec<Url>) -> Self {
+        assert!(!beacon_rpc_urls.is_empty(), "at least one beacon RPC URL is required");
+
+        let nodes = beacon_rpc_urls
+            .into_iter()
+            .map(|url| BeaconNode {
+                inner: beacon_api_client::mainnet::Client::new(url.clone()),
+                url,
+            })
+            .collect();
+
+        let (active, _) = watch::channel(0);
+        Self { client: reqwest::Client::new(), nodes, active: Arc::new(active) }
+    }
+
+    /// Returns the URL of the active beacon node.
+    pub fn active_url(&self) -> &Url {
+        &self.nodes[self.active_index()].url
+    }
+
+    /// Subscribe to changes of the active beacon node. The received value is the index of the
+    /// new active node, whose URL is returned by [BeaconClient::active_url].
+    pub fn subscribe_active_node(&self) -> watch::Receiver<usize> {
+        self.active.subscribe()
+    }
+
+    fn active_index(&self) -> usize {
+        *self.active.borrow()
+    }
+
+    /// Spawn a background task that periodically checks the sync status of all the beacon
+    /// nodes, and fails over to the first healthy node (in order of priority) when the active
+    /// one is unhealthy.
+    ///
+    /// A node is healthy if it responds, is not syncing, its execution client is online and
+    /// its head is at most [MAX_HEAD_SLOT_LAG] slots behind the most advanced node.
+    pub fn spawn_health_checks(&self) -> JoinHandle<()> {
+        let this = self.clone();
+        ApiMetrics::set_active_beacon_node(this.active_index(), true);
+
+        tokio::spawn(async move {
+            let mut ticker = interval(HEALTH_CHECK_INTERVAL);
+            loop {
+                ticker.tick().await;
+
+                let statuses = futures::future::join_all(
+                    this.nodes.iter().map(|node| this.get_sync_status(&node.url)),
+                )
+                .await
+                .into_iter()
+                .zip(this.nodes.iter())
+                .map(|(res, node)| match res {
+                    Ok(status) => Some(status),
+                    Err(err) => {
+                        debug!(?err, url = %node.url, "Failed to fetch beacon node sync status");
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+
+                let current = this.active_index();
+                let Some(next) = select_active_node(current, &statuses) else {
+                    error!("No healthy beacon node available, keeping the active one");
+                    continue;
+                };
+
+                if next != current {
+                    let (from, to) = (&this.nodes[current].url, &this.nodes[next].url);
+                    warn!(%from, %to, "Active beacon node is unhealthy, failing over");
+
+                    ApiMetrics::set_active_beacon_node(current, false);
+                    ApiMetrics::set_active_beacon_node(next, true);
+                    ApiMetrics::increment_beacon_node_failovers();
+                    this.active.send_replace(next);
+                    info!(url = %to, "Switched active beacon node");
+                }
+            }
+        })
+    }
+
+    /// Fetch the sync status of the beacon node at the given URL.
+    async fn get_sync_status(&self, url: &Url) -> BeaconClientResult<SyncStatus> {
+        let url = url.join("/eth/v1/node/syncing").map_err(|_| BeaconClientError::Url)?;
+
+        let res = self.client.get(url).timeout(HEALTH_CHECK_INTERVAL).send().await?;
+        Ok(res.error_for_status()?.json::<ResponseData<SyncStatus>>().await?.data)
     }
 
     /// Fetch the previous RANDAO value from the beacon node.
@@ -70,7 +185,7 @@ impl BeaconClient {
         // so we implement it manually here.
 
         let url = self
-            .beacon_rpc_url
+            .active_url()
             .join("/eth/v1/beacon/states/head/randao")
             .map_err(|_| BeaconClientError::Url)?;
 
@@ -87,7 +202,7 @@ impl BeaconClient {
     ///
     /// This function also maps the return type into [alloy::rpc::types::Withdrawal]s.
     pub async fn get_expected_withdrawals_at_head(&self) -> BeaconClientResult<Vec<Withdrawal>> {
-        let res = self.inner.get_expected_withdrawals(StateId::Head, None).await?;
+        let res = self.get_expected_withdrawals(StateId::Head, None).await?;
 
         let mut withdrawals = Vec::with_capacity(res.len());
         for w in res {
@@ -104,9 +219,77 @@ impl BeaconClient {
 
     /// Fetch the parent beacon block root from the beacon chain.
     pub async fn get_parent_beacon_block_root(&self) -> BeaconClientResult<B256> {
-        let res = self.inner.get_beacon_block_root(BlockId::Head).await?;
+        let res = self.get_beacon_block_root(BlockId::Head).await?;
         Ok(B256::from_slice(res.as_slice()))
     }
+
+    /// Fetch the execution block number and hash of the beacon block with the given root, from
+    /// its execution payload header.
+    pub async fn get_execution_block(&self, block_root: B256) -> BeaconClientResult<(u64, B256)> {
+        let url = self
+            .active_url()
+            .join(&format!("/eth/v1/beacon/blinded_blocks/{block_root}"))
+            .map_err(|_| BeaconClientError::Url)?;
+
+        #[derive(Deserialize)]
+        struct Inner {
+            message: Message,
+        }
+
+        #[derive(Deserialize)]
+        struct Message {
+            body: Body,
+        }
+
+        #[derive(Deserialize)]
+        struct Body {
+            execution_payload_header: PayloadHeader,
+        }
+
+        #[derive(Deserialize)]
+        struct PayloadHeader {
+            #[serde(with = "as_str")]
+            block_number: u64,
+            block_hash: B256,
+        }
+
+        let res = self.client.get(url).send().await?.error_for_status()?;
+        let header =
+            res.json::<ResponseData<Inner>>().await?.data.message.body.execution_payload_header;
+        Ok((header.block_number, header.block_hash))
+    }
+}
+
+/// The sync status of a beacon node, as returned by the `/eth/v1/node/syncing` endpoint.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+struct SyncStatus {
+    #[serde(with = "as_str")]
+    head_slot: u64,
+    is_syncing: bool,
+    #[serde(default)]
+    el_offline: bool,
+}
+
+impl SyncStatus {
+    fn is_healthy(&self, best_head_slot: u64) -> bool {
+        !self.is_syncing && !self.el_offline && self.head_slot + MAX_HEAD_SLOT_LAG >= best_head_slot
+    }
+}
+
+/// Select the beacon node to use given the sync statuses of all the nodes (or `None` if the
+/// status could not be fetched): the current one if it is healthy, otherwise the first healthy
+/// one in order of priority. Returns `None` if no node is healthy.
+fn select_active_node(current: usize, statuses: &[Option<SyncStatus>]) -> Option<usize> {
+    let best_head_slot = statuses.iter().flatten().map(|s| s.head_slot).max()?;
+    let is_healthy = |index: usize| {
+        statuses.get(index).copied().flatten().is_some_and(|s| s.is_healthy(best_head_slot))
+    };
+
+    if is_healthy(current) {
+        return Some(current);
+    }
+
+    (0..statuses.len()).find(|&index| is_healthy(index))
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -116,7 +299,11 @@ struct ResponseData<T> {
 
 impl Debug for BeaconClient {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("BeaconClient").field("beacon_rpc_url", &self.beacon_rpc_url).finish()
+        let urls = self.nodes.iter().map(|node| node.url.as_str()).collect::<Vec<_>>();
+        f.debug_struct("BeaconClient")
+            .field("beacon_rpc_urls", &urls)
+            .field("active_url", &self.active_url().as_str())
+            .finish()
     }
 }
 
@@ -126,6 +313,24 @@ mod tests {
     use crate::test_util::try_get_beacon_api_url;
     use tracing::warn;
 
+    #[test]
+    fn test_select_active_node() {
+        let status =
+            |head_slot, is_syncing| Some(SyncStatus { head_slot, is_syncing, el_offline: false });
+
+        // The active node is kept while healthy, even if a node with higher priority is
+        assert_eq!(select_active_node(1, &[status(100, false), status(99, false)]), Some(1));
+
+        // Unreachable, syncing or lagging nodes are replaced by the first healthy one
+        assert_eq!(select_active_node(0, &[None, status(100, false), status(100, false)]), Some(1));
+        assert_eq!(select_active_node(0, &[status(100, true), None, status(100, false)]), Some(2));
+        assert_eq!(select_active_node(0, &[status(90, false), status(100, false)]), Some(1));
+
+        // No healthy node
+        assert_eq!(select_active_node(0, &[None, status(100, true)]), None);
+        assert_eq!(select_active_node(0, &[None, None]), None);
+    }
+
     #[tokio::test]
     async fn test_get_prev_randao() -> eyre::Result<()> {
         let _ = tracing_subscriber::fmt::try_init();
@@ -4,8 +4,7 @@ use alloy::hex;
 use axum::http::StatusCode;
 use beacon_api_client::VersionedValue;
 use ethereum_consensus::{
-    builder::SignedValidatorRegistration, crypto::PublicKey as BlsPublicKey,
-    deneb::mainnet::SignedBlindedBeaconBlock, Fork,
+    builder::SignedValidatorRegistration, crypto::PublicKey as BlsPublicKey, Fork,
 };
 use reqwest::Url;
 use tracing::{error, span_enabled, trace, warn, Level};
@@ -13,6 +12,7 @@ use tracing::{error, span_enabled, trace, warn, Level};
 use crate::{
     api::{
         builder::GetHeaderParams,
+        encoding::{SignedBlindedBlock, CONSENSUS_VERSION_HEADER, JSON_CONTENT_TYPE},
         spec::{
             BuilderApi, BuilderApiError, ConstraintsApi, ErrorResponse, DELEGATE_PATH,
             GET_PAYLOAD_PATH, REGISTER_VALIDATORS_PATH, REVOKE_PATH, STATUS_PATH,
@@ -20,8 +20,8 @@ use crate::{
         },
     },
     primitives::{
-        BatchedSignedConstraints, GetPayloadResponse, SignedBuilderBid, SignedDelegation,
-        SignedRevocation,
+        BatchedSignedConstraints, GetPayloadResponse, InclusionProofs, SignedBuilderBidWithProofs,
+        SignedDelegation, SignedRevocation, VersionedSignedBuilderBid,
     },
 };
 
@@ -170,7 +170,7 @@ impl BuilderApi for ConstraintsClient {
     async fn get_header(
         &self,
         params: GetHeaderParams,
-    ) -> Result<SignedBuilderBid, BuilderApiError> {
+    ) -> Result<VersionedValue<VersionedSignedBuilderBid>, BuilderApiError> {
         let parent_hash = hex::encode_prefixed(params.parent_hash.as_ref());
         let public_key = hex::encode_prefixed(params.public_key.as_ref());
 
@@ -189,21 +189,27 @@ impl BuilderApi for ConstraintsClient {
             return Err(BuilderApiError::FailedGettingHeader(error));
         }
 
-        let header = response.json::<SignedBuilderBid>().await?;
+        let header = response.json::<VersionedValue<serde_json::Value>>().await?;
+        let bid = decode_bid(header.version, header.data)?;
 
-        Ok(header)
+        Ok(VersionedValue { version: header.version, data: bid, meta: header.meta })
     }
 
     /// Implements: <https://ethereum.github.io/builder-specs/#/Builder/submitBlindedBlock>
+    ///
+    /// The signed blinded block is forwarded in its original encoding, along with its fork
+    /// in the `Eth-Consensus-Version` header. The payload is always requested as JSON.
     async fn get_payload(
         &self,
-        signed_block: SignedBlindedBeaconBlock,
+        signed_block: SignedBlindedBlock,
     ) -> Result<GetPayloadResponse, BuilderApiError> {
         let response = self
             .client
             .post(self.endpoint(GET_PAYLOAD_PATH))
-            .header("content-type", "application/json")
-            .body(serde_json::to_vec(&signed_block)?)
+            .header("content-type", signed_block.encoding.content_type())
+            .header("accept", JSON_CONTENT_TYPE)
+            .header(CONSENSUS_VERSION_HEADER, signed_block.version.to_string())
+            .body(signed_block.body)
             .send()
             .await?;
 
@@ -243,7 +249,7 @@ impl ConstraintsApi for ConstraintsClient {
     async fn get_header_with_proofs(
         &self,
         params: GetHeaderParams,
-    ) -> Result<VersionedValue<SignedBuilderBid>, BuilderApiError> {
+    ) -> Result<VersionedValue<SignedBuilderBidWithProofs>, BuilderApiError> {
         let parent_hash = hex::encode_prefixed(params.parent_hash.as_ref());
         let public_key = hex::encode_prefixed(params.public_key.as_ref());
 
@@ -266,13 +272,18 @@ impl ConstraintsApi for ConstraintsClient {
             return Err(BuilderApiError::FailedGettingHeader(error));
         }
 
-        let header = response.json::<VersionedValue<SignedBuilderBid>>().await?;
-
-        if !matches!(header.version, Fork::Deneb) {
-            return Err(BuilderApiError::InvalidFork(header.version.to_string()));
+        let mut header = response.json::<VersionedValue<serde_json::Value>>().await?;
+        let proofs = match header.data.as_object_mut().and_then(|data| data.remove("proofs")) {
+            Some(proofs) => serde_json::from_value(proofs)?,
+            None => InclusionProofs::default(),
         };
+        let bid = decode_bid(header.version, header.data)?;
 
-        Ok(header)
+        Ok(VersionedValue {
+            version: header.version,
+            data: SignedBuilderBidWithProofs { bid, proofs },
+            meta: header.meta,
+        })
     }
 
     async fn delegate(&self, signed_data: &[SignedDelegation]) -> Result<(), BuilderApiError> {
@@ -310,11 +321,22 @@ impl ConstraintsApi for ConstraintsClient {
     }
 }
 
+/// Decodes a bid according to the version of the response, rejecting unsupported forks.
+fn decode_bid(
+    version: Fork,
+    data: serde_json::Value,
+) -> Result<VersionedSignedBuilderBid, BuilderApiError> {
+    VersionedSignedBuilderBid::from_json(version, data)?
+        .ok_or_else(|| BuilderApiError::InvalidFork(version.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
+    use ethereum_consensus::Fork;
     use reqwest::Url;
 
-    use super::ConstraintsClient;
+    use super::{decode_bid, ConstraintsClient};
+    use crate::primitives::{SignedBuilderBidElectra, VersionedSignedBuilderBid};
 
     #[test]
     fn test_join_endpoints() {
@@ -329,4 +351,18 @@ mod tests {
             Url::parse("http://localhost:8080/eth/v1/builder/validators").unwrap()
         );
     }
+
+    #[test]
+    fn test_decode_bid_by_version() {
+        let data = serde_json::to_value(SignedBuilderBidElectra::default()).unwrap();
+
+        let bid = decode_bid(Fork::Electra, data.clone()).unwrap();
+        assert!(matches!(bid, VersionedSignedBuilderBid::Electra(_)));
+
+        // The version of the response decides the fork, not the shape of the bid
+        let bid = decode_bid(Fork::Deneb, data.clone()).unwrap();
+        assert!(matches!(bid, VersionedSignedBuilderBid::Deneb(_)));
+
+        assert!(decode_bid(Fork::Capella, data).is_err());
+    }
 }
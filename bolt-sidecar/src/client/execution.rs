@@ -1,17 +1,23 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::{Deref, DerefMut},
+};
 
 use alloy::{
+    consensus::Header,
     eips::BlockNumberOrTag,
     primitives::{Address, Bytes, TxHash, B256, U256, U64},
     providers::{ProviderBuilder, RootProvider},
     rpc::{
         client::{BatchRequest, ClientBuilder, RpcClient},
-        types::{Block, FeeHistory, TransactionReceipt},
+        types::{Block, FeeHistory, Transaction, TransactionReceipt, TransactionRequest},
     },
     transports::{http::Http, TransportErrorKind, TransportResult},
 };
 use futures::{stream::FuturesUnordered, StreamExt};
 use reqwest::{Client, Url};
+use serde::Deserialize;
+use serde_json::json;
 
 use crate::primitives::AccountState;
 
@@ -142,6 +148,24 @@ impl ExecutionClient {
         self.rpc.request("eth_getBlockByNumber", (tag, full)).await
     }
 
+    /// Get the hash of the block with the given tag, e.g. the safe or finalized one.
+    pub async fn get_block_hash(&self, tag: BlockNumberOrTag) -> TransportResult<B256> {
+        let block: Block = self.rpc.request("eth_getBlockByNumber", (tag, false)).await?;
+
+        Ok(block.header.hash)
+    }
+
+    /// Get the pending transactions in the txpool, grouped by sender and nonce.
+    ///
+    /// Reference: https://geth.ethereum.org/docs/interacting-with-geth/rpc/ns-txpool#txpool-content
+    pub async fn get_txpool_pending(
+        &self,
+    ) -> TransportResult<HashMap<Address, BTreeMap<String, Transaction>>> {
+        let content: TxpoolContent = self.rpc.request("txpool_content", ()).await?;
+
+        Ok(content.pending)
+    }
+
     /// Send a raw transaction to the network.
     #[allow(unused)]
     pub async fn send_raw_transaction(&self, raw: Bytes) -> TransportResult<B256> {
@@ -174,6 +198,60 @@ impl ExecutionClient {
             .map(|r| r.ok())
             .collect())
     }
+
+    /// Simulate the given calls in a new block with the values of the given header, on top of
+    /// the latest block, and return the gas used by each of them.
+    ///
+    /// Reference: https://github.com/ethereum/execution-apis/blob/main/src/eth/execute.yaml
+    pub async fn simulate_gas_used(
+        &self,
+        header: &Header,
+        calls: Vec<TransactionRequest>,
+    ) -> TransportResult<Vec<u64>> {
+        let payload = json!({
+            "blockStateCalls": [{
+                "blockOverrides": {
+                    "number": U64::from(header.number),
+                    "time": U64::from(header.timestamp),
+                    "gasLimit": U64::from(header.gas_limit),
+                    "feeRecipient": header.beneficiary,
+                    "prevRandao": header.mix_hash,
+                    "baseFeePerGas": U64::from(header.base_fee_per_gas.unwrap_or_default()),
+                },
+                "calls": calls,
+            }],
+            "validation": true,
+        });
+
+        let blocks: Vec<SimulatedBlock> =
+            self.rpc.request("eth_simulateV1", (payload, BlockNumberOrTag::Latest)).await?;
+
+        Ok(blocks
+            .into_iter()
+            .flat_map(|block| block.calls)
+            .map(|call| call.gas_used.to())
+            .collect())
+    }
+}
+
+/// A block of the `eth_simulateV1` response. Only the results of the calls are deserialized.
+#[derive(Debug, Deserialize)]
+struct SimulatedBlock {
+    calls: Vec<SimulatedCall>,
+}
+
+/// The result of a call simulated with `eth_simulateV1`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SimulatedCall {
+    gas_used: U64,
+}
+
+/// The response of the `txpool_content` method. Only the pending (executable) transactions
+/// are deserialized.
+#[derive(Debug, Default, Deserialize)]
+struct TxpoolContent {
+    pending: HashMap<Address, BTreeMap<String, Transaction>>,
 }
 
 #[cfg(test)]
@@ -1,15 +1,23 @@
 use core::fmt;
 use std::{
     fmt::{Display, Formatter},
-    ops::Deref,
     time::Duration,
 };
 
-use alloy::primitives::{address, Address};
+use alloy::{
+    eips::{eip4844::MAX_BLOBS_PER_BLOCK, eip7691::MAX_BLOBS_PER_BLOCK_ELECTRA},
+    primitives::{address, Address},
+};
 use clap::{Args, ValueEnum};
-use ethereum_consensus::deneb::{compute_fork_data_root, Root};
+use ethereum_consensus::{
+    deneb::{compute_fork_data_root, Root},
+    phase0::mainnet::SLOTS_PER_EPOCH,
+    Fork,
+};
 use serde::Deserialize;
 
+use super::network::NetworkSpec;
+
 /// Default commitment deadline duration.
 ///
 /// The sidecar will stop accepting new commitments for the next block
@@ -32,6 +40,8 @@ pub const DEFAULT_CHAIN_CONFIG: ChainConfig = ChainConfig {
     commitment_deadline: DEFAULT_COMMITMENT_DEADLINE_IN_MILLIS,
     slot_time: DEFAULT_SLOT_TIME_IN_SECONDS,
     enable_unsafe_lookahead: false,
+    electra_fork_epoch: None,
+    network: None,
 };
 
 /// The address of the canonical BoltManager contract for the Holesky chain.
@@ -39,12 +49,27 @@ pub const DEFAULT_CHAIN_CONFIG: ChainConfig = ChainConfig {
 /// https://holesky.etherscan.io/address/0x440202829b493F9FF43E730EB5e8379EEa3678CF
 pub const MANAGER_ADDRESS_HOLESKY: Address = address!("440202829b493F9FF43E730EB5e8379EEa3678CF");
 
+/// Error returned for slots outside of the forks supported by the sidecar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("slot {0} is not in the Deneb or Electra fork, the only ones supported")]
+pub struct UnsupportedForkError(pub u64);
+
 /// Configuration for the chain the sidecar is running on.
 #[derive(Debug, Clone, Copy, Args, Deserialize)]
 pub struct ChainConfig {
     /// Chain on which the sidecar is running
     #[clap(long, env = "BOLT_SIDECAR_CHAIN", default_value_t = Chain::Mainnet)]
     pub(crate) chain: Chain,
+    /// Path to a consensus-spec `config.yaml` defining a custom network. If provided, its
+    /// chain ID, fork versions and epochs, slot time, slots per epoch and BoltManager address
+    /// override the ones of the selected [Chain].
+    #[clap(
+        long = "chain-config-path",
+        value_name = "PATH",
+        env = "BOLT_SIDECAR_CHAIN_CONFIG_PATH",
+        value_parser = |path: &str| NetworkSpec::from_config_path(path)
+    )]
+    pub(crate) network: Option<NetworkSpec>,
     /// The deadline in the slot at which the sidecar will stop accepting
     /// new commitments for the next block (parsed as milliseconds).
     #[clap(
@@ -55,6 +80,8 @@ pub struct ChainConfig {
     pub(crate) commitment_deadline: u64,
     /// The slot time duration in seconds. If provided,
     /// it overrides the default for the selected [Chain].
+    ///
+    /// Ignored if a custom network config is provided.
     #[clap(
         long,
         env = "BOLT_SIDECAR_SLOT_TIME",
@@ -69,6 +96,10 @@ pub struct ChainConfig {
         default_value_t = DEFAULT_CHAIN_CONFIG.enable_unsafe_lookahead
     )]
     pub(crate) enable_unsafe_lookahead: bool,
+    /// The epoch at which the Electra fork is activated. If provided,
+    /// it overrides the fork schedule of the selected [Chain].
+    #[clap(long, env = "BOLT_SIDECAR_ELECTRA_FORK_EPOCH")]
+    pub(crate) electra_fork_epoch: Option<u64>,
 }
 
 impl Default for ChainConfig {
@@ -77,14 +108,6 @@ impl Default for ChainConfig {
     }
 }
 
-impl Deref for ChainConfig {
-    type Target = Chain;
-
-    fn deref(&self) -> &Self::Target {
-        &self.chain
-    }
-}
-
 /// Supported chains for the sidecar
 #[derive(Debug, Clone, Copy, ValueEnum, Deserialize)]
 #[clap(rename_all = "kebab_case")]
@@ -127,6 +150,39 @@ impl Chain {
         }
     }
 
+    /// Get the epoch at which the Deneb fork is activated on the given chain.
+    ///
+    /// Reference: <https://github.com/eth-clients/mainnet/blob/main/metadata/config.yaml>
+    pub const fn deneb_fork_epoch(&self) -> u64 {
+        match self {
+            Self::Mainnet => 269568,
+            Self::Holesky => 29696,
+            Self::Helder | Self::Kurtosis => 0,
+        }
+    }
+
+    /// Get the epoch at which the Electra fork is activated on the given chain, if scheduled.
+    ///
+    /// Reference: <https://github.com/eth-clients/mainnet/blob/main/metadata/config.yaml>
+    pub const fn electra_fork_epoch(&self) -> Option<u64> {
+        match self {
+            Self::Mainnet => Some(364032),
+            Self::Holesky => Some(115968),
+            Self::Helder | Self::Kurtosis => None,
+        }
+    }
+
+    /// Get the epoch at which the Fulu fork is activated on the given chain, if scheduled.
+    ///
+    /// Reference: <https://github.com/eth-clients/mainnet/blob/main/metadata/config.yaml>
+    pub const fn fulu_fork_epoch(&self) -> Option<u64> {
+        match self {
+            Self::Mainnet => Some(411392),
+            Self::Holesky => Some(165120),
+            Self::Helder | Self::Kurtosis => None,
+        }
+    }
+
     /// Returns the address of the canonical BoltManager contract for a given chain, if present
     pub const fn manager_address(&self) -> Option<Address> {
         match self {
@@ -143,9 +199,42 @@ impl Display for Chain {
 }
 
 impl ChainConfig {
+    /// Get the chain ID of the network.
+    pub fn id(&self) -> u64 {
+        self.network.map_or_else(|| self.chain.id(), |network| network.chain_id)
+    }
+
+    /// Get the name of the network.
+    pub fn name(&self) -> &'static str {
+        if self.network.is_some() {
+            "custom"
+        } else {
+            self.chain.name()
+        }
+    }
+
+    /// Get the genesis fork version of the network.
+    pub fn fork_version(&self) -> [u8; 4] {
+        self.network
+            .map_or_else(|| self.chain.fork_version(), |network| network.genesis_fork_version)
+    }
+
+    /// Returns the address of the canonical BoltManager contract of the network, if present.
+    pub fn manager_address(&self) -> Option<Address> {
+        match self.network {
+            Some(network) => network.manager_address,
+            None => self.chain.manager_address(),
+        }
+    }
+
     /// Get the slot time for the given chain in seconds.
     pub fn slot_time(&self) -> u64 {
-        self.slot_time
+        self.network.map_or(self.slot_time, |network| network.seconds_per_slot)
+    }
+
+    /// Get the number of slots per epoch of the network.
+    pub fn slots_per_epoch(&self) -> u64 {
+        self.network.map_or(SLOTS_PER_EPOCH, |network| network.slots_per_epoch)
     }
 
     /// Get the domain for signing application-builder messages on the given chain.
@@ -158,6 +247,53 @@ impl ChainConfig {
         self.compute_domain_from_mask(COMMIT_BOOST_DOMAIN_MASK)
     }
 
+    /// Get the epoch at which the Deneb fork is activated.
+    pub fn deneb_fork_epoch(&self) -> u64 {
+        self.network
+            .map_or_else(|| self.chain.deneb_fork_epoch(), |network| network.deneb_fork_epoch)
+    }
+
+    /// Get the epoch at which the Electra fork is activated, if scheduled.
+    pub fn electra_fork_epoch(&self) -> Option<u64> {
+        self.electra_fork_epoch.or_else(|| match self.network {
+            Some(network) => network.electra_fork_epoch,
+            None => self.chain.electra_fork_epoch(),
+        })
+    }
+
+    /// Get the epoch at which the Fulu fork is activated, if scheduled.
+    pub fn fulu_fork_epoch(&self) -> Option<u64> {
+        match self.network {
+            Some(network) => network.fulu_fork_epoch,
+            None => self.chain.fulu_fork_epoch(),
+        }
+    }
+
+    /// Get the fork that is active at the given slot, according to the fork schedule.
+    ///
+    /// Returns an error for slots before the Deneb fork or from the Fulu fork onwards,
+    /// as the sidecar only supports the Deneb and Electra forks.
+    pub fn fork_at_slot(&self, slot: u64) -> Result<Fork, UnsupportedForkError> {
+        let epoch = slot / self.slots_per_epoch();
+
+        if epoch < self.deneb_fork_epoch() || self.fulu_fork_epoch().is_some_and(|e| epoch >= e) {
+            return Err(UnsupportedForkError(slot));
+        }
+
+        match self.electra_fork_epoch() {
+            Some(electra) if epoch >= electra => Ok(Fork::Electra),
+            _ => Ok(Fork::Deneb),
+        }
+    }
+
+    /// Get the maximum number of blobs in a block at the given slot.
+    pub fn max_blobs_per_block(&self, slot: u64) -> Result<usize, UnsupportedForkError> {
+        match self.fork_at_slot(slot)? {
+            Fork::Electra => Ok(MAX_BLOBS_PER_BLOCK_ELECTRA as usize),
+            _ => Ok(MAX_BLOBS_PER_BLOCK),
+        }
+    }
+
     /// Get the commitment deadline duration for the given chain.
     pub fn commitment_deadline(&self) -> Duration {
         Duration::from_millis(self.commitment_deadline)
@@ -167,7 +303,7 @@ impl ChainConfig {
     fn compute_domain_from_mask(&self, mask: [u8; 4]) -> [u8; 32] {
         let mut domain = [0; 32];
 
-        let fork_version = self.chain.fork_version();
+        let fork_version = self.fork_version();
 
         // Note: the application builder domain specs require the genesis_validators_root
         // to be 0x00 for any out-of-protocol message. The commit-boost domain follows the
@@ -241,4 +377,57 @@ mod tests {
         let kurtosis = ChainConfig::kurtosis(0, 0);
         assert_eq!(kurtosis.application_builder_domain(), BUILDER_DOMAIN_KURTOSIS);
     }
+
+    #[test]
+    fn test_custom_network() {
+        use super::{Chain, ChainConfig};
+        use crate::config::NetworkSpec;
+
+        let network = NetworkSpec {
+            chain_id: 17000,
+            genesis_fork_version: [1, 1, 112, 0],
+            deneb_fork_epoch: 0,
+            electra_fork_epoch: Some(10),
+            fulu_fork_epoch: None,
+            seconds_per_slot: 6,
+            slots_per_epoch: 8,
+            deposit_contract_address: Default::default(),
+            manager_address: None,
+        };
+        let custom =
+            ChainConfig { chain: Chain::Mainnet, network: Some(network), ..Default::default() };
+
+        assert_eq!(custom.id(), 17000);
+        assert_eq!(custom.slot_time(), 6);
+        assert_eq!(custom.slots_per_epoch(), 8);
+        assert_eq!(custom.manager_address(), None);
+        assert_eq!(custom.application_builder_domain(), BUILDER_DOMAIN_HOLESKY);
+        assert_eq!(custom.fork_at_slot(80), Ok(ethereum_consensus::Fork::Electra));
+    }
+
+    #[test]
+    fn test_fork_at_slot() {
+        use ethereum_consensus::Fork;
+
+        use super::{ChainConfig, UnsupportedForkError};
+
+        let mainnet = ChainConfig::mainnet();
+        assert_eq!(mainnet.fork_at_slot(364032 * 32 - 1), Ok(Fork::Deneb));
+        assert_eq!(mainnet.fork_at_slot(364032 * 32), Ok(Fork::Electra));
+        assert_eq!(mainnet.max_blobs_per_block(364032 * 32 - 1), Ok(6));
+        assert_eq!(mainnet.max_blobs_per_block(364032 * 32), Ok(9));
+
+        // Forks other than Deneb and Electra are rejected
+        let slot = 269568 * 32 - 1;
+        assert_eq!(mainnet.fork_at_slot(slot), Err(UnsupportedForkError(slot)));
+        let slot = 411392 * 32;
+        assert_eq!(mainnet.fork_at_slot(slot), Err(UnsupportedForkError(slot)));
+        assert!(mainnet.max_blobs_per_block(slot).is_err());
+
+        // Unscheduled forks can be overridden
+        let mut kurtosis = ChainConfig::kurtosis(0, 0);
+        assert_eq!(kurtosis.fork_at_slot(u64::MAX), Ok(Fork::Deneb));
+        kurtosis.electra_fork_epoch = Some(0);
+        assert_eq!(kurtosis.fork_at_slot(0), Ok(Fork::Electra));
+    }
 }
@@ -25,11 +25,40 @@ pub struct CommitmentOpts {
         conflicts_with("port")
     )]
     pub firewall_rpcs: Option<Vec<Url>>,
+    /// Comma-separated list of priorities of the firewall RPCs, in the same order as
+    /// `firewall_rpcs`. When the same transaction is received from several RPCs, the one with
+    /// the highest priority wins. RPCs without a priority default to 0.
+    #[clap(
+        long,
+        env = "BOLT_SIDECAR_FIREWALL_RPC_PRIORITIES",
+        value_delimiter = ',',
+        requires("firewall_rpcs")
+    )]
+    pub firewall_rpc_priorities: Option<Vec<u32>>,
+    /// Port to serve the status of the firewall RPCs on, as JSON on `GET /status`.
+    #[clap(long, env = "BOLT_SIDECAR_FIREWALL_STATUS_PORT", requires("firewall_rpcs"))]
+    pub firewall_status_port: Option<u16>,
+    /// Accept inclusion requests forwarded by the firewall RPCs without the user signature.
+    /// Unsigned requests are charged to the sender of their first transaction.
+    ///
+    /// DEPRECATED: compatibility flag for RPCs that don't forward the signature yet. It will
+    /// be removed in a future release.
+    #[clap(
+        long,
+        env = "BOLT_SIDECAR_FIREWALL_ALLOW_UNSIGNED_REQUESTS",
+        default_value_t = false,
+        requires("firewall_rpcs")
+    )]
+    pub firewall_allow_unsigned_requests: bool,
     /// Secret ECDSA key used to sign commitment messages on behalf of your validators.
     /// This MUST be set to the private key of your operator address registered in a restaking
-    /// protocol.
-    #[clap(long, env = "BOLT_SIDECAR_OPERATOR_PRIVATE_KEY")]
-    pub operator_private_key: EcdsaSecretKeyWrapper,
+    /// protocol. Not required with commit-boost, which signs with its proxy ECDSA key instead.
+    #[clap(
+        long,
+        env = "BOLT_SIDECAR_OPERATOR_PRIVATE_KEY",
+        required_unless_present("commit_boost_signer_url")
+    )]
+    pub operator_private_key: Option<EcdsaSecretKeyWrapper>,
 }
 
 #[cfg(test)]
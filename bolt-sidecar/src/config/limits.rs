@@ -11,9 +11,19 @@ pub const DEFAULT_MAX_COMMITTED_GAS: u64 = 10_000_000;
 /// Default min profit to accept for a commitment.
 pub const DEFAULT_MIN_PROFIT: u64 = 2_000_000_000; // 2 Gwei
 
+/// Default min profit to accept per blob of a commitment. Blob commitments are only priced
+/// on their gas by default.
+pub const DEFAULT_MIN_PROFIT_PER_BLOB: u64 = 0;
+
 /// Default max account states size.
 pub const DEFAULT_MAX_ACCOUNT_STATES_SIZE: u64 = 1_024;
 
+/// Default max requests per second to accept from a single IP address.
+pub const DEFAULT_MAX_REQUESTS_PER_SECOND_PER_IP: u32 = 20;
+
+/// Default max commitment requests being processed at the same time.
+pub const DEFAULT_MAX_IN_FLIGHT_REQUESTS: usize = 256;
+
 /// Limits for the sidecar.
 #[cfg_attr(test, derive(PartialEq))]
 #[derive(Debug, Parser, Clone, Copy, serde::Serialize, serde::Deserialize)]
@@ -32,6 +42,14 @@ pub struct LimitsOpts {
         default_value_t = LimitsOpts::default().min_inclusion_profit
     )]
     pub min_inclusion_profit: u64,
+    /// Min profit per blob to accept a blob commitment, paid with the priority fee on top of
+    /// the min profit per gas. Blob fees are burned and don't pay the proposer.
+    #[clap(
+        long,
+        env = "BOLT_SIDECAR_MIN_PROFIT_PER_BLOB",
+        default_value_t = LimitsOpts::default().min_profit_per_blob
+    )]
+    pub min_profit_per_blob: u64,
     /// The maximum size in MiB of the [crate::state::ExecutionState] ScoreCache that holds account
     /// states. Each [crate::primitives::AccountState] is 48 bytes, its score is [usize] bytes, and
     /// its key is 20 bytes, so the default value of 1024 KiB = 1 MiB can hold around 15k account
@@ -42,6 +60,28 @@ pub struct LimitsOpts {
         default_value_t = LimitsOpts::default().max_account_states_size,
     )]
     pub max_account_states_size: NonZero<usize>,
+    /// Max requests per second to accept from a single IP address on the commitments API
+    #[clap(
+        long,
+        env = "BOLT_SIDECAR_MAX_REQUESTS_PER_SECOND_PER_IP",
+        default_value_t = LimitsOpts::default().max_requests_per_second_per_ip
+    )]
+    pub max_requests_per_second_per_ip: NonZero<u32>,
+    /// Max commitment requests to accept from a single transaction sender per slot. Disabled
+    /// if unset.
+    #[clap(long, env = "BOLT_SIDECAR_MAX_COMMITMENTS_PER_SENDER")]
+    pub max_commitments_per_sender_per_slot: Option<NonZero<usize>>,
+    /// Max committed gas to accept from a single transaction sender per slot. Disabled if
+    /// unset.
+    #[clap(long, env = "BOLT_SIDECAR_MAX_COMMITTED_GAS_PER_SENDER")]
+    pub max_committed_gas_per_sender_per_slot: Option<NonZero<u64>>,
+    /// Max commitment requests being processed at the same time
+    #[clap(
+        long,
+        env = "BOLT_SIDECAR_MAX_IN_FLIGHT_REQUESTS",
+        default_value_t = LimitsOpts::default().max_in_flight_requests
+    )]
+    pub max_in_flight_requests: NonZero<usize>,
 }
 
 impl Default for LimitsOpts {
@@ -50,7 +90,14 @@ impl Default for LimitsOpts {
             max_committed_gas_per_slot: NonZero::new(DEFAULT_MAX_COMMITTED_GAS)
                 .expect("Valid non-zero"),
             min_inclusion_profit: DEFAULT_MIN_PROFIT,
+            min_profit_per_blob: DEFAULT_MIN_PROFIT_PER_BLOB,
             max_account_states_size: NonZero::new(1_024).expect("Valid non-zero"),
+            max_requests_per_second_per_ip: NonZero::new(DEFAULT_MAX_REQUESTS_PER_SECOND_PER_IP)
+                .expect("Valid non-zero"),
+            max_commitments_per_sender_per_slot: None,
+            max_committed_gas_per_sender_per_slot: None,
+            max_in_flight_requests: NonZero::new(DEFAULT_MAX_IN_FLIGHT_REQUESTS)
+                .expect("Valid non-zero"),
         }
     }
 }
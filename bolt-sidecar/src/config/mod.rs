@@ -1,6 +1,6 @@
-use std::env;
+use std::{env, path::PathBuf};
 
-use alloy::primitives::Address;
+use alloy::primitives::{Address, U256};
 use clap::Parser;
 use eyre::bail;
 use reqwest::Url;
@@ -10,6 +10,10 @@ use serde::Deserialize;
 pub mod chain;
 pub use chain::ChainConfig;
 
+/// Custom network definitions loaded from consensus-spec config files.
+pub mod network;
+pub use network::NetworkSpec;
+
 /// Commitment related options.
 pub mod commitments;
 pub use commitments::CommitmentOpts;
@@ -26,7 +30,10 @@ use telemetry::TelemetryOpts;
 pub mod limits;
 use limits::LimitsOpts;
 
-use crate::common::secrets::{BlsSecretKeyWrapper, JwtSecretConfig};
+use crate::{
+    builder::fallback::FallbackBuildStrategy,
+    common::secrets::{BlsSecretKeyWrapper, JwtSecretConfig},
+};
 
 /// Default port for the Constraints proxy server, binded to the default port used by MEV-Boost.
 pub const DEFAULT_CONSTRAINTS_PROXY_PORT: u16 = 18550;
@@ -37,12 +44,32 @@ pub struct Opts {
     /// Commitment options for the sidecar
     #[clap(flatten)]
     pub commitment_opts: CommitmentOpts,
-    /// Execution client API URL
-    #[clap(long, env = "BOLT_SIDECAR_EXECUTION_API_URL", default_value = "http://localhost:8545")]
-    pub execution_api_url: Url,
-    /// URL for the beacon client
-    #[clap(long, env = "BOLT_SIDECAR_BEACON_API_URL", default_value = "http://localhost:5052")]
-    pub beacon_api_url: Url,
+    /// URLs for the execution clients, comma-separated and in order of priority. State, local
+    /// block building and BoltManager requests fail over to the next one if the active client
+    /// fails or lags behind the beacon head.
+    #[clap(
+        long = "execution-api-url",
+        env = "BOLT_SIDECAR_EXECUTION_API_URL",
+        value_delimiter = ',',
+        default_value = "http://localhost:8545"
+    )]
+    pub execution_api_urls: Vec<Url>,
+    /// Websocket URL of the execution client. If provided, the sidecar subscribes to new heads
+    /// and applies their state diffs to the tracked accounts as soon as they are received,
+    /// instead of fetching them again on every new beacon head. They are still fetched once per
+    /// epoch, since the diffs miss withdrawals and system calls. The execution client must
+    /// support the `debug_traceBlockByHash` method with the `prestateTracer`.
+    #[clap(long, env = "BOLT_SIDECAR_EXECUTION_WS_URL")]
+    pub execution_ws_url: Option<Url>,
+    /// URLs for the beacon clients, comma-separated and in order of priority. The first
+    /// healthy one is used, and requests fail over to the next healthy one if it goes down.
+    #[clap(
+        long = "beacon-api-url",
+        env = "BOLT_SIDECAR_BEACON_API_URL",
+        value_delimiter = ',',
+        default_value = "http://localhost:5052"
+    )]
+    pub beacon_api_urls: Vec<Url>,
     /// Execution client Engine API URL. This is needed for fallback block building and must be a
     /// synced Geth node.
     #[clap(long, env = "BOLT_SIDECAR_ENGINE_API_URL", default_value = "http://localhost:8551")]
@@ -77,6 +104,27 @@ pub struct Opts {
     /// one is created. You can generate one with the `bolt` CLI tool, using `bolt generate bls`.
     #[clap(long, env = "BOLT_SIDECAR_BUILDER_PRIVATE_KEY", default_value_t = BlsSecretKeyWrapper::random())]
     pub builder_private_key: BlsSecretKeyWrapper,
+    /// Minimum value in wei for a relay bid to be accepted. Relay bids below this value, or below
+    /// the value of the locally-built fallback block, are replaced by the fallback block.
+    #[clap(long, env = "BOLT_SIDECAR_MIN_BID", default_value_t = U256::ZERO)]
+    pub min_bid: U256,
+    /// Fill the rest of the locally-built fallback block with pending transactions from the
+    /// execution client's txpool, ordered by effective tip, after the constrained transactions.
+    /// Blob transactions fill the remaining blob space if their blobs are available from
+    /// `engine_getBlobsV1`.
+    #[clap(long, env = "BOLT_SIDECAR_FILL_FALLBACK_BLOCK", default_value_t = false)]
+    pub fill_fallback_block: bool,
+    /// The strategy used to build fallback blocks: "hints" assembles the block locally from
+    /// the engine API validation errors, "engine" lets the execution client build it with
+    /// `engine_forkchoiceUpdated` and `engine_getPayload` at the commitment deadline. The
+    /// selected strategy is never replaced by the other one if it fails.
+    #[clap(
+        long,
+        env = "BOLT_SIDECAR_FALLBACK_BUILD_STRATEGY",
+        value_enum,
+        default_value_t = FallbackBuildStrategy::Hints
+    )]
+    pub fallback_build_strategy: FallbackBuildStrategy,
     /// Unsafely disables consensus checks when validating commitments.
     ///
     /// If enabled, the sidecar will sign every commitment request with the first private key
@@ -86,6 +134,17 @@ pub struct Opts {
     /// Unsafely disables on-chain checks of validators and operator when starting the sidecar
     #[clap(long, env = "BOLT_SIDECAR_UNSAFE_DISABLE_ONCHAIN_CHECKS", default_value_t = false)]
     pub unsafe_disable_onchain_checks: bool,
+    /// Unsafely disables the stale state guard.
+    ///
+    /// If enabled, commitment requests are validated against the last known execution state
+    /// even if it failed to update to the latest head, instead of being rejected.
+    #[clap(long, env = "BOLT_SIDECAR_UNSAFE_DISABLE_STALE_STATE_GUARD", default_value_t = false)]
+    pub unsafe_disable_stale_state_guard: bool,
+    /// Path of the file to append the fulfillment audits of the slots served by the sidecar to,
+    /// as JSON lines. Audits are always served by the commitments API, but only kept in memory
+    /// for the last slots if not set.
+    #[clap(long, env = "BOLT_SIDECAR_AUDIT_LOG_PATH")]
+    pub audit_log_path: Option<PathBuf>,
     /// Operating limits for the sidecar
     #[clap(flatten)]
     pub limits: LimitsOpts,
@@ -0,0 +1,247 @@
+use std::{fs, path::Path, str::FromStr};
+
+use alloy::primitives::Address;
+use serde::Deserialize;
+use serde_yaml::Value;
+
+/// The number of slots per epoch in the `mainnet` consensus preset.
+pub const MAINNET_PRESET_SLOTS_PER_EPOCH: u64 = 32;
+
+/// The number of slots per epoch in the `minimal` consensus preset.
+pub const MINIMAL_PRESET_SLOTS_PER_EPOCH: u64 = 8;
+
+/// The value used in consensus configs for forks that are not scheduled.
+pub const FAR_FUTURE_EPOCH: u64 = u64::MAX;
+
+/// The non-standard key of the config file holding the address of the BoltManager contract.
+pub const BOLT_MANAGER_ADDRESS_KEY: &str = "BOLT_MANAGER_ADDRESS";
+
+/// Errors that can occur while loading a network definition.
+#[derive(Debug, thiserror::Error)]
+pub enum NetworkSpecError {
+    /// The config file could not be read.
+    #[error("failed to read network config file: {0}")]
+    Io(#[from] std::io::Error),
+    /// The config file is not a valid YAML mapping.
+    #[error("invalid network config: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    /// A required key is missing from the config file.
+    #[error("missing key in network config: {0}")]
+    MissingKey(&'static str),
+    /// A key of the config file has an invalid value.
+    #[error("invalid value for {key} in network config: {value}")]
+    InvalidValue {
+        /// The key of the invalid value.
+        key: &'static str,
+        /// The invalid value.
+        value: String,
+    },
+    /// The preset of the config file is not supported.
+    #[error("unsupported preset base in network config: {0}")]
+    UnsupportedPreset(String),
+}
+
+/// The definition of a custom network, loaded from a standard consensus-spec `config.yaml`.
+///
+/// Reference: <https://github.com/ethereum/consensus-specs/blob/dev/configs/mainnet.yaml>
+///
+/// The genesis time is not part of the definition, as it is fetched from the beacon node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct NetworkSpec {
+    /// The execution chain ID (`DEPOSIT_CHAIN_ID`).
+    pub chain_id: u64,
+    /// The genesis fork version, used to compute signing domains (`GENESIS_FORK_VERSION`).
+    pub genesis_fork_version: [u8; 4],
+    /// The epoch at which the Deneb fork is activated (`DENEB_FORK_EPOCH`), 0 if not set.
+    pub deneb_fork_epoch: u64,
+    /// The epoch at which the Electra fork is activated, if scheduled (`ELECTRA_FORK_EPOCH`).
+    pub electra_fork_epoch: Option<u64>,
+    /// The epoch at which the Fulu fork is activated, if scheduled (`FULU_FORK_EPOCH`).
+    pub fulu_fork_epoch: Option<u64>,
+    /// The slot time in seconds (`SECONDS_PER_SLOT`).
+    pub seconds_per_slot: u64,
+    /// The number of slots per epoch, from the `PRESET_BASE` unless `SLOTS_PER_EPOCH` is set.
+    pub slots_per_epoch: u64,
+    /// The address of the deposit contract (`DEPOSIT_CONTRACT_ADDRESS`).
+    pub deposit_contract_address: Address,
+    /// The address of the BoltManager contract, if deployed (`BOLT_MANAGER_ADDRESS`).
+    pub manager_address: Option<Address>,
+}
+
+impl NetworkSpec {
+    /// Load a network definition from the `config.yaml` file at the given path.
+    pub fn from_config_path(path: impl AsRef<Path>) -> Result<Self, NetworkSpecError> {
+        let contents = fs::read_to_string(path)?;
+        contents.parse()
+    }
+}
+
+/// The values of a consensus-spec `config.yaml` needed by the sidecar. Other values are ignored.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct ConsensusConfig {
+    preset_base: Option<String>,
+    deposit_chain_id: Option<u64>,
+    /// Fork versions are parsed as integers by YAML if they are not quoted.
+    genesis_fork_version: Option<Value>,
+    deneb_fork_epoch: Option<u64>,
+    electra_fork_epoch: Option<u64>,
+    fulu_fork_epoch: Option<u64>,
+    seconds_per_slot: Option<u64>,
+    slots_per_epoch: Option<u64>,
+    deposit_contract_address: Option<String>,
+    bolt_manager_address: Option<String>,
+}
+
+impl FromStr for NetworkSpec {
+    type Err = NetworkSpecError;
+
+    /// Parse a network definition from the contents of a `config.yaml` file.
+    fn from_str(contents: &str) -> Result<Self, Self::Err> {
+        let config: ConsensusConfig = serde_yaml::from_str(contents)?;
+
+        let slots_per_epoch = match config.slots_per_epoch {
+            Some(slots_per_epoch) => slots_per_epoch,
+            None => match config.preset_base.as_deref().unwrap_or("mainnet") {
+                "mainnet" => MAINNET_PRESET_SLOTS_PER_EPOCH,
+                "minimal" => MINIMAL_PRESET_SLOTS_PER_EPOCH,
+                other => return Err(NetworkSpecError::UnsupportedPreset(other.to_string())),
+            },
+        };
+
+        let genesis_fork_version = config
+            .genesis_fork_version
+            .ok_or(NetworkSpecError::MissingKey("GENESIS_FORK_VERSION"))?;
+        let deposit_contract_address = config
+            .deposit_contract_address
+            .ok_or(NetworkSpecError::MissingKey("DEPOSIT_CONTRACT_ADDRESS"))?;
+
+        let manager_address = match config.bolt_manager_address {
+            Some(value) => Some(parse_address(BOLT_MANAGER_ADDRESS_KEY, &value)?),
+            None => None,
+        };
+
+        Ok(Self {
+            chain_id: config
+                .deposit_chain_id
+                .ok_or(NetworkSpecError::MissingKey("DEPOSIT_CHAIN_ID"))?,
+            genesis_fork_version: parse_fork_version(
+                "GENESIS_FORK_VERSION",
+                &genesis_fork_version,
+            )?,
+            deneb_fork_epoch: config.deneb_fork_epoch.unwrap_or_default(),
+            electra_fork_epoch: config
+                .electra_fork_epoch
+                .filter(|epoch| *epoch != FAR_FUTURE_EPOCH),
+            fulu_fork_epoch: config.fulu_fork_epoch.filter(|epoch| *epoch != FAR_FUTURE_EPOCH),
+            seconds_per_slot: config
+                .seconds_per_slot
+                .ok_or(NetworkSpecError::MissingKey("SECONDS_PER_SLOT"))?,
+            slots_per_epoch,
+            deposit_contract_address: parse_address(
+                "DEPOSIT_CONTRACT_ADDRESS",
+                &deposit_contract_address,
+            )?,
+            manager_address,
+        })
+    }
+}
+
+fn invalid(key: &'static str, value: &str) -> NetworkSpecError {
+    NetworkSpecError::InvalidValue { key, value: value.to_string() }
+}
+
+fn parse_address(key: &'static str, value: &str) -> Result<Address, NetworkSpecError> {
+    value.parse().map_err(|_| invalid(key, value))
+}
+
+/// Parse a fork version, either from a hex string or from the integer it was read as.
+fn parse_fork_version(key: &'static str, value: &Value) -> Result<[u8; 4], NetworkSpecError> {
+    let version = match value {
+        Value::String(hex) => alloy::hex::decode(hex).ok().and_then(|bytes| bytes.try_into().ok()),
+        Value::Number(number) => {
+            number.as_u64().and_then(|n| u32::try_from(n).ok()).map(u32::to_be_bytes)
+        }
+        _ => None,
+    };
+
+    version.ok_or_else(|| invalid(key, &format!("{value:?}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::address;
+
+    use super::*;
+
+    const HOLESKY_CONFIG: &str = r#"
+# Extends the mainnet preset
+PRESET_BASE: 'mainnet'
+CONFIG_NAME: holesky
+
+# Genesis
+MIN_GENESIS_ACTIVE_VALIDATOR_COUNT: 16384
+GENESIS_FORK_VERSION: 0x01017000
+
+# Deneb
+DENEB_FORK_VERSION: 0x05017000
+DENEB_FORK_EPOCH: 29696
+
+# Electra
+ELECTRA_FORK_VERSION: 0x06017000
+ELECTRA_FORK_EPOCH: 115968  # Mon, Feb 24 at 21:55:12 UTC
+
+# Time parameters
+SECONDS_PER_SLOT: 12
+
+# Deposit contract
+DEPOSIT_CHAIN_ID: 17000
+DEPOSIT_NETWORK_ID: 17000
+DEPOSIT_CONTRACT_ADDRESS: 0x4242424242424242424242424242424242424242
+
+BLOB_SCHEDULE:
+  - EPOCH: 269568
+    MAX_BLOBS_PER_BLOCK: 6
+"#;
+
+    #[test]
+    fn test_parse_network_config() {
+        let spec: NetworkSpec = HOLESKY_CONFIG.parse().unwrap();
+
+        assert_eq!(spec.chain_id, 17000);
+        assert_eq!(spec.genesis_fork_version, [1, 1, 112, 0]);
+        assert_eq!(spec.deneb_fork_epoch, 29696);
+        assert_eq!(spec.electra_fork_epoch, Some(115968));
+        assert_eq!(spec.fulu_fork_epoch, None);
+        assert_eq!(spec.seconds_per_slot, 12);
+        assert_eq!(spec.slots_per_epoch, 32);
+        assert_eq!(
+            spec.deposit_contract_address,
+            address!("4242424242424242424242424242424242424242")
+        );
+        assert_eq!(spec.manager_address, None);
+
+        // Minimal preset, unscheduled Electra fork and BoltManager deployment
+        let config = HOLESKY_CONFIG
+            .replace("PRESET_BASE: 'mainnet'", "PRESET_BASE: 'minimal'")
+            .replace("115968", "18446744073709551615")
+            + "BOLT_MANAGER_ADDRESS: 0x440202829b493F9FF43E730EB5e8379EEa3678CF\n";
+        let spec: NetworkSpec = config.parse().unwrap();
+
+        assert_eq!(spec.slots_per_epoch, 8);
+        assert_eq!(spec.electra_fork_epoch, None);
+        assert_eq!(
+            spec.manager_address,
+            Some(address!("440202829b493F9FF43E730EB5e8379EEa3678CF"))
+        );
+
+        let err = HOLESKY_CONFIG.replace("DEPOSIT_CHAIN_ID: 17000\n", "").parse::<NetworkSpec>();
+        assert!(matches!(err, Err(NetworkSpecError::MissingKey("DEPOSIT_CHAIN_ID"))));
+
+        let quoted = HOLESKY_CONFIG.replace("0x01017000", "'0x01017000'");
+        assert_eq!(quoted.parse::<NetworkSpec>().unwrap().genesis_fork_version, [1, 1, 112, 0]);
+
+        let err = "- not a mapping".parse::<NetworkSpec>();
+        assert!(matches!(err, Err(NetworkSpecError::Yaml(_))));
+    }
+}
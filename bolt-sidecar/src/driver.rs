@@ -2,13 +2,11 @@ use std::{fmt, sync::Arc, time::Instant};
 
 use alloy::{
     consensus::{TxType, Typed2718},
+    primitives::B256,
     rpc::types::beacon::events::HeadEvent,
     signers::local::PrivateKeySigner,
 };
-use ethereum_consensus::{
-    clock::{self, SlotStream, SystemTimeProvider},
-    phase0::mainnet::SLOTS_PER_EPOCH,
-};
+use ethereum_consensus::clock::{self, SlotStream, SystemTimeProvider};
 use eyre::Context;
 use futures::StreamExt;
 use tokio::sync::mpsc;
@@ -18,13 +16,16 @@ use crate::{
     api::{
         builder::{start_builder_proxy_server, BuilderProxyConfig},
         commitments::{
-            firewall::receiver::CommitmentsReceiver,
+            firewall::{
+                notifications::{NotificationsTx, ProposerSlot, SidecarNotification},
+                receiver::CommitmentsReceiver,
+            },
             server::{CommitmentEvent, CommitmentsApiServer},
             spec::CommitmentError,
         },
         spec::ConstraintsApi,
     },
-    builder::payload_fetcher::LocalPayloadFetcher,
+    builder::{payload_fetcher::LocalPayloadFetcher, PayloadPrebuilder},
     chain_io::BoltManager,
     client::{BeaconClient, ConstraintsClient},
     common::backoff::retry_with_backoff,
@@ -32,10 +33,14 @@ use crate::{
     crypto::{SignableBLS, SignerECDSA},
     primitives::{
         commitment::SignedCommitment, read_signed_delegations_from_file, CommitmentRequest,
-        ConstraintsMessage, FetchPayloadRequest, SignedConstraints,
+        ConstraintsMessage, FetchConstraintsRequest, FetchPayloadRequest, SignedConstraints,
     },
     signer::{keystore::KeystoreSigner, local::LocalSigner, CommitBoostSigner, SignerBLS},
-    state::{fetcher::StateFetcher, ConsensusState, ExecutionState, HeadTracker, StateClient},
+    state::{
+        auditor::BlockSource, fetcher::StateFetcher, BlockStateDiff, CommitmentIndex,
+        ConsensusState, ExecutionState, FailoverStateClient, FulfillmentAuditor, HeadTracker,
+        StateDiffStream,
+    },
     telemetry::ApiMetrics,
     LocalBuilder,
 };
@@ -54,33 +59,47 @@ const API_EVENTS_BUFFER_SIZE: usize = 1024;
 pub struct SidecarDriver<C, ECDSA> {
     /// Head tracker for monitoring the beacon chain clock
     head_tracker: HeadTracker,
+    /// Stream of the state diffs of new execution blocks, if an execution websocket is provided
+    state_diff_stream: Option<StateDiffStream>,
     /// Execution state for tracking the current head and block templates
     execution: ExecutionState<C>,
     /// Consensus state for tracking the current slot and validator indexes
     consensus: ConsensusState,
+    /// Beacon client, used to fetch the execution block number of new heads
+    beacon_client: BeaconClient,
     /// Signer for creating constraints
     constraint_signer: SignerBLS,
     /// Signer for creating commitment responses
     commitment_signer: ECDSA,
     /// Local block builder for creating local payloads
     local_builder: LocalBuilder,
+    /// Background builder of local payloads, triggered by block template changes
+    prebuilder: PayloadPrebuilder,
     /// Client for interacting with the constraints service
     constraints_client: ConstraintsClient,
     /// Channel for receiving incoming API events
     api_events_rx: mpsc::Receiver<CommitmentEvent>,
     /// Channel for receiving requests to fetch a local payload
     payload_requests_rx: mpsc::Receiver<FetchPayloadRequest>,
+    /// Channel for receiving requests to fetch the signed constraints of a slot
+    constraints_requests_rx: mpsc::Receiver<FetchConstraintsRequest>,
     /// Stream of slots made from the consensus clock
     slot_stream: SlotStream<SystemTimeProvider>,
+    /// Channel for pushing notifications to the firewall RPCs, if the sidecar connects to any
+    notifications: Option<NotificationsTx>,
+    /// Index of the issued commitments and their lifecycle, queried by the commitments API
+    commitment_index: Arc<CommitmentIndex>,
+    /// Auditor of the fulfillment of the issued commitments once their slots are over
+    auditor: FulfillmentAuditor,
     /// Whether to skip consensus checks (should only be used for testing)
     unsafe_skip_consensus_checks: bool,
 }
 
-impl SidecarDriver<StateClient, PrivateKeySigner> {
+impl SidecarDriver<FailoverStateClient, PrivateKeySigner> {
     /// Create a new sidecar driver with the given [Opts] and private key signer.
     pub async fn with_local_signer(opts: &Opts) -> eyre::Result<Self> {
-        // The default state client simply uses the execution API URL to fetch state updates.
-        let state_client = StateClient::new(opts.execution_api_url.clone());
+        // The default state client fails over between the execution API URLs.
+        let state_client = FailoverStateClient::from_urls(opts.execution_api_urls.clone());
 
         // Constraints are signed with a BLS private key
         let constraint_signer = SignerBLS::Local(LocalSigner::new(
@@ -93,20 +112,27 @@ impl SidecarDriver<StateClient, PrivateKeySigner> {
         ));
 
         // Commitment responses are signed with a regular Ethereum wallet private key.
-        let commitment_key = opts.commitment_opts.operator_private_key.0.clone();
+        let commitment_key =
+            opts.commitment_opts.operator_private_key.clone().expect("operator private key").0;
         let commitment_signer = PrivateKeySigner::from_signing_key(commitment_key);
 
-        Self::from_components(opts, constraint_signer, commitment_signer, state_client)
-            .await
-            .wrap_err("Failed to initialize sidecar with local signer")
+        Self::from_components(
+            opts,
+            constraint_signer,
+            commitment_signer,
+            state_client.clone(),
+            state_client,
+        )
+        .await
+        .wrap_err("Failed to initialize sidecar with local signer")
     }
 }
 
-impl SidecarDriver<StateClient, PrivateKeySigner> {
+impl SidecarDriver<FailoverStateClient, PrivateKeySigner> {
     /// Create a new sidecar driver with the given [Opts] and keystore signer.
     pub async fn with_keystore_signer(opts: &Opts) -> eyre::Result<Self> {
-        // The default state client simply uses the execution API URL to fetch state updates.
-        let state_client = StateClient::new(opts.execution_api_url.clone());
+        // The default state client fails over between the execution API URLs.
+        let state_client = FailoverStateClient::from_urls(opts.execution_api_urls.clone());
 
         let keystore = if let Some(psw) = opts.constraint_signing.keystore_password.as_ref() {
             KeystoreSigner::from_password(
@@ -125,20 +151,27 @@ impl SidecarDriver<StateClient, PrivateKeySigner> {
         let keystore_signer = SignerBLS::Keystore(keystore);
 
         // Commitment responses are signed with a regular Ethereum wallet private key.
-        let commitment_key = opts.commitment_opts.operator_private_key.0.clone();
+        let commitment_key =
+            opts.commitment_opts.operator_private_key.clone().expect("operator private key").0;
         let commitment_signer = PrivateKeySigner::from_signing_key(commitment_key);
 
-        Self::from_components(opts, keystore_signer, commitment_signer, state_client)
-            .await
-            .wrap_err("Failed to initialize sidecar with keystore signer")
+        Self::from_components(
+            opts,
+            keystore_signer,
+            commitment_signer,
+            state_client.clone(),
+            state_client,
+        )
+        .await
+        .wrap_err("Failed to initialize sidecar with keystore signer")
     }
 }
 
-impl SidecarDriver<StateClient, CommitBoostSigner> {
+impl SidecarDriver<FailoverStateClient, CommitBoostSigner> {
     /// Create a new sidecar driver with the given [Opts] and commit-boost signer.
     pub async fn with_commit_boost_signer(opts: &Opts) -> eyre::Result<Self> {
-        // The default state client simply uses the execution API URL to fetch state updates.
-        let state_client = StateClient::new(opts.execution_api_url.clone());
+        // The default state client fails over between the execution API URLs.
+        let state_client = FailoverStateClient::from_urls(opts.execution_api_urls.clone());
 
         let commit_boost_signer = CommitBoostSigner::new(
             opts.constraint_signing.commit_boost_signer_url.clone().expect("CommitBoost URL"),
@@ -147,19 +180,29 @@ impl SidecarDriver<StateClient, CommitBoostSigner> {
 
         let cb_bls_signer = SignerBLS::CommitBoost(commit_boost_signer.clone());
 
-        Self::from_components(opts, cb_bls_signer, commit_boost_signer, state_client)
-            .await
-            .wrap_err("Failed to initialize sidecar with commit-boost signer")
+        Self::from_components(
+            opts,
+            cb_bls_signer,
+            commit_boost_signer,
+            state_client.clone(),
+            state_client,
+        )
+        .await
+        .wrap_err("Failed to initialize sidecar with commit-boost signer")
     }
 }
 
-impl<C: StateFetcher, ECDSA: SignerECDSA> SidecarDriver<C, ECDSA> {
-    /// Create a new sidecar driver with the given components
+impl<C: StateFetcher, ECDSA: SignerECDSA + Clone + Sync + 'static> SidecarDriver<C, ECDSA> {
+    /// Create a new sidecar driver with the given components.
+    ///
+    /// The `execution_api` client is used for the execution API requests made outside of the
+    /// state fetcher, i.e. by the local builder and the BoltManager checks.
     pub async fn from_components(
         opts: &Opts,
         constraint_signer: SignerBLS,
         commitment_signer: ECDSA,
         fetcher: C,
+        execution_api: FailoverStateClient,
     ) -> eyre::Result<Self> {
         let mut constraints_client = ConstraintsClient::new(opts.constraints_api_url.clone());
 
@@ -179,8 +222,10 @@ impl<C: StateFetcher, ECDSA: SignerECDSA> SidecarDriver<C, ECDSA> {
 
         if opts.unsafe_disable_onchain_checks {
             warn!("Skipping validators and operator public keys verification: --unsafe-disable-onchain-checks is 'true'");
-        } else if let Some(manager) =
-            BoltManager::from_chain(opts.execution_api_url.clone(), *opts.chain)
+        } else if let Some(manager) = opts
+            .chain
+            .manager_address()
+            .map(|address| BoltManager::from_address(execution_api.clone(), address))
         {
             info!(
                 validator_pubkeys = %validator_pubkeys.len(),
@@ -199,69 +244,113 @@ impl<C: StateFetcher, ECDSA: SignerECDSA> SidecarDriver<C, ECDSA> {
             );
         }
 
-        let beacon_client = BeaconClient::new(opts.beacon_api_url.clone());
-        let execution = ExecutionState::new(fetcher, opts.limits).await?;
+        let beacon_client = BeaconClient::from_urls(opts.beacon_api_urls.clone());
+        beacon_client.spawn_health_checks();
+        let commitment_index = Arc::new(CommitmentIndex::default());
+        let execution = ExecutionState::new(fetcher, opts.limits)
+            .await?
+            .with_chain(opts.chain)
+            .with_stale_state_guard(!opts.unsafe_disable_stale_state_guard)
+            .with_commitment_index(commitment_index.clone());
 
         let genesis_time = beacon_client.get_genesis_details().await?.genesis_time;
-        let slot_stream =
-            clock::from_system_time(genesis_time, opts.chain.slot_time(), SLOTS_PER_EPOCH)
-                .into_stream();
-
-        let local_builder = LocalBuilder::new(opts, genesis_time);
-        let head_tracker = HeadTracker::start(beacon_client.clone());
+        let slot_stream = clock::from_system_time(
+            genesis_time,
+            opts.chain.slot_time(),
+            opts.chain.slots_per_epoch(),
+        )
+        .into_stream();
+
+        let local_builder =
+            LocalBuilder::new(opts, beacon_client.clone(), execution_api, genesis_time);
+        let prebuilder = PayloadPrebuilder::spawn(local_builder.clone());
+        let head_tracker = HeadTracker::start(beacon_client.clone(), opts.chain.slots_per_epoch());
+        let state_diff_stream = opts.execution_ws_url.clone().map(StateDiffStream::start);
 
         let consensus = ConsensusState::new(
-            beacon_client,
+            beacon_client.clone(),
             opts.chain.commitment_deadline(),
             opts.chain.enable_unsafe_lookahead,
+            opts.chain.slots_per_epoch(),
         );
 
         let (payload_requests_tx, payload_requests_rx) = mpsc::channel(16);
+        let (constraints_requests_tx, constraints_requests_rx) = mpsc::channel(16);
         let builder_proxy_cfg = BuilderProxyConfig {
             constraints_client: constraints_client.clone(),
             server_port: opts.constraints_proxy_port,
+            min_bid: opts.min_bid,
+            chain: opts.chain,
         };
 
         // start the builder api proxy server
         tokio::spawn(async move {
-            let payload_fetcher = LocalPayloadFetcher::new(payload_requests_tx);
+            let payload_fetcher =
+                LocalPayloadFetcher::new(payload_requests_tx, constraints_requests_tx);
             if let Err(err) = start_builder_proxy_server(payload_fetcher, builder_proxy_cfg).await {
                 error!(?err, "Builder API proxy server failed");
             }
         });
 
+        let mut notifications = None;
         let api_events_rx = if let Some(urls) = opts.commitment_opts.firewall_rpcs.clone() {
-            CommitmentsReceiver::new(
-                opts.commitment_opts.operator_private_key.clone(),
-                opts.chain.chain,
+            let priorities = opts.commitment_opts.firewall_rpc_priorities.clone();
+            // The websocket connections are authenticated with the same signer as commitments
+            let mut receiver = CommitmentsReceiver::new(
+                commitment_signer.clone(),
+                opts.chain.id(),
                 opts.limits,
                 urls,
                 validator_pubkeys.into_iter().collect(),
             )
-            .run()
+            .with_priorities(priorities.unwrap_or_default())
+            .with_commitment_index(commitment_index.clone())
+            .with_unsigned_requests(opts.commitment_opts.firewall_allow_unsigned_requests);
+
+            if let Some(port) = opts.commitment_opts.firewall_status_port {
+                receiver = receiver.with_status_port(port);
+            }
+
+            notifications = Some(receiver.notifications());
+            receiver.run()
         } else {
             let port = opts.commitment_opts.port.unwrap_or(DEFAULT_RPC_PORT);
             // start the commitments api server
             let api_addr = format!("0.0.0.0:{}", port);
             let (api_events_tx, api_events_rx) = mpsc::channel(API_EVENTS_BUFFER_SIZE);
-            CommitmentsApiServer::new(api_addr).run(api_events_tx, opts.limits).await;
+            CommitmentsApiServer::new(api_addr)
+                .with_commitment_index(commitment_index.clone())
+                .run(api_events_tx, opts.limits)
+                .await;
             api_events_rx
         };
 
+        let mut auditor = FulfillmentAuditor::new(commitment_index.clone());
+        if let Some(path) = opts.audit_log_path.clone() {
+            auditor = auditor.with_log_path(path);
+        }
+
         let unsafe_skip_consensus_checks = opts.unsafe_disable_consensus_checks;
 
         Ok(Self {
             unsafe_skip_consensus_checks,
             head_tracker,
+            state_diff_stream,
             execution,
             consensus,
+            beacon_client,
             constraint_signer,
             commitment_signer,
             local_builder,
+            prebuilder,
             constraints_client,
             api_events_rx,
             payload_requests_rx,
+            constraints_requests_rx,
             slot_stream,
+            notifications,
+            commitment_index,
+            auditor,
         })
     }
 
@@ -278,16 +367,20 @@ impl<C: StateFetcher, ECDSA: SignerECDSA> SidecarDriver<C, ECDSA> {
                 Ok(head_event) = self.head_tracker.next_head() => {
                     self.handle_new_head_event(head_event).await;
                 }
+                Some(diff) = next_state_diff(&mut self.state_diff_stream) => {
+                    self.handle_state_diff(diff);
+                }
                 Some(slot) = self.consensus.wait_commitment_deadline() => {
                     self.handle_commitment_deadline(slot).await;
                 }
                 Some(payload_request) = self.payload_requests_rx.recv() => {
                     self.handle_fetch_payload_request(payload_request);
                 }
+                Some(constraints_request) = self.constraints_requests_rx.recv() => {
+                    self.handle_fetch_constraints_request(constraints_request);
+                }
                 Some(slot) = self.slot_stream.next() => {
-                    if let Err(e) = self.consensus.update_slot(slot).await {
-                        error!(err = ?e, "Failed to update consensus state slot");
-                    }
+                    self.handle_new_slot(slot).await;
                 }
             }
         }
@@ -385,10 +478,17 @@ impl<C: StateFetcher, ECDSA: SignerECDSA> SidecarDriver<C, ECDSA> {
             self.execution.add_constraint(target_slot, signed_constraints);
         }
 
+        // The block template changed: pre-build a new local payload in the background.
+        self.schedule_prebuild(target_slot);
+
+        let remaining_gas = self.execution.remaining_committed_gas(target_slot);
+        self.notify(SidecarNotification::RemainingGas { slot: target_slot, remaining_gas });
+
         // Create a commitment by signing the request
         match inclusion_request.commit_and_sign(&self.commitment_signer).await {
             Ok(commitment) => {
                 debug!(target_slot, elapsed = ?start.elapsed(), "Commitment signed and sent");
+                self.commitment_index.on_accepted(commitment.clone());
                 response.send(Ok(SignedCommitment::Inclusion(commitment))).ok()
             }
             Err(err) => {
@@ -405,15 +505,102 @@ impl<C: StateFetcher, ECDSA: SignerECDSA> SidecarDriver<C, ECDSA> {
         let slot = head_event.slot;
         info!(slot, "Received new head event");
 
-        // We use None to signal that we want to fetch the latest EL head
-        if let Err(e) = self.execution.update_head(None, slot).await {
-            error!(err = ?e, "Failed to update execution state head");
+        // Fetch the state at the execution block of the new head, so that execution clients
+        // lagging behind it are failed over. We use None to signal that we want to fetch the
+        // latest EL head if the block number is not available.
+        let block = match self.beacon_client.get_execution_block(head_event.block).await {
+            Ok(block) => Some(block),
+            Err(err) => {
+                warn!(?err, "Failed to fetch the execution block of the new head");
+                None
+            }
+        };
+        let block_hash = block.map(|(_, hash)| hash);
+
+        // The block of the slot was built locally if it matches our fallback payload
+        let is_local = self.local_builder.get_cached_payload(slot).is_some_and(|payload| {
+            block_hash == Some(B256::from_slice(payload.bid.header().block_hash.as_ref()))
+        });
+        let source = if is_local { BlockSource::Local } else { BlockSource::Relay };
+        self.auditor.on_new_head(slot, block_hash, source);
+
+        match self.execution.update_head(block, slot).await {
+            // The commitments up to the new head have been resolved with their receipts
+            Ok(()) => self.auditor.audit_until(slot),
+            Err(e) => error!(err = ?e, "Failed to update execution state head, the state is stale"),
+        }
+
+        // Payloads built on top of the previous head are no longer valid: rebuild the one for
+        // the next slot on top of the new head.
+        self.local_builder.invalidate_payloads();
+        self.schedule_prebuild(slot + 1);
+    }
+
+    /// Handle a new slot from the beacon chain clock, updating the consensus state and
+    /// announcing the proposer slots of the sidecar when a new epoch starts.
+    async fn handle_new_slot(&mut self, slot: u64) {
+        let epoch = self.consensus.epoch();
+
+        if let Err(e) = self.consensus.update_slot(slot).await {
+            error!(err = ?e, "Failed to update consensus state slot");
+            return;
+        }
+
+        if self.consensus.epoch() != epoch {
+            self.notify_proposer_slots();
+        }
+    }
+
+    /// Push the slots of the current and next epoch for which the sidecar can sign constraints.
+    fn notify_proposer_slots(&self) {
+        if self.notifications.is_none() {
+            return;
+        }
+
+        let available_pubkeys = self.constraint_signer.available_pubkeys();
+        let slots = self
+            .consensus
+            .lookahead()
+            .filter(|duty| {
+                self.constraints_client
+                    .find_signing_key(duty.public_key.clone(), available_pubkeys.clone())
+                    .is_some()
+            })
+            .map(|duty| ProposerSlot { slot: duty.slot, validator_pubkey: duty.public_key.clone() })
+            .collect::<Vec<_>>();
+
+        debug!(epoch = self.consensus.epoch(), count = slots.len(), "Pushing proposer slots");
+        self.notify(SidecarNotification::ProposerSlots { epoch: self.consensus.epoch(), slots });
+    }
+
+    /// Push a notification to the connected firewall RPCs, if any.
+    fn notify(&self, notification: SidecarNotification) {
+        if let Some(notifications) = &self.notifications {
+            notifications.send(notification);
+        }
+    }
+
+    /// Handle the state diff of a new execution block, applying it to the execution state.
+    fn handle_state_diff(&mut self, diff: BlockStateDiff) {
+        let block_number = diff.block_number;
+        if self.execution.apply_state_diff(diff) {
+            debug!(block_number, "Applied execution state diff");
+        }
+    }
+
+    /// Schedule a background build of the local payload for the given slot, if there is a
+    /// block template for it.
+    fn schedule_prebuild(&mut self, slot: u64) {
+        if let Some(template) = self.execution.get_block_template(slot) {
+            self.prebuilder.schedule(slot, template.clone());
         }
     }
 
     /// Handle a commitment deadline event, submitting constraints to the Constraints client service
     /// and starting to build a local payload for the given target slot.
     async fn handle_commitment_deadline(&mut self, slot: u64) {
+        self.notify(SidecarNotification::CommitmentDeadline { slot });
+
         let Some(template) = self.execution.get_block_template(slot) else {
             // Nothing to do then. Block templates are created only when constraints are added,
             // which means we haven't issued any commitment for this slot because we are
@@ -421,22 +608,27 @@ impl<C: StateFetcher, ECDSA: SignerECDSA> SidecarDriver<C, ECDSA> {
             return;
         };
 
-        info!(slot, "Commitment deadline reached, building local block");
+        info!(slot, "Commitment deadline reached, finalizing local block");
 
-        if let Err(e) = self.local_builder.build_new_local_payload(slot, template).await {
+        if let Err(e) = self.local_builder.finalize_local_payload(slot, template).await {
             error!(err = ?e, "Error while building local payload at deadline for slot {slot}");
         };
 
         let constraints = Arc::new(template.signed_constraints_list.clone());
         let constraints_client = Arc::new(self.constraints_client.clone());
+        let commitment_index = Arc::clone(&self.commitment_index);
 
         // Submit constraints to the constraints service with an exponential retry mechanism.
         tokio::spawn(retry_with_backoff(Some(10), None, move || {
             let constraints_client = Arc::clone(&constraints_client);
             let constraints = Arc::clone(&constraints);
+            let commitment_index = Arc::clone(&commitment_index);
             async move {
                 match constraints_client.submit_constraints(constraints.as_ref()).await {
-                    Ok(_) => Ok(()),
+                    Ok(_) => {
+                        commitment_index.on_submitted(slot);
+                        Ok(())
+                    }
                     Err(e) => {
                         error!(err = ?e, "Failed to submit constraints, retrying...");
                         Err(e)
@@ -448,10 +640,10 @@ impl<C: StateFetcher, ECDSA: SignerECDSA> SidecarDriver<C, ECDSA> {
 
     /// Handle a fetch payload request, responding with the local payload if available.
     fn handle_fetch_payload_request(&mut self, request: FetchPayloadRequest) {
-        info!(slot = request.slot, "Received local payload request");
+        debug!(slot = request.slot, "Received local payload request");
 
-        let Some(payload_and_bid) = self.local_builder.get_cached_payload() else {
-            warn!(slot = request.slot, "No local payload found");
+        let Some(payload_and_bid) = self.local_builder.get_cached_payload(request.slot) else {
+            debug!(slot = request.slot, "No local payload found");
             let _ = request.response_tx.send(None);
             return;
         };
@@ -460,12 +652,33 @@ impl<C: StateFetcher, ECDSA: SignerECDSA> SidecarDriver<C, ECDSA> {
             error!(err = ?e, "Failed to send payload and bid in response channel");
         }
     }
+
+    /// Handle a fetch constraints request, responding with the signed constraints of the
+    /// block template for the requested slot, if any.
+    fn handle_fetch_constraints_request(&mut self, request: FetchConstraintsRequest) {
+        let constraints = self
+            .execution
+            .get_block_template(request.slot)
+            .map(|template| template.signed_constraints_list.clone())
+            .unwrap_or_default();
+
+        debug!(
+            slot = request.slot,
+            count = constraints.len(),
+            "Responding with signed constraints"
+        );
+
+        if request.response_tx.send(constraints).is_err() {
+            error!(slot = request.slot, "Failed to send signed constraints in response channel");
+        }
+    }
 }
 
-impl fmt::Debug for SidecarDriver<StateClient, PrivateKeySigner> {
+impl fmt::Debug for SidecarDriver<FailoverStateClient, PrivateKeySigner> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("SidecarDriver")
             .field("head_tracker", &self.head_tracker)
+            .field("state_diff_stream", &self.state_diff_stream)
             .field("execution", &self.execution)
             .field("consensus", &self.consensus)
             .field("constraint_signer", &self.constraint_signer)
@@ -474,6 +687,15 @@ impl fmt::Debug for SidecarDriver<StateClient, PrivateKeySigner> {
             .field("constraints_client", &self.constraints_client)
             .field("api_events_rx", &self.api_events_rx)
             .field("payload_requests_rx", &self.payload_requests_rx)
+            .field("constraints_requests_rx", &self.constraints_requests_rx)
             .finish()
     }
 }
+
+/// Get the next state diff from the stream, if any. Never resolves if there is no stream.
+async fn next_state_diff(stream: &mut Option<StateDiffStream>) -> Option<BlockStateDiff> {
+    match stream {
+        Some(stream) => stream.next_diff().await,
+        None => std::future::pending().await,
+    }
+}
@@ -177,8 +177,8 @@ impl InclusionRequest {
     }
 
     /// Validates the priority fee against a minimum priority fee.
-    /// Returns `true` if the "effective priority fee" is greater than or equal to the set minimum
-    /// priority fee, `false` otherwise.
+    /// Returns the minimum priority fee of each transaction if their "effective priority fee" is
+    /// greater than or equal to it, or a [PricingError::TipTooLow] error otherwise.
     /// Returns an error if min priority fee cannot be calculated.
     pub fn validate_min_priority_fee(
         &self,
@@ -186,7 +186,9 @@ impl InclusionRequest {
         preconfirmed_gas: u64,
         min_inclusion_profit: u64,
         max_base_fee: u128,
-    ) -> Result<bool, PricingError> {
+    ) -> Result<Vec<u128>, PricingError> {
+        let mut min_priority_fees = Vec::with_capacity(self.txs.len());
+
         // Each included tx will move the price up
         // So we need to calculate the minimum priority fee for each tx
         let mut local_preconfirmed_gas = preconfirmed_gas;
@@ -203,10 +205,12 @@ impl InclusionRequest {
                     min_priority_fee: min_priority_fee as u128,
                 });
             }
+            min_priority_fees.push(min_priority_fee as u128);
+
             // Increment the preconfirmed gas for the next transaction in the bundle
             local_preconfirmed_gas = local_preconfirmed_gas.saturating_add(tx.gas_limit());
         }
-        Ok(true)
+        Ok(min_priority_fees)
     }
 
     /// Returns the total gas limit of all transactions in this request.
@@ -0,0 +1,188 @@
+use alloy::eips::eip7685::Requests;
+use ethereum_consensus::{
+    primitives::{Bytes32, ExecutionAddress, Gwei},
+    serde::as_str,
+    ssz::prelude::*,
+};
+
+use super::{BlsPublicKey, BlsSignature};
+
+/// The maximum number of deposit requests in an execution payload.
+pub const MAX_DEPOSIT_REQUESTS_PER_PAYLOAD: usize = 8192;
+/// The maximum number of withdrawal requests in an execution payload.
+pub const MAX_WITHDRAWAL_REQUESTS_PER_PAYLOAD: usize = 16;
+/// The maximum number of consolidation requests in an execution payload.
+pub const MAX_CONSOLIDATION_REQUESTS_PER_PAYLOAD: usize = 2;
+
+/// The EIP-7685 request type of deposit requests (EIP-6110).
+pub const DEPOSIT_REQUEST_TYPE: u8 = 0x00;
+/// The EIP-7685 request type of withdrawal requests (EIP-7002).
+pub const WITHDRAWAL_REQUEST_TYPE: u8 = 0x01;
+/// The EIP-7685 request type of consolidation requests (EIP-7251).
+pub const CONSOLIDATION_REQUEST_TYPE: u8 = 0x02;
+
+/// A deposit request, as defined in the Electra consensus specs.
+///
+/// Reference: <https://github.com/ethereum/consensus-specs/blob/dev/specs/electra/beacon-chain.md#depositrequest>
+#[derive(
+    Debug, Default, Clone, PartialEq, Eq, SimpleSerialize, serde::Serialize, serde::Deserialize,
+)]
+#[allow(missing_docs)]
+pub struct DepositRequest {
+    pub pubkey: BlsPublicKey,
+    pub withdrawal_credentials: Bytes32,
+    #[serde(with = "as_str")]
+    pub amount: Gwei,
+    pub signature: BlsSignature,
+    #[serde(with = "as_str")]
+    pub index: u64,
+}
+
+/// A withdrawal request, as defined in the Electra consensus specs.
+///
+/// Reference: <https://github.com/ethereum/consensus-specs/blob/dev/specs/electra/beacon-chain.md#withdrawalrequest>
+#[derive(
+    Debug, Default, Clone, PartialEq, Eq, SimpleSerialize, serde::Serialize, serde::Deserialize,
+)]
+#[allow(missing_docs)]
+pub struct WithdrawalRequest {
+    pub source_address: ExecutionAddress,
+    pub validator_pubkey: BlsPublicKey,
+    #[serde(with = "as_str")]
+    pub amount: Gwei,
+}
+
+/// A consolidation request, as defined in the Electra consensus specs.
+///
+/// Reference: <https://github.com/ethereum/consensus-specs/blob/dev/specs/electra/beacon-chain.md#consolidationrequest>
+#[derive(
+    Debug, Default, Clone, PartialEq, Eq, SimpleSerialize, serde::Serialize, serde::Deserialize,
+)]
+#[allow(missing_docs)]
+pub struct ConsolidationRequest {
+    pub source_address: ExecutionAddress,
+    pub source_pubkey: BlsPublicKey,
+    pub target_pubkey: BlsPublicKey,
+}
+
+/// The execution requests of a block, added to the beacon block body and to the builder bid
+/// from the Electra fork onwards.
+///
+/// Reference: <https://github.com/ethereum/consensus-specs/blob/dev/specs/electra/beacon-chain.md#executionrequests>
+#[derive(
+    Debug, Default, Clone, PartialEq, Eq, SimpleSerialize, serde::Serialize, serde::Deserialize,
+)]
+#[allow(missing_docs)]
+pub struct ExecutionRequests {
+    pub deposits: List<DepositRequest, MAX_DEPOSIT_REQUESTS_PER_PAYLOAD>,
+    pub withdrawals: List<WithdrawalRequest, MAX_WITHDRAWAL_REQUESTS_PER_PAYLOAD>,
+    pub consolidations: List<ConsolidationRequest, MAX_CONSOLIDATION_REQUESTS_PER_PAYLOAD>,
+}
+
+/// Errors that can occur when converting execution requests from their EIP-7685 encoding.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum ExecutionRequestsError {
+    #[error("Empty execution request")]
+    EmptyRequest,
+    #[error("Unknown execution request type: {0}")]
+    UnknownRequestType(u8),
+    #[error("Execution request types must be unique and in ascending order")]
+    InvalidOrder,
+    #[error("Failed to decode execution requests: {0:?}")]
+    Ssz(#[from] DeserializeError),
+}
+
+impl ExecutionRequests {
+    /// Decode the execution requests from their EIP-7685 encoding, as returned by the
+    /// `engine_getPayloadV4` method. Each request is made of its type byte followed by
+    /// the SSZ-encoded list of requests of that type.
+    pub fn from_eip7685(requests: &Requests) -> Result<Self, ExecutionRequestsError> {
+        let mut execution_requests = Self::default();
+        let mut previous_type = None;
+
+        for request in requests.iter() {
+            let (&request_type, data) =
+                request.split_first().ok_or(ExecutionRequestsError::EmptyRequest)?;
+
+            if previous_type.is_some_and(|previous| previous >= request_type) {
+                return Err(ExecutionRequestsError::InvalidOrder);
+            }
+            previous_type = Some(request_type);
+
+            match request_type {
+                DEPOSIT_REQUEST_TYPE => execution_requests.deposits = ssz_rs::deserialize(data)?,
+                WITHDRAWAL_REQUEST_TYPE => {
+                    execution_requests.withdrawals = ssz_rs::deserialize(data)?
+                }
+                CONSOLIDATION_REQUEST_TYPE => {
+                    execution_requests.consolidations = ssz_rs::deserialize(data)?
+                }
+                other => return Err(ExecutionRequestsError::UnknownRequestType(other)),
+            }
+        }
+
+        Ok(execution_requests)
+    }
+
+    /// Encode the execution requests with EIP-7685, as expected by the `engine_newPayloadV4`
+    /// method and the `requests_hash` of the block header. Empty request lists are omitted.
+    pub fn to_eip7685(&self) -> Result<Requests, SerializeError> {
+        let mut requests = Requests::default();
+
+        requests.push_request_with_type(DEPOSIT_REQUEST_TYPE, ssz_rs::serialize(&self.deposits)?);
+        requests
+            .push_request_with_type(WITHDRAWAL_REQUEST_TYPE, ssz_rs::serialize(&self.withdrawals)?);
+        requests.push_request_with_type(
+            CONSOLIDATION_REQUEST_TYPE,
+            ssz_rs::serialize(&self.consolidations)?,
+        );
+
+        Ok(requests)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{eips::eip7685::EMPTY_REQUESTS_HASH, primitives::Bytes};
+
+    use super::*;
+
+    #[test]
+    fn test_execution_requests_eip7685_roundtrip() {
+        let empty = ExecutionRequests::default().to_eip7685().unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(empty.requests_hash(), EMPTY_REQUESTS_HASH);
+
+        let mut execution_requests = ExecutionRequests::default();
+        execution_requests.deposits.push(DepositRequest {
+            amount: 32_000_000_000,
+            index: 7,
+            ..Default::default()
+        });
+        execution_requests.consolidations.push(ConsolidationRequest::default());
+
+        let requests = execution_requests.to_eip7685().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].len(), 1 + 192);
+        assert_eq!(requests[0][0], DEPOSIT_REQUEST_TYPE);
+        assert_eq!(requests[1].len(), 1 + 116);
+        assert_eq!(requests[1][0], CONSOLIDATION_REQUEST_TYPE);
+
+        let decoded = ExecutionRequests::from_eip7685(&requests).unwrap();
+        assert_eq!(decoded, execution_requests);
+
+        // Request types must be in ascending order
+        let unordered = Requests::new(vec![requests[1].clone(), requests[0].clone()]);
+        assert!(matches!(
+            ExecutionRequests::from_eip7685(&unordered),
+            Err(ExecutionRequestsError::InvalidOrder)
+        ));
+
+        let unknown = Requests::new(vec![Bytes::from_static(&[0x05, 0x00])]);
+        assert!(matches!(
+            ExecutionRequests::from_eip7685(&unknown),
+            Err(ExecutionRequestsError::UnknownRequestType(0x05))
+        ));
+    }
+}
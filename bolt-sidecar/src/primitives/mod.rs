@@ -1,9 +1,9 @@
 use alloy::primitives::U256;
 use ethereum_consensus::{
-    crypto::KzgCommitment,
+    crypto::{KzgCommitment, KzgProof},
     deneb::{
         self,
-        mainnet::{BlobsBundle, MAX_BLOB_COMMITMENTS_PER_BLOCK},
+        mainnet::{Blob, BlobsBundle, MAX_BLOB_COMMITMENTS_PER_BLOCK},
         presets::mainnet::ExecutionPayloadHeader,
         Hash32,
     },
@@ -39,12 +39,20 @@ pub use transaction::{deserialize_txs, serialize_txs, FullTransaction, Transacti
 /// Miscellaneous types and utilities.
 pub mod misc;
 
+/// Inclusion proof types and verification utilities.
+pub mod proofs;
+pub use proofs::InclusionProofs;
+
 /// Signature types and utilities.
 pub mod signature;
 
 /// JSON-RPC helper types and functions.
 pub mod jsonrpc;
 
+/// Electra fork types that are not available in `ethereum-consensus`.
+pub mod electra;
+pub use electra::ExecutionRequests;
+
 /// An alias for a Beacon Chain slot number
 pub type Slot = u64;
 
@@ -81,39 +89,108 @@ pub struct SignedBuilderBid {
     pub signature: BlsSignature,
 }
 
-/// Signed builder bid with the proposer signature and Bolt inclusion proofs
+/// Builder bid from the Electra fork onwards, which commits to the execution requests
+/// of the payload.
+///
+/// Reference: <https://github.com/ethereum/builder-specs/blob/main/specs/electra/builder.md#builderbid>
 #[derive(Debug, Default, Clone, SimpleSerialize, serde::Serialize, serde::Deserialize)]
 #[allow(missing_docs)]
-pub struct SignedBuilderBidWithProofs {
-    pub bid: SignedBuilderBid,
-    pub proofs: List<ConstraintProof, 300>,
+pub struct BuilderBidElectra {
+    pub header: ExecutionPayloadHeader,
+    pub blob_kzg_commitments: List<KzgCommitment, MAX_BLOB_COMMITMENTS_PER_BLOCK>,
+    pub execution_requests: ExecutionRequests,
+    #[serde(with = "as_str")]
+    pub value: U256,
+    #[serde(rename = "pubkey")]
+    pub public_key: BlsPublicKey,
 }
 
-/// A proof that a transaction is included in a block
+/// Signed Electra builder bid with the proposer signature
 #[derive(Debug, Default, Clone, SimpleSerialize, serde::Serialize, serde::Deserialize)]
 #[allow(missing_docs)]
-pub struct ConstraintProof {
-    #[serde(rename = "txHash")]
-    tx_hash: Hash32,
-    #[serde(rename = "merkleProof")]
-    merkle_proof: MerkleProof,
+pub struct SignedBuilderBidElectra {
+    pub message: BuilderBidElectra,
+    pub signature: BlsSignature,
 }
 
-/// A merkle proof that a transaction is included in a block.
-#[derive(Debug, Default, Clone, SimpleSerialize, serde::Serialize, serde::Deserialize)]
-pub struct MerkleProof {
-    /// Index of the transaction in the block
-    index: u64,
-    /// List of hashes that are part of the merkle proof
-    hashes: List<Hash32, 1000>,
+/// A signed builder bid for any of the forks supported by the sidecar.
+///
+/// The JSON representation doesn't carry the fork, which is communicated separately
+/// in the `version` field of the response: bids are decoded with [`Self::from_json`]
+/// according to that version.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged)]
+#[allow(missing_docs)]
+pub enum VersionedSignedBuilderBid {
+    Electra(SignedBuilderBidElectra),
+    Deneb(SignedBuilderBid),
 }
 
-/// Merkle multi-proof that a set of transactions are included in a block
-#[derive(Debug, Default, Clone, SimpleSerialize, serde::Serialize, serde::Deserialize)]
-pub struct MerkleMultiProof {
-    transaction_hashes: List<Hash32, 300>,
-    generalized_indexes: List<u64, 300>,
-    merkle_hashes: List<Hash32, 1000>,
+impl Default for VersionedSignedBuilderBid {
+    fn default() -> Self {
+        Self::Deneb(SignedBuilderBid::default())
+    }
+}
+
+impl VersionedSignedBuilderBid {
+    /// Decodes the JSON representation of a bid for the given fork.
+    ///
+    /// Returns `Ok(None)` if the fork is not supported.
+    pub fn from_json(
+        version: Fork,
+        data: serde_json::Value,
+    ) -> Result<Option<Self>, serde_json::Error> {
+        match version {
+            Fork::Deneb => serde_json::from_value(data).map(|bid| Some(Self::Deneb(bid))),
+            Fork::Electra => serde_json::from_value(data).map(|bid| Some(Self::Electra(bid))),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the fork of the bid
+    pub fn version(&self) -> Fork {
+        match self {
+            Self::Deneb(_) => Fork::Deneb,
+            Self::Electra(_) => Fork::Electra,
+        }
+    }
+
+    /// Returns the value of the bid in wei
+    pub fn value(&self) -> U256 {
+        match self {
+            Self::Deneb(bid) => bid.message.value,
+            Self::Electra(bid) => bid.message.value,
+        }
+    }
+
+    /// Returns the execution payload header of the bid
+    pub fn header(&self) -> &ExecutionPayloadHeader {
+        match self {
+            Self::Deneb(bid) => &bid.message.header,
+            Self::Electra(bid) => &bid.message.header,
+        }
+    }
+
+    /// Returns the SSZ encoding of the bid
+    pub fn ssz_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        match self {
+            Self::Deneb(bid) => ssz_rs::serialize(bid),
+            Self::Electra(bid) => ssz_rs::serialize(bid),
+        }
+    }
+}
+
+/// Signed builder bid with the proposer signature and Bolt inclusion proofs.
+///
+/// Reference: <https://docs.boltprotocol.xyz/technical-docs/api/builder#get_header_with_proofs>
+#[derive(Debug, Default, Clone, serde::Serialize)]
+pub struct SignedBuilderBidWithProofs {
+    /// The signed builder bid.
+    #[serde(flatten)]
+    pub bid: VersionedSignedBuilderBid,
+    /// The inclusion proofs of the constrained transactions in the bid's payload.
+    #[serde(default)]
+    pub proofs: InclusionProofs,
 }
 
 /// Request to fetch a payload for a given slot
@@ -125,11 +202,20 @@ pub struct FetchPayloadRequest {
     pub response_tx: oneshot::Sender<Option<PayloadAndBid>>,
 }
 
-/// Response to a fetch payload request
+/// Request to fetch the signed constraints for a given slot
 #[derive(Debug)]
+pub struct FetchConstraintsRequest {
+    /// Slot number for the constraints to fetch
+    pub slot: u64,
+    /// Channel to send the response to
+    pub response_tx: oneshot::Sender<Vec<SignedConstraints>>,
+}
+
+/// Response to a fetch payload request
+#[derive(Debug, Clone)]
 #[allow(missing_docs)]
 pub struct PayloadAndBid {
-    pub bid: SignedBuilderBid,
+    pub bid: VersionedSignedBuilderBid,
     pub payload: GetPayloadResponse,
 }
 
@@ -150,8 +236,42 @@ impl Default for PayloadAndBlobs {
     }
 }
 
+/// SSZ-serializable blobs bundle, as defined in the builder-specs.
+///
+/// Reference: <https://github.com/ethereum/builder-specs/blob/main/specs/deneb/builder.md#blobsbundle>
+#[derive(Debug, Default, Clone, SimpleSerialize)]
+#[allow(missing_docs)]
+pub struct SszBlobsBundle {
+    pub commitments: List<KzgCommitment, MAX_BLOB_COMMITMENTS_PER_BLOCK>,
+    pub proofs: List<KzgProof, MAX_BLOB_COMMITMENTS_PER_BLOCK>,
+    pub blobs: List<Blob, MAX_BLOB_COMMITMENTS_PER_BLOCK>,
+}
+
+impl TryFrom<&BlobsBundle> for SszBlobsBundle {
+    type Error = SimpleSerializeError;
+
+    fn try_from(bundle: &BlobsBundle) -> Result<Self, Self::Error> {
+        Ok(Self {
+            commitments: List::try_from(bundle.commitments.clone()).map_err(|(_, err)| err)?,
+            proofs: List::try_from(bundle.proofs.clone()).map_err(|(_, err)| err)?,
+            blobs: List::try_from(bundle.blobs.clone()).map_err(|(_, err)| err)?,
+        })
+    }
+}
+
+/// SSZ-serializable execution payload and blobs bundle, returned by the `submitBlindedBlock`
+/// endpoint from Deneb onwards when the SSZ encoding is requested.
+///
+/// Reference: <https://github.com/ethereum/builder-specs/blob/main/specs/deneb/builder.md#executionpayloadandblobsbundle>
+#[derive(Debug, Default, Clone, SimpleSerialize)]
+#[allow(missing_docs)]
+pub struct ExecutionPayloadAndBlobsBundle {
+    pub execution_payload: deneb::mainnet::ExecutionPayload,
+    pub blobs_bundle: SszBlobsBundle,
+}
+
 /// Response to a get payload request
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(tag = "version", content = "data")]
 #[allow(missing_docs)]
 pub enum GetPayloadResponse {
@@ -181,6 +301,24 @@ impl GetPayloadResponse {
             Self::Deneb(payload) | Self::Electra(payload) => &payload.execution_payload,
         }
     }
+
+    /// Returns a response for the given fork with the payload and blobs bundle
+    pub fn new(fork: Fork, payload_and_blobs: PayloadAndBlobs) -> Self {
+        match fork {
+            Fork::Electra => Self::Electra(payload_and_blobs),
+            _ => Self::from(payload_and_blobs),
+        }
+    }
+
+    /// Returns the fork version of the payload
+    pub fn version(&self) -> Fork {
+        match self {
+            Self::Bellatrix(_) => Fork::Bellatrix,
+            Self::Capella(_) => Fork::Capella,
+            Self::Deneb(_) => Fork::Deneb,
+            Self::Electra(_) => Fork::Electra,
+        }
+    }
 }
 
 impl From<PayloadAndBlobs> for GetPayloadResponse {
@@ -0,0 +1,225 @@
+use alloy::{
+    eips::eip2718::Encodable2718,
+    primitives::{TxHash, B256},
+};
+use serde::{Deserialize, Serialize};
+use ssz_rs::{HashTreeRoot, List};
+
+use super::{FullTransaction, SignedConstraints};
+
+/// The maximum number of bytes in a single transaction, as defined in the consensus specs.
+const MAX_BYTES_PER_TRANSACTION: usize = 1_073_741_824;
+
+/// Inclusion proofs for the constrained transactions of a block, as returned by relays
+/// alongside the header.
+///
+/// Reference: <https://docs.boltprotocol.xyz/technical-docs/api/builder#get_header_with_proofs>
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InclusionProofs {
+    /// The transaction hashes these inclusion proofs are for. The hash tree roots of
+    /// these transactions are the leaves of the transactions tree.
+    pub transaction_hashes: Vec<TxHash>,
+    /// The generalized indexes of the nodes in the transactions tree.
+    pub generalized_indexes: Vec<usize>,
+    /// The proof hashes for the transactions tree.
+    pub merkle_hashes: Vec<B256>,
+}
+
+impl InclusionProofs {
+    /// Returns the total number of leaves in the tree.
+    pub fn total_leaves(&self) -> usize {
+        self.transaction_hashes.len()
+    }
+
+    /// Returns `true` if no proofs were provided.
+    pub fn is_empty(&self) -> bool {
+        self.transaction_hashes.is_empty()
+    }
+}
+
+/// Errors that can occur when verifying inclusion proofs.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum ProofError {
+    #[error("Leaves and indices length mismatch")]
+    LengthMismatch,
+    #[error("Mismatch in provided leaves and leaves to prove")]
+    LeavesMismatch,
+    #[error("Hash not found in constraints: {0:?}")]
+    MissingHash(TxHash),
+    #[error("Failed to compute the hash tree root of transaction {0:?}")]
+    Merkleization(TxHash),
+    #[error("Proof verification failed")]
+    VerificationFailed,
+}
+
+/// Returns the hash and the SSZ hash tree root of a constrained transaction, which is the leaf
+/// of the transactions tree that needs to be proven.
+///
+/// For blob transactions, the hash tree root is computed without the blob sidecar, as the
+/// sidecar is not part of the execution payload.
+pub fn transaction_leaf(tx: &FullTransaction) -> Result<(TxHash, B256), ProofError> {
+    let hash = *tx.hash();
+    let raw = tx.clone().into_signed().encoded_2718();
+
+    let leaf = List::<u8, MAX_BYTES_PER_TRANSACTION>::try_from(raw)
+        .map_err(|_| ProofError::Merkleization(hash))?
+        .hash_tree_root()
+        .map_err(|_| ProofError::Merkleization(hash))?;
+
+    Ok((hash, leaf))
+}
+
+/// Verifies the provided multiproofs against the constraints & transactions root.
+/// TODO: support bundle proof verification a.k.a. relative ordering!
+pub fn verify_multiproofs(
+    constraints: &[SignedConstraints],
+    proofs: &InclusionProofs,
+    root: B256,
+) -> Result<(), ProofError> {
+    // Check if the length of the leaves and indices match
+    if proofs.transaction_hashes.len() != proofs.generalized_indexes.len() {
+        return Err(ProofError::LengthMismatch);
+    }
+
+    let constrained = constraints
+        .iter()
+        .flat_map(|c| c.message.transactions.iter())
+        .map(transaction_leaf)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Check if the total leaves matches the proofs provided
+    if constrained.len() != proofs.total_leaves() {
+        return Err(ProofError::LeavesMismatch);
+    }
+
+    // Get the leaves from the constraints by matching the proven hashes, in the same order as
+    // the generalized indexes.
+    let leaves = proofs
+        .transaction_hashes
+        .iter()
+        .map(|hash| {
+            constrained
+                .iter()
+                .find(|(saved_hash, _)| saved_hash == hash)
+                .map(|(_, leaf)| *leaf)
+                .ok_or(ProofError::MissingHash(*hash))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Verify the Merkle multiproof against the root
+    ssz_rs::multiproofs::verify_merkle_multiproof(
+        &leaves,
+        &proofs.merkle_hashes,
+        &proofs.generalized_indexes,
+        root,
+    )
+    .map_err(|_| ProofError::VerificationFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use alloy::primitives::{hex, B256};
+
+    use super::*;
+    use crate::primitives::ConstraintsMessage;
+
+    /// Hash of the blob transaction inside `test_data/signed_constraints_with_blob.json`.
+    const BLOB_TX_HASH: [u8; 32] =
+        hex!("00724d63ef8a791110a66d6e7433d097637aec698f5cf81c44446e1ea5c45a1a");
+
+    fn blob_constraints() -> Vec<SignedConstraints> {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("test_data/signed_constraints_with_blob.json");
+
+        let raw = std::fs::read_to_string(path).expect("to read signed constraints");
+        let value: serde_json::Value = serde_json::from_str(&raw).unwrap();
+        let message: ConstraintsMessage =
+            serde_json::from_value(value[0]["message"].clone()).unwrap();
+
+        vec![SignedConstraints { message, signature: Default::default() }]
+    }
+
+    /// Proof generated from bolt-builder code for the blob transaction.
+    fn blob_proofs() -> (B256, InclusionProofs) {
+        let root =
+            B256::from(hex!("085f9483581f0302fd8a5a7b03e5aa9f110d4548bd679bedc04764dc9405a700"));
+
+        let merkle_hashes = [
+            hex!("8c0bd07dcc7050700654b730d245db145c92ad92ef6ac81e2361533c66ee9688"),
+            hex!("ee38e5ba99fa98c9c8963c7e9c59e3128f285454f27daf9549d19c4bb98039fd"),
+            hex!("af0302f3b715a72dab24a7590f01dc5717c642a39fc5a92bc09518b24e05d56c"),
+            hex!("c78009fdf07fc56a11f122370658a353aaa542ed63e44c4bc15ff4cd105ab33c"),
+            hex!("536d98837f2dd165a55d5eeae91485954472d56f246df256bf3cae19352a123c"),
+            hex!("9efde052aa15429fae05bad4d0b1d7c64da64d03d7a1854a588c2cb8430c0d30"),
+            hex!("d88ddfeed400a8755596b21942c1497e114c302e6118290f91e6772976041fa1"),
+            hex!("87eb0ddba57e35f6d286673802a4af5975e22506c7cf4c64bb6be5ee11527f2c"),
+            hex!("26846476fd5fc54a5d43385167c95144f2643f533cc85bb9d16b782f8d7db193"),
+            hex!("506d86582d252405b840018792cad2bf1259f1ef5aa5f887e13cb2f0094f51e1"),
+            hex!("ffff0ad7e659772f9534c195c815efc4014ef1e1daed4404c06385d11192e92b"),
+            hex!("6cf04127db05441cd833107a52be852868890e4317e6a02ab47683aa75964220"),
+            hex!("b7d05f875f140027ef5118a2247bbb84ce8f2f0f1123623085daf7960c329f5f"),
+            hex!("df6af5f5bbdb6be9ef8aa618e4bf8073960867171e29676f8b284dea6a08a85e"),
+            hex!("b58d900f5e182e3c50ef74969ea16c7726c549757cc23523c369587da7293784"),
+            hex!("d49a7502ffcfb0340b1d7885688500ca308161a7f96b62df9d083b71fcc8f2bb"),
+            hex!("8fe6b1689256c0d385f42f5bbe2027a22c1996e110ba97c171d3e5948de92beb"),
+            hex!("8d0d63c39ebade8509e0ae3c9c3876fb5fa112be18f905ecacfecb92057603ab"),
+            hex!("95eec8b2e541cad4e91de38385f2e046619f54496c2382cb6cacd5b98c26f5a4"),
+            hex!("f893e908917775b62bff23294dbbe3a1cd8e6cc1c35b4801887b646a6f81f17f"),
+            hex!("0600000000000000000000000000000000000000000000000000000000000000"),
+        ]
+        .iter()
+        .map(B256::from)
+        .collect();
+
+        let proofs = InclusionProofs {
+            transaction_hashes: vec![B256::from(BLOB_TX_HASH)],
+            generalized_indexes: vec![2097152],
+            merkle_hashes,
+        };
+
+        (root, proofs)
+    }
+
+    #[test]
+    fn test_transaction_leaf_strips_blob_sidecar() {
+        let constraints = blob_constraints();
+        let (hash, leaf) = transaction_leaf(&constraints[0].message.transactions[0]).unwrap();
+
+        assert_eq!(hash, B256::from(BLOB_TX_HASH));
+        assert_eq!(
+            leaf,
+            B256::from(hex!("b4bb948e1cfc750a20fa08d6661d3f0717ca367eec45d81fcf92e8f1ae1fe688"))
+        );
+    }
+
+    #[test]
+    fn test_verify_multiproofs() {
+        let constraints = blob_constraints();
+        let (root, proofs) = blob_proofs();
+
+        assert!(verify_multiproofs(&constraints, &proofs, root).is_ok());
+
+        // A proof against a different root must be rejected
+        assert!(matches!(
+            verify_multiproofs(&constraints, &proofs, B256::ZERO),
+            Err(ProofError::VerificationFailed)
+        ));
+
+        // Missing proofs for a constrained transaction must be rejected
+        assert!(matches!(
+            verify_multiproofs(&constraints, &InclusionProofs::default(), root),
+            Err(ProofError::LeavesMismatch)
+        ));
+
+        // Proofs for a transaction that wasn't constrained must be rejected
+        let mut wrong_hash = proofs.clone();
+        wrong_hash.transaction_hashes = vec![B256::ZERO];
+        assert!(matches!(
+            verify_multiproofs(&constraints, &wrong_hash, root),
+            Err(ProofError::MissingHash(_))
+        ));
+    }
+}
@@ -0,0 +1,423 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use alloy::primitives::{TxHash, B256};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::{common::time::current_timestamp, telemetry::ApiMetrics};
+
+use super::{
+    commitment_index::{CommitmentState, CommitmentStatus},
+    CommitmentIndex,
+};
+
+/// The source of the block proposed in a slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BlockSource {
+    /// The block was delivered by a relay.
+    Relay,
+    /// The block was built locally by the fallback builder.
+    Local,
+}
+
+/// The reason why a commitment was not fulfilled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BreachReason {
+    /// The block delivered by the relay doesn't include all the committed transactions.
+    RelayBlockWithoutTx,
+    /// The fallback block doesn't include all the committed transactions.
+    FallbackBlockWithoutTx,
+    /// The user invalidated the commitment by changing the nonce or balance of a sender.
+    InvalidatedByUser,
+    /// No block was proposed in the slot.
+    SlotMissed,
+}
+
+impl BreachReason {
+    /// Returns the reason as a metric label.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::RelayBlockWithoutTx => "relay_block_without_tx",
+            Self::FallbackBlockWithoutTx => "fallback_block_without_tx",
+            Self::InvalidatedByUser => "invalidated_by_user",
+            Self::SlotMissed => "slot_missed",
+        }
+    }
+
+    /// Returns whether the proposer is liable for the breach, i.e. if it wasn't caused by the
+    /// user.
+    pub fn is_liable(&self) -> bool {
+        !matches!(self, Self::InvalidatedByUser)
+    }
+}
+
+/// A commitment that was not fulfilled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Breach {
+    /// The digest of the inclusion request.
+    pub digest: B256,
+    /// The hashes of the committed transactions missing from the block.
+    pub missing_tx_hashes: Vec<TxHash>,
+    /// Why the commitment was not fulfilled.
+    pub reason: BreachReason,
+}
+
+/// The fulfillment audit of the commitments of a slot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotAudit {
+    /// The audited slot.
+    pub slot: u64,
+    /// The hash of the execution block of the slot, if any and if known.
+    pub block_hash: Option<B256>,
+    /// The source of the block of the slot, if any.
+    pub source: Option<BlockSource>,
+    /// The number of commitments issued for the slot.
+    pub commitments: usize,
+    /// The number of commitments with all their transactions included.
+    pub fulfilled: usize,
+    /// The number of commitments whose resolution failed, which are not audited.
+    pub unresolved: usize,
+    /// The commitments that were not fulfilled.
+    pub breaches: Vec<Breach>,
+    /// When the slot was audited, in seconds since the UNIX epoch.
+    pub audited_at: u64,
+}
+
+impl SlotAudit {
+    /// Returns the number of breaches the proposer is liable for.
+    pub fn liable_breaches(&self) -> usize {
+        self.breaches.iter().filter(|breach| breach.reason.is_liable()).count()
+    }
+}
+
+/// The report of the fulfillment audits of the last slots served by the sidecar.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FulfillmentReport {
+    /// The total number of audited commitments.
+    pub commitments: usize,
+    /// The total number of fulfilled commitments.
+    pub fulfilled: usize,
+    /// The total number of breached commitments the proposer is liable for.
+    pub liable_breaches: usize,
+    /// The audits of the slots, in ascending order.
+    pub audits: Vec<SlotAudit>,
+}
+
+impl FulfillmentReport {
+    /// Creates a report from the given slot audits.
+    pub fn new(audits: Vec<SlotAudit>) -> Self {
+        let commitments = audits.iter().map(|audit| audit.commitments).sum();
+        let fulfilled = audits.iter().map(|audit| audit.fulfilled).sum();
+        let liable_breaches = audits.iter().map(SlotAudit::liable_breaches).sum();
+
+        Self { commitments, fulfilled, liable_breaches, audits }
+    }
+}
+
+/// The block proposed in a slot, as seen in the head events.
+#[derive(Debug, Clone, Copy)]
+struct SlotHead {
+    block_hash: Option<B256>,
+    source: BlockSource,
+}
+
+/// Audits the fulfillment of the commitments issued by the sidecar once their slots are over,
+/// classifying the reason of each breach.
+///
+/// Audits are recorded in the [CommitmentIndex] to be served by the commitments API, and
+/// optionally appended as JSON lines to a log file to keep an accountability record.
+#[derive(Debug)]
+pub struct FulfillmentAuditor {
+    /// The index of the issued commitments, with their resolved state.
+    index: Arc<CommitmentIndex>,
+    /// The path of the audit log file, if any.
+    log_path: Option<PathBuf>,
+    /// The blocks of the slots not audited yet.
+    heads: BTreeMap<u64, SlotHead>,
+    /// The last audited slot.
+    last_audited_slot: u64,
+}
+
+impl FulfillmentAuditor {
+    /// Creates a new auditor for the commitments of the given index.
+    pub fn new(index: Arc<CommitmentIndex>) -> Self {
+        Self { index, log_path: None, heads: BTreeMap::new(), last_audited_slot: 0 }
+    }
+
+    /// Sets the path of the audit log file. The audits already in the file are loaded in the
+    /// index, so that the report survives restarts.
+    pub fn with_log_path(mut self, path: PathBuf) -> Self {
+        match load_audits(&path) {
+            Ok(audits) => {
+                debug!(count = audits.len(), ?path, "Loaded fulfillment audits");
+                for audit in audits {
+                    self.last_audited_slot = self.last_audited_slot.max(audit.slot);
+                    self.index.record_audit(audit);
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => warn!(?err, ?path, "Failed to load the fulfillment audit log"),
+        }
+
+        self.log_path = Some(path);
+        self
+    }
+
+    /// Records the block proposed in a slot, from a new head event.
+    pub fn on_new_head(&mut self, slot: u64, block_hash: Option<B256>, source: BlockSource) {
+        self.heads.insert(slot, SlotHead { block_hash, source });
+    }
+
+    /// Audits the commitments of the slots up to the given one, which must have been resolved
+    /// by the execution state. Slots without a recorded head are considered missed.
+    pub fn audit_until(&mut self, slot: u64) {
+        if slot <= self.last_audited_slot {
+            return;
+        }
+
+        for (audit_slot, statuses) in self.index.statuses_between(self.last_audited_slot, slot) {
+            let head = self.heads.get(&audit_slot).copied();
+            let audit = audit_slot_commitments(audit_slot, head, &statuses);
+
+            for breach in &audit.breaches {
+                warn!(
+                    slot = audit_slot,
+                    digest = %breach.digest,
+                    reason = breach.reason.as_str(),
+                    "Commitment not fulfilled"
+                );
+                ApiMetrics::increment_breached_commitments(breach.reason.as_str());
+            }
+            ApiMetrics::increment_fulfilled_commitments(audit.fulfilled as u64);
+
+            if let Err(err) = self.append_to_log(&audit) {
+                warn!(?err, slot = audit_slot, "Failed to persist the fulfillment audit");
+            }
+            self.index.record_audit(audit);
+        }
+
+        self.last_audited_slot = slot;
+        self.heads.retain(|head_slot, _| *head_slot > slot);
+    }
+
+    /// Appends the audit to the log file as a JSON line, if any.
+    fn append_to_log(&self, audit: &SlotAudit) -> io::Result<()> {
+        let Some(path) = &self.log_path else { return Ok(()) };
+
+        let mut line = serde_json::to_string(audit)?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(line.as_bytes())
+    }
+}
+
+/// Classifies the resolved commitments of a slot, given the block proposed in it.
+fn audit_slot_commitments(
+    slot: u64,
+    head: Option<SlotHead>,
+    statuses: &[CommitmentStatus],
+) -> SlotAudit {
+    let mut audit = SlotAudit {
+        slot,
+        block_hash: head.and_then(|head| head.block_hash),
+        source: head.map(|head| head.source),
+        commitments: statuses.len(),
+        fulfilled: 0,
+        unresolved: 0,
+        breaches: Vec::new(),
+        audited_at: current_timestamp(),
+    };
+
+    for status in statuses {
+        let reason = match status.state {
+            CommitmentState::Included => {
+                audit.fulfilled += 1;
+                continue;
+            }
+            CommitmentState::Accepted | CommitmentState::Submitted => {
+                audit.unresolved += 1;
+                continue;
+            }
+            CommitmentState::Invalidated => BreachReason::InvalidatedByUser,
+            CommitmentState::Missed => match head.map(|head| head.source) {
+                None => BreachReason::SlotMissed,
+                Some(BlockSource::Relay) => BreachReason::RelayBlockWithoutTx,
+                Some(BlockSource::Local) => BreachReason::FallbackBlockWithoutTx,
+            },
+        };
+
+        let missing_tx_hashes = status
+            .tx_hashes
+            .iter()
+            .filter(|hash| !status.receipts.iter().any(|r| r.transaction_hash == **hash))
+            .copied()
+            .collect();
+
+        audit.breaches.push(Breach { digest: status.digest, missing_tx_hashes, reason });
+    }
+
+    audit
+}
+
+/// Loads the audits from the log file at the given path, skipping the malformed lines.
+fn load_audits(path: &PathBuf) -> io::Result<Vec<SlotAudit>> {
+    let contents = fs::read_to_string(path)?;
+
+    let audits = contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(audit) => Some(audit),
+            Err(err) => {
+                warn!(?err, "Skipping malformed fulfillment audit");
+                None
+            }
+        })
+        .collect();
+
+    Ok(audits)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{Address, Bloom},
+        rpc::types::TransactionReceipt,
+        signers::{k256::SecretKey, local::PrivateKeySigner},
+    };
+    use serde_json::json;
+
+    use crate::{
+        primitives::{misc::IntoSigned, signature::AlloySignatureWrapper},
+        state::commitment_index::TargetBlock,
+        test_util::{create_signed_inclusion_request, default_test_transaction},
+    };
+
+    use super::*;
+
+    fn status(digest: u8, state: CommitmentState) -> CommitmentStatus {
+        CommitmentStatus {
+            digest: B256::with_last_byte(digest),
+            slot: 10,
+            tx_hashes: vec![TxHash::with_last_byte(digest)],
+            state,
+            accepted_at: 0,
+            submitted_at: None,
+            finalized_at: None,
+            receipts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_audit_slot_commitments() {
+        let statuses = [
+            status(1, CommitmentState::Included),
+            status(2, CommitmentState::Missed),
+            status(3, CommitmentState::Invalidated),
+            status(4, CommitmentState::Submitted),
+        ];
+
+        let relay_head = SlotHead { block_hash: Some(B256::ZERO), source: BlockSource::Relay };
+        let audit = audit_slot_commitments(10, Some(relay_head), &statuses);
+        assert_eq!((audit.commitments, audit.fulfilled, audit.unresolved), (4, 1, 1));
+        assert_eq!(audit.breaches[0].reason, BreachReason::RelayBlockWithoutTx);
+        assert_eq!(audit.breaches[0].missing_tx_hashes, vec![TxHash::with_last_byte(2)]);
+        assert_eq!(audit.breaches[1].reason, BreachReason::InvalidatedByUser);
+        assert_eq!(audit.liable_breaches(), 1);
+
+        let local_head = SlotHead { block_hash: None, source: BlockSource::Local };
+        let audit = audit_slot_commitments(10, Some(local_head), &statuses);
+        assert_eq!(audit.breaches[0].reason, BreachReason::FallbackBlockWithoutTx);
+
+        let audit = audit_slot_commitments(10, None, &statuses);
+        assert_eq!(audit.breaches[0].reason, BreachReason::SlotMissed);
+    }
+
+    fn receipt(tx_hash: TxHash, block: TargetBlock) -> TransactionReceipt {
+        serde_json::from_value(json!({
+            "type": "0x2",
+            "status": "0x1",
+            "cumulativeGasUsed": "0x5208",
+            "logs": [],
+            "logsBloom": Bloom::ZERO,
+            "transactionHash": tx_hash,
+            "transactionIndex": "0x0",
+            "blockHash": block.hash,
+            "blockNumber": format!("{:#x}", block.number),
+            "gasUsed": "0x5208",
+            "effectiveGasPrice": "0x3b9aca00",
+            "from": Address::ZERO,
+            "to": Address::ZERO,
+            "contractAddress": null
+        }))
+        .expect("valid receipt")
+    }
+
+    #[tokio::test]
+    async fn test_audit_until() -> eyre::Result<()> {
+        let index = Arc::new(CommitmentIndex::default());
+        let log_path =
+            std::env::temp_dir().join(format!("bolt-audits-{}.jsonl", uuid::Uuid::now_v7()));
+        let mut auditor = FulfillmentAuditor::new(index.clone()).with_log_path(log_path.clone());
+
+        let sk = SecretKey::random(&mut rand::thread_rng());
+        let sender = PrivateKeySigner::from(sk.clone()).address();
+        let mut tx_hashes = Vec::new();
+        for (nonce, slot) in [(0, 10), (1, 11)] {
+            let tx = default_test_transaction(sender, Some(nonce));
+            let request = create_signed_inclusion_request(&[tx], &sk, slot).await?;
+            let commitment = request.into_signed(AlloySignatureWrapper::test_signature());
+            tx_hashes.push(*commitment.txs[0].hash());
+            index.on_accepted(commitment);
+        }
+
+        // Slot 10 has no head: its transaction lands in the block of slot 11, with the other one
+        let block = TargetBlock { number: 100, hash: Some(B256::repeat_byte(1)) };
+        let receipts = tx_hashes.iter().map(|hash| receipt(*hash, block)).collect::<Vec<_>>();
+        auditor.on_new_head(11, block.hash, BlockSource::Relay);
+        index.on_resolved(&tx_hashes[..1], &receipts, None);
+        index.on_resolved(&tx_hashes[1..], &receipts, Some(block));
+        auditor.audit_until(11);
+
+        let report = index.fulfillment_report();
+        assert_eq!((report.commitments, report.fulfilled, report.liable_breaches), (2, 1, 1));
+
+        let missed = &report.audits[0];
+        assert_eq!((missed.slot, missed.source, missed.fulfilled), (10, None, 0));
+        assert_eq!(missed.breaches[0].reason, BreachReason::SlotMissed);
+        assert_eq!(missed.breaches[0].missing_tx_hashes, tx_hashes[..1]);
+
+        let fulfilled = &report.audits[1];
+        assert_eq!((fulfilled.slot, fulfilled.block_hash), (11, block.hash));
+        assert!(fulfilled.breaches.is_empty());
+
+        // Slots are audited once
+        auditor.audit_until(11);
+        assert_eq!(index.fulfillment_report().audits.len(), 2);
+
+        // The audits are persisted and reloaded in a new index on restart
+        let reloaded_index = Arc::new(CommitmentIndex::default());
+        let mut reloaded =
+            FulfillmentAuditor::new(reloaded_index.clone()).with_log_path(log_path.clone());
+        assert_eq!(reloaded_index.fulfillment_report(), report);
+
+        // The reloaded slots are not audited again
+        reloaded.audit_until(11);
+        assert_eq!(fs::read_to_string(&log_path)?.lines().count(), 2);
+
+        fs::remove_file(log_path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,351 @@
+use std::collections::{BTreeMap, HashMap};
+
+use alloy::{
+    primitives::{TxHash, B256},
+    rpc::types::TransactionReceipt,
+};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+use crate::{common::time::current_timestamp, primitives::commitment::InclusionCommitment};
+
+use super::auditor::{FulfillmentReport, SlotAudit};
+
+/// The number of slots for which commitments are tracked. Older slots are pruned.
+const MAX_TRACKED_SLOTS: usize = 256;
+
+/// The lifecycle state of an inclusion commitment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CommitmentState {
+    /// The commitment has been issued and its constraints added to the block template.
+    Accepted,
+    /// The constraints of the commitment have been submitted to the constraints API at the
+    /// commitment deadline.
+    Submitted,
+    /// The constraints of the commitment have been removed from the block template, because
+    /// the nonce or balance of a sender changed.
+    Invalidated,
+    /// All the transactions of the commitment have been included in the target block.
+    Included,
+    /// Some transactions of the commitment are missing from the target block.
+    Missed,
+}
+
+impl CommitmentState {
+    /// Returns whether the state is final, i.e. the commitment won't transition anymore.
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Invalidated | Self::Included | Self::Missed)
+    }
+}
+
+/// The status of an inclusion commitment, as returned by the commitment status API.
+/// Timestamps are in seconds since the UNIX epoch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitmentStatus {
+    /// The digest of the inclusion request.
+    pub digest: B256,
+    /// The target slot of the commitment.
+    pub slot: u64,
+    /// The hashes of the committed transactions.
+    pub tx_hashes: Vec<TxHash>,
+    /// The current state of the commitment.
+    pub state: CommitmentState,
+    /// When the commitment was issued.
+    pub accepted_at: u64,
+    /// When the constraints were submitted to the constraints API, if they were.
+    pub submitted_at: Option<u64>,
+    /// When the commitment reached its final state, if it did.
+    pub finalized_at: Option<u64>,
+    /// The receipts of the committed transactions that were included, once resolved.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub receipts: Vec<TransactionReceipt>,
+}
+
+/// The execution block of the target slot of resolved commitments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TargetBlock {
+    /// The block number.
+    pub number: u64,
+    /// The block hash, if known.
+    pub hash: Option<B256>,
+}
+
+impl TargetBlock {
+    /// Returns whether the transaction of the given receipt was included in this block.
+    fn contains(&self, receipt: &TransactionReceipt) -> bool {
+        receipt.block_number == Some(self.number) &&
+            self.hash.is_none_or(|hash| receipt.block_hash == Some(hash))
+    }
+}
+
+/// A commitment issued by the sidecar, with its status.
+#[derive(Debug)]
+struct IndexedCommitment {
+    commitment: InclusionCommitment,
+    status: CommitmentStatus,
+}
+
+#[derive(Debug, Default)]
+struct IndexInner {
+    /// The issued commitments and their status, by request digest.
+    commitments: HashMap<B256, IndexedCommitment>,
+    /// The request digest of each committed transaction.
+    digests_by_tx: HashMap<TxHash, B256>,
+    /// The request digests of the commitments of each slot.
+    digests_by_slot: BTreeMap<u64, Vec<B256>>,
+    /// The fulfillment audits of the slots served by the sidecar.
+    audits: BTreeMap<u64, SlotAudit>,
+}
+
+impl IndexInner {
+    /// Finds a commitment by request digest, or by the hash of one of its transactions.
+    fn find(&self, hash: &B256) -> Option<&IndexedCommitment> {
+        self.commitments
+            .get(hash)
+            .or_else(|| self.digests_by_tx.get(hash).and_then(|d| self.commitments.get(d)))
+    }
+
+    fn remove_slot(&mut self, slot: u64) {
+        for digest in self.digests_by_slot.remove(&slot).unwrap_or_default() {
+            if let Some(indexed) = self.commitments.remove(&digest) {
+                for hash in indexed.status.tx_hashes {
+                    self.digests_by_tx.remove(&hash);
+                }
+            }
+        }
+    }
+}
+
+/// An index of the inclusion commitments issued by the sidecar and their lifecycle, updated
+/// by the driver as the slots progress and queried by the commitments API.
+#[derive(Debug, Default)]
+pub struct CommitmentIndex {
+    inner: RwLock<IndexInner>,
+}
+
+impl CommitmentIndex {
+    /// Records a new commitment issued by the sidecar.
+    pub fn on_accepted(&self, commitment: InclusionCommitment) {
+        let digest = commitment.digest();
+        let slot = commitment.slot;
+        let tx_hashes = commitment.txs.iter().map(|tx| *tx.hash()).collect::<Vec<_>>();
+
+        let mut inner = self.inner.write();
+
+        for hash in &tx_hashes {
+            inner.digests_by_tx.insert(*hash, digest);
+        }
+        inner.digests_by_slot.entry(slot).or_default().push(digest);
+        let status = CommitmentStatus {
+            digest,
+            slot,
+            tx_hashes,
+            state: CommitmentState::Accepted,
+            accepted_at: current_timestamp(),
+            submitted_at: None,
+            finalized_at: None,
+            receipts: Vec::new(),
+        };
+        inner.commitments.insert(digest, IndexedCommitment { commitment, status });
+
+        while inner.digests_by_slot.len() > MAX_TRACKED_SLOTS {
+            let Some(&oldest) = inner.digests_by_slot.keys().next() else { break };
+            inner.remove_slot(oldest);
+        }
+    }
+
+    /// Records the submission of the constraints of the given slot to the constraints API.
+    pub fn on_submitted(&self, slot: u64) {
+        let mut inner = self.inner.write();
+        let IndexInner { commitments, digests_by_slot, .. } = &mut *inner;
+        let Some(digests) = digests_by_slot.get(&slot) else { return };
+
+        let now = current_timestamp();
+        for indexed in digests.iter().filter_map(|digest| commitments.get_mut(digest)) {
+            let status = &mut indexed.status;
+            if status.state == CommitmentState::Accepted {
+                status.state = CommitmentState::Submitted;
+                status.submitted_at = Some(now);
+            }
+        }
+    }
+
+    /// Records the removal of the given transaction from its block template, which invalidates
+    /// its whole commitment.
+    pub fn on_invalidated(&self, tx_hash: &TxHash) {
+        let mut inner = self.inner.write();
+        let Some(digest) = inner.digests_by_tx.get(tx_hash).copied() else { return };
+        let Some(indexed) = inner.commitments.get_mut(&digest) else { return };
+
+        let status = &mut indexed.status;
+        if !status.state.is_final() {
+            status.state = CommitmentState::Invalidated;
+            status.finalized_at = Some(current_timestamp());
+        }
+    }
+
+    /// Resolves the commitments of the given transactions, once the block of their target slot
+    /// is known, with the receipts found for them. Commitments with all their transactions
+    /// included in the target block are marked as included, the others as missed.
+    ///
+    /// `block` is the block of the target slot, or `None` if the slot has no block: then the
+    /// transactions can only have been included in a later block, and are all missed.
+    pub fn on_resolved(
+        &self,
+        tx_hashes: &[TxHash],
+        receipts: &[TransactionReceipt],
+        block: Option<TargetBlock>,
+    ) {
+        let mut inner = self.inner.write();
+        let IndexInner { commitments, digests_by_tx, .. } = &mut *inner;
+
+        let now = current_timestamp();
+        for digest in tx_hashes.iter().filter_map(|hash| digests_by_tx.get(hash)) {
+            // Commitments with several transactions are seen several times, but resolved once.
+            let Some(IndexedCommitment { status, .. }) = commitments.get_mut(digest) else {
+                continue;
+            };
+            if status.state.is_final() {
+                continue;
+            }
+
+            let tx_receipts = status
+                .tx_hashes
+                .iter()
+                .filter_map(|hash| receipts.iter().find(|r| r.transaction_hash == *hash))
+                .filter(|receipt| block.is_some_and(|block| block.contains(receipt)))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            status.state = if tx_receipts.len() == status.tx_hashes.len() {
+                CommitmentState::Included
+            } else {
+                CommitmentState::Missed
+            };
+            status.receipts = tx_receipts;
+            status.finalized_at = Some(now);
+        }
+    }
+
+    /// Returns the status of the commitments of each slot in the range `(from, to]`, in
+    /// ascending slot order.
+    pub fn statuses_between(&self, from: u64, to: u64) -> Vec<(u64, Vec<CommitmentStatus>)> {
+        if from >= to {
+            return Vec::new();
+        }
+
+        let inner = self.inner.read();
+        inner
+            .digests_by_slot
+            .range(from + 1..=to)
+            .map(|(slot, digests)| {
+                let statuses = digests
+                    .iter()
+                    .filter_map(|digest| inner.commitments.get(digest))
+                    .map(|indexed| indexed.status.clone())
+                    .collect();
+                (*slot, statuses)
+            })
+            .collect()
+    }
+
+    /// Records the fulfillment audit of a slot.
+    pub fn record_audit(&self, audit: SlotAudit) {
+        let mut inner = self.inner.write();
+        inner.audits.insert(audit.slot, audit);
+
+        while inner.audits.len() > MAX_TRACKED_SLOTS {
+            inner.audits.pop_first();
+        }
+    }
+
+    /// Returns the report of the fulfillment audits of the last slots served by the sidecar.
+    pub fn fulfillment_report(&self) -> FulfillmentReport {
+        let inner = self.inner.read();
+        FulfillmentReport::new(inner.audits.values().cloned().collect())
+    }
+
+    /// Returns the commitment with the given request digest or transaction hash.
+    pub fn get_commitment(&self, hash: &B256) -> Option<InclusionCommitment> {
+        let inner = self.inner.read();
+        inner.find(hash).map(|indexed| indexed.commitment.clone())
+    }
+
+    /// Returns the status of the commitment with the given request digest or transaction hash.
+    pub fn get_status(&self, hash: &B256) -> Option<CommitmentStatus> {
+        let inner = self.inner.read();
+        inner.find(hash).map(|indexed| indexed.status.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::signers::{k256::SecretKey, local::PrivateKeySigner};
+
+    use crate::{
+        primitives::{misc::IntoSigned, signature::AlloySignatureWrapper},
+        test_util::{create_signed_inclusion_request, default_test_transaction},
+    };
+
+    use super::*;
+
+    async fn create_commitment(nonce: u64, slot: u64) -> eyre::Result<InclusionCommitment> {
+        let sk = SecretKey::random(&mut rand::thread_rng());
+        let signer = PrivateKeySigner::from(sk.clone());
+        let tx = default_test_transaction(signer.address(), Some(nonce));
+        let request = create_signed_inclusion_request(&[tx], &sk, slot).await?;
+
+        Ok(request.into_signed(AlloySignatureWrapper::test_signature()))
+    }
+
+    #[tokio::test]
+    async fn test_commitment_lifecycle() -> eyre::Result<()> {
+        let index = CommitmentIndex::default();
+
+        let commitment = create_commitment(0, 10).await?;
+        let other_commitment = create_commitment(1, 10).await?;
+        let (digest, other_digest) = (commitment.digest(), other_commitment.digest());
+        let (tx, other_tx) = (*commitment.txs[0].hash(), *other_commitment.txs[0].hash());
+
+        index.on_accepted(commitment);
+        index.on_accepted(other_commitment);
+        assert_eq!(index.get_status(&digest).unwrap().state, CommitmentState::Accepted);
+        assert_eq!(index.get_commitment(&tx).unwrap().digest(), digest);
+
+        index.on_invalidated(&other_tx);
+        index.on_submitted(10);
+        assert_eq!(index.get_status(&digest).unwrap().state, CommitmentState::Submitted);
+        assert_eq!(index.get_status(&other_digest).unwrap().state, CommitmentState::Invalidated);
+
+        // No receipts: the remaining commitment is missed, the invalidated one is unchanged
+        let block = TargetBlock { number: 1, hash: Some(B256::repeat_byte(1)) };
+        index.on_resolved(&[tx], &[], Some(block));
+        let status = index.get_status(&tx).unwrap();
+        assert_eq!(status.state, CommitmentState::Missed);
+        assert!(status.submitted_at.is_some() && status.finalized_at.is_some());
+        assert_eq!(index.get_status(&other_tx).unwrap().state, CommitmentState::Invalidated);
+
+        assert!(index.get_status(&B256::ZERO).is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_prune_old_slots() -> eyre::Result<()> {
+        let index = CommitmentIndex::default();
+
+        let oldest = create_commitment(0, 0).await?;
+        let oldest_digest = oldest.digest();
+        index.on_accepted(oldest);
+
+        for slot in 1..=MAX_TRACKED_SLOTS as u64 {
+            index.on_accepted(create_commitment(0, slot).await?);
+        }
+
+        assert!(index.get_status(&oldest_digest).is_none());
+
+        Ok(())
+    }
+}
@@ -4,9 +4,9 @@ use std::{
 };
 
 use beacon_api_client::ProposerDuty;
-use ethereum_consensus::{crypto::PublicKey as BlsPublicKey, phase0::mainnet::SLOTS_PER_EPOCH};
+use ethereum_consensus::crypto::PublicKey as BlsPublicKey;
 use tokio::join;
-use tracing::debug;
+use tracing::{debug, warn};
 
 use super::CommitmentDeadline;
 use crate::{
@@ -42,6 +42,11 @@ struct Epoch {
     /// NOTE: if the `unsafe_lookhead` flag is enabled, then this field also contains
     /// the next epoch's proposer duties.
     pub proposer_duties: Vec<ProposerDuty>,
+    /// The proposer duties of the next epoch, if they are not already part of
+    /// `proposer_duties`, i.e. if the `unsafe_lookahead` flag is disabled.
+    ///
+    /// NOTE: these are not used to validate requests, only to announce the lookahead.
+    pub next_proposer_duties: Vec<ProposerDuty>,
 }
 
 /// Represents the consensus state container for the sidecar.
@@ -72,6 +77,8 @@ pub struct ConsensusState {
     /// It is considered unsafe because it is possible for the next epoch's duties to
     /// change if there are beacon chain deposits or withdrawals in the current epoch.
     unsafe_lookahead_enabled: bool,
+    /// The number of slots per epoch of the chain.
+    slots_per_epoch: u64,
 }
 
 impl fmt::Debug for ConsensusState {
@@ -83,6 +90,7 @@ impl fmt::Debug for ConsensusState {
             .field("commitment_deadline", &self.commitment_deadline)
             .field("commitment_deadline_duration", &self.commitment_deadline_duration)
             .field("unsafe_lookahead_enabled", &self.unsafe_lookahead_enabled)
+            .field("slots_per_epoch", &self.slots_per_epoch)
             .finish()
     }
 }
@@ -93,6 +101,7 @@ impl ConsensusState {
         beacon_api_client: BeaconClient,
         commitment_deadline_duration: Duration,
         unsafe_lookahead_enabled: bool,
+        slots_per_epoch: u64,
     ) -> Self {
         Self {
             beacon_api_client,
@@ -102,6 +111,7 @@ impl ConsensusState {
             commitment_deadline: CommitmentDeadline::new(0, commitment_deadline_duration),
             commitment_deadline_duration,
             unsafe_lookahead_enabled,
+            slots_per_epoch,
         }
     }
 
@@ -151,13 +161,13 @@ impl ConsensusState {
         self.latest_slot = slot;
 
         // Calculate the current value of epoch
-        let epoch = slot / SLOTS_PER_EPOCH;
+        let epoch = slot / self.slots_per_epoch;
 
         // If the epoch has changed, update the proposer duties
         if epoch != self.epoch.value {
             debug!("Updating epoch to {epoch}");
             self.epoch.value = epoch;
-            self.epoch.start_slot = epoch * SLOTS_PER_EPOCH;
+            self.epoch.start_slot = epoch * self.slots_per_epoch;
 
             self.fetch_proposer_duties(epoch).await?;
         } else if self.epoch.proposer_duties.is_empty() {
@@ -169,31 +179,48 @@ impl ConsensusState {
         Ok(())
     }
 
-    /// Fetch proposer duties for the given epoch and the next one if the unsafe lookahead flag is
-    /// set
+    /// Fetch proposer duties for the given epoch and the next one. The next epoch's duties are
+    /// used to validate requests only if the unsafe lookahead flag is set.
     async fn fetch_proposer_duties(&mut self, epoch: u64) -> Result<(), ConsensusError> {
-        let duties = if self.unsafe_lookahead_enabled {
-            let two_epoch_duties = join!(
-                self.beacon_api_client.get_proposer_duties(epoch),
-                self.beacon_api_client.get_proposer_duties(epoch + 1)
-            );
-
-            match two_epoch_duties {
-                (Ok((_, mut duties)), Ok((_, next_duties))) => {
-                    duties.extend(next_duties);
-                    duties
-                }
-                (Err(e), _) | (_, Err(e)) => return Err(ConsensusError::BeaconApiError(e)),
+        let (duties, next_duties) = join!(
+            self.beacon_api_client.get_proposer_duties(epoch),
+            self.beacon_api_client.get_proposer_duties(epoch + 1)
+        );
+
+        let mut duties = duties?.1;
+        let next_duties = match next_duties {
+            Ok((_, next_duties)) => next_duties,
+            Err(e) if self.unsafe_lookahead_enabled => return Err(e.into()),
+            Err(e) => {
+                // The next epoch's duties are only announced in this case, so don't fail.
+                warn!(?e, epoch = epoch + 1, "Failed to fetch the next epoch's proposer duties");
+                Vec::new()
             }
-        } else {
-            self.beacon_api_client.get_proposer_duties(epoch).await?.1
         };
 
+        if self.unsafe_lookahead_enabled {
+            duties.extend(next_duties);
+            self.epoch.next_proposer_duties = Vec::new();
+        } else {
+            self.epoch.next_proposer_duties = next_duties;
+        }
+
         self.epoch.proposer_duties = duties;
 
         Ok(())
     }
 
+    /// Returns the current epoch.
+    pub fn epoch(&self) -> u64 {
+        self.epoch.value
+    }
+
+    /// Returns the proposer duties of the current and next epoch, whether or not the unsafe
+    /// lookahead is enabled.
+    pub fn lookahead(&self) -> impl Iterator<Item = &ProposerDuty> {
+        self.epoch.proposer_duties.iter().chain(self.epoch.next_proposer_duties.iter())
+    }
+
     /// Finds the validator public key for the given slot from the proposer duties.
     fn find_validator_pubkey_for_slot(&self, slot: u64) -> Result<BlsPublicKey, ConsensusError> {
         self.epoch
@@ -208,14 +235,15 @@ impl ConsensusState {
     /// the current epoch or next epoch (if unsafe lookahead is enabled)
     fn furthest_slot(&self) -> u64 {
         self.epoch.start_slot +
-            SLOTS_PER_EPOCH +
-            if self.unsafe_lookahead_enabled { SLOTS_PER_EPOCH } else { 0 }
+            self.slots_per_epoch +
+            if self.unsafe_lookahead_enabled { self.slots_per_epoch } else { 0 }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use beacon_api_client::BlockId;
+    use ethereum_consensus::phase0::mainnet::SLOTS_PER_EPOCH;
     use reqwest::Url;
     use tracing::warn;
 
@@ -245,6 +273,7 @@ mod tests {
             commitment_deadline: CommitmentDeadline::new(0, commitment_deadline_duration),
             commitment_deadline_duration,
             unsafe_lookahead_enabled: false,
+            slots_per_epoch: SLOTS_PER_EPOCH,
         };
 
         // Update the slot to 32
@@ -291,6 +320,7 @@ mod tests {
             commitment_deadline_duration,
             // We test for both epochs
             unsafe_lookahead_enabled: true,
+            slots_per_epoch: SLOTS_PER_EPOCH,
         };
 
         let epoch =
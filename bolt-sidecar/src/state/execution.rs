@@ -1,10 +1,9 @@
 use alloy::{
     consensus::{BlobTransactionValidationError, EnvKzgSettings, Transaction},
-    eips::eip4844::MAX_BLOBS_PER_BLOCK,
-    primitives::{Address, U256},
+    primitives::{Address, B256, U256},
     transports::TransportError,
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 use thiserror::Error;
 use tracing::{debug, error, trace, warn};
 
@@ -14,7 +13,7 @@ use crate::{
         score_cache::ScoreCache,
         transactions::{calculate_max_basefee, max_transaction_cost, validate_transaction},
     },
-    config::limits::LimitsOpts,
+    config::{chain::UnsupportedForkError, limits::LimitsOpts, ChainConfig},
     primitives::{
         signature::SignatureError, AccountState, InclusionRequest, SignedConstraints, Slot,
     },
@@ -22,7 +21,10 @@ use crate::{
     telemetry::ApiMetrics,
 };
 
-use super::{account_state::AccountStateCache, fetcher::StateFetcher, InclusionPricer};
+use super::{
+    account_state::AccountStateCache, commitment_index::TargetBlock, fetcher::StateFetcher,
+    BlockStateDiff, CommitmentIndex, InclusionPricer,
+};
 
 /// Possible commitment validation errors.
 ///
@@ -35,6 +37,9 @@ pub enum ValidationError {
     /// The transaction blob fee is too low to cover the maximum blob base fee.
     #[error("Transaction blob fee is too low, need {0} gwei to cover the maximum blob basefee")]
     BlobBaseFeeTooLow(u128),
+    /// The priority fee doesn't pay for the blobs of the transaction on top of its gas.
+    #[error("Priority fee paid for blobs {0} is less than the min blob fee {1}")]
+    BlobTipTooLow(u128, u128),
     /// The transaction blob is invalid.
     #[error(transparent)]
     BlobValidation(#[from] BlobTransactionValidationError),
@@ -86,6 +91,12 @@ pub enum ValidationError {
     /// The transaction chain ID does not match the expected chain ID.
     #[error("Chain ID mismatch")]
     ChainIdMismatch,
+    /// The target slot is not in a fork supported by the sidecar.
+    #[error(transparent)]
+    UnsupportedFork(#[from] UnsupportedForkError),
+    /// The execution state is out of date, as the last head update failed.
+    #[error("Execution state is stale, the sidecar is not synced with the execution client")]
+    StaleState,
     /// NOTE: this should not be exposed to the user.
     #[error("Internal error: {0}")]
     Internal(String),
@@ -102,6 +113,7 @@ impl ValidationError {
         match self {
             Self::BaseFeeTooLow(_) => "base_fee_too_low",
             Self::BlobBaseFeeTooLow(_) => "blob_base_fee_too_low",
+            Self::BlobTipTooLow(_, _) => "blob_tip_too_low",
             Self::BlobValidation(_) => "blob_validation",
             Self::MaxBaseFeeCalcOverflow => "max_base_fee_calc_overflow",
             Self::NonceTooLow(_, _) => "nonce_too_low",
@@ -119,6 +131,8 @@ impl ValidationError {
             Self::Signature(_) => "signature",
             Self::RecoverSigner => "recover_signer",
             Self::ChainIdMismatch => "chain_id_mismatch",
+            Self::UnsupportedFork(_) => "unsupported_fork",
+            Self::StaleState => "stale_state",
             Self::Internal(_) => "internal",
         }
     }
@@ -138,6 +152,9 @@ impl ValidationError {
 pub struct ExecutionState<C> {
     /// The latest block number.
     block_number: u64,
+    /// The hash of the latest block, if known. It is used to apply the streamed state diff of
+    /// the next block only on top of its parent, and to detect reorgs of the head block.
+    block_hash: Option<B256>,
     /// The latest slot number.
     slot: u64,
     /// The basefee at the head block.
@@ -149,6 +166,10 @@ pub struct ExecutionState<C> {
     ///
     /// INVARIANT: the entries are modified only when receiving a new head.
     account_states: AccountStateCache,
+    /// The epoch of the last head at which the account states were fetched from the client.
+    /// Streamed state diffs miss balance changes outside of transactions, like withdrawals and
+    /// system calls, so the accounts are fetched again at least once per epoch.
+    fetched_epoch: Option<u64>,
     /// The block templates by target SLOT NUMBER.
     /// We have multiple block templates because in rare cases we might have multiple
     /// proposal duties for a single lookahead.
@@ -159,6 +180,8 @@ pub struct ExecutionState<C> {
     block_templates: HashMap<Slot, BlockTemplate>,
     /// The chain ID of the chain (constant).
     chain_id: u64,
+    /// The chain configuration, used to apply the limits of the fork of the target slot.
+    chain: ChainConfig,
     /// The limits set for the sidecar.
     limits: LimitsOpts,
     /// The KZG settings for validating blobs.
@@ -169,6 +192,13 @@ pub struct ExecutionState<C> {
     validation_params: ValidationParams,
     /// Pricing calculator for preconfirmations.
     pricing: InclusionPricer,
+    /// Whether the state is known to be out of date, because the last head update failed.
+    stale: bool,
+    /// Whether to reject requests while the state is stale.
+    stale_state_guard: bool,
+    /// The index of the issued commitments, updated when their constraints are invalidated
+    /// or their slot is resolved.
+    commitment_index: Arc<CommitmentIndex>,
 }
 
 /// Other values used for validation.
@@ -212,20 +242,52 @@ impl<C: StateFetcher> ExecutionState<C> {
             basefee,
             blob_basefee,
             block_number,
+            block_hash: None,
             chain_id,
+            chain: ChainConfig::default(),
             limits,
             client,
             slot: 0,
             account_states: AccountStateCache(ScoreCache::with_max_len(num_accounts)),
+            fetched_epoch: None,
             block_templates: HashMap::new(),
             // Load the default KZG settings
             kzg_settings: EnvKzgSettings::default(),
             // TODO: add a way to configure these values from CLI
             validation_params: ValidationParams::new(limits.max_committed_gas_per_slot.get()),
             pricing: InclusionPricer::new(limits.max_committed_gas_per_slot.get()),
+            stale: false,
+            stale_state_guard: true,
+            commitment_index: Arc::new(CommitmentIndex::default()),
         })
     }
 
+    /// Sets the chain configuration, used to apply the limits of the fork of the target slot.
+    /// Defaults to the Mainnet configuration.
+    pub fn with_chain(mut self, chain: ChainConfig) -> Self {
+        self.chain = chain;
+        self
+    }
+
+    /// Sets the index of the issued commitments to keep up to date with the block templates.
+    pub fn with_commitment_index(mut self, index: Arc<CommitmentIndex>) -> Self {
+        self.commitment_index = index;
+        self
+    }
+
+    /// Sets whether to reject requests while the state is stale, i.e. after a failed head
+    /// update. Enabled by default.
+    pub fn with_stale_state_guard(mut self, enabled: bool) -> Self {
+        self.stale_state_guard = enabled;
+        self
+    }
+
+    /// Returns true if the state is known to be out of date, because the last head update
+    /// failed.
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
     /// Returns the current base fee in gwei
     pub fn basefee(&self) -> u128 {
         self.basefee
@@ -246,6 +308,10 @@ impl<C: StateFetcher> ExecutionState<C> {
         &mut self,
         req: &mut InclusionRequest,
     ) -> Result<(), ValidationError> {
+        if self.stale && self.stale_state_guard {
+            return Err(ValidationError::StaleState);
+        }
+
         req.recover_signers()?;
 
         let target_slot = req.slot;
@@ -304,19 +370,22 @@ impl<C: StateFetcher> ExecutionState<C> {
 
         // Ensure max_priority_fee_per_gas is greater than or equal to the calculated
         // min_priority_fee
-        if let Err(err) = req.validate_min_priority_fee(
+        let min_priority_fees = match req.validate_min_priority_fee(
             &self.pricing,
             template_committed_gas,
             self.limits.min_inclusion_profit,
             max_basefee,
         ) {
-            return Err(match err {
-                pricing::PricingError::TipTooLow { tip, min_priority_fee } => {
-                    ValidationError::MaxPriorityFeePerGasTooLow(tip, min_priority_fee)
-                }
-                other => ValidationError::Pricing(other),
-            });
-        }
+            Ok(min_priority_fees) => min_priority_fees,
+            Err(err) => {
+                return Err(match err {
+                    pricing::PricingError::TipTooLow { tip, min_priority_fee } => {
+                        ValidationError::MaxPriorityFeePerGasTooLow(tip, min_priority_fee)
+                    }
+                    other => ValidationError::Pricing(other),
+                })
+            }
+        };
 
         if target_slot < self.slot {
             debug!(%target_slot, %self.slot, "Target slot lower than current slot");
@@ -334,7 +403,9 @@ impl<C: StateFetcher> ExecutionState<C> {
         // and balance diffs that will be applied to the account state.
         let mut bundle_nonce_diff_map = HashMap::new();
         let mut bundle_balance_diff_map = HashMap::new();
-        for tx in &req.txs {
+        let mut preconfirmed_blobs =
+            self.get_block_template(target_slot).map(|t| t.blob_count()).unwrap_or(0);
+        for (tx, min_priority_fee) in req.txs.iter().zip(min_priority_fees) {
             let sender = tx.sender().expect("Recovered sender");
 
             let (nonce_diff, balance_diff, highest_slot_for_account) =
@@ -390,10 +461,10 @@ impl<C: StateFetcher> ExecutionState<C> {
 
             // Check EIP-4844-specific limits
             if let Some(transaction) = tx.as_eip4844_with_sidecar() {
-                if let Some(template) = self.block_templates.get(&target_slot) {
-                    if template.blob_count() >= MAX_BLOBS_PER_BLOCK {
-                        return Err(ValidationError::Eip4844Limit);
-                    }
+                let blob_count = transaction.tx().blob_versioned_hashes.len();
+                let max_blobs = self.chain.max_blobs_per_block(target_slot)?;
+                if preconfirmed_blobs + blob_count > max_blobs {
+                    return Err(ValidationError::Eip4844Limit);
                 }
 
                 // Calculate max possible increase in blob basefee
@@ -407,8 +478,26 @@ impl<C: StateFetcher> ExecutionState<C> {
                     return Err(ValidationError::BlobBaseFeeTooLow(max_blob_basefee));
                 }
 
+                // Blobs are paid with the priority fee, on top of the min priority fee of the
+                // transaction gas.
+                let min_blob_fee = self.pricing.calculate_min_blob_fee(
+                    blob_count as u64,
+                    preconfirmed_blobs as u64,
+                    max_blobs as u64,
+                    self.limits.min_profit_per_blob,
+                );
+                let tip = tx.effective_tip_per_gas(max_basefee).unwrap_or_default();
+                let blob_tip = tip.saturating_sub(min_priority_fee) * tx.gas_limit() as u128;
+
+                debug!(%blob_tip, %min_blob_fee, "Validating blob fee");
+                if blob_tip < min_blob_fee {
+                    return Err(ValidationError::BlobTipTooLow(blob_tip, min_blob_fee));
+                }
+
                 // Validate blob against KZG settings
                 transaction.validate_blob(self.kzg_settings.get())?;
+
+                preconfirmed_blobs += blob_count;
             }
 
             // Increase the bundle nonce and balance diffs for this sender for the next iteration
@@ -431,25 +520,53 @@ impl<C: StateFetcher> ExecutionState<C> {
         }
     }
 
-    /// Updates the state corresponding to the provided block number and slot.
-    /// If the block number is not provided, the state will be updated to
-    /// the latest head from the EL.
+    /// Updates the state corresponding to the provided block number and hash, and slot.
+    /// If the block is not provided, the state will be updated to the latest head from the EL.
     pub async fn update_head(
         &mut self,
-        block_number: Option<u64>,
+        block: Option<(u64, B256)>,
         slot: u64,
     ) -> Result<(), TransportError> {
         self.slot = slot;
 
-        let accounts = self.account_states.keys().collect::<Vec<_>>();
-        let update = self.client.get_state_update(accounts, block_number).await?;
-        trace!(%slot, ?update, "Applying execution state update");
+        // The state is out of date until the update of the new head is applied.
+        self.stale = true;
+        ApiMetrics::set_execution_state_stale(true);
+
+        // If the state is already at the requested block thanks to the streamed state diffs,
+        // there is no need to fetch it again. A block with the same number but a different
+        // hash was reorged, and its state must be fetched. The diffs don't track withdrawals
+        // and system calls, so the state is fetched anyway in the first head of a new epoch.
+        let epoch = slot / self.chain.slots_per_epoch();
+        let is_current_block = block.is_some_and(|(number, hash)| {
+            number == self.block_number && self.block_hash == Some(hash)
+        });
+        let update = if is_current_block && self.fetched_epoch == Some(epoch) {
+            None
+        } else {
+            let accounts = self.account_states.keys().collect::<Vec<_>>();
+            let block_number = block.map(|(number, _)| number);
+            let update = self.client.get_state_update(accounts, block_number).await?;
+            trace!(%slot, ?update, "Applying execution state update");
+            self.fetched_epoch = Some(epoch);
+            Some(update)
+        };
+
+        // The block of the new head, to check that the committed transactions of its slot were
+        // included in it rather than in a later block.
+        let head_block = TargetBlock {
+            number: block
+                .map(|(number, _)| number)
+                .or(update.as_ref().map(|update| update.block_number))
+                .unwrap_or(self.block_number),
+            hash: block.map(|(_, hash)| hash),
+        };
 
         // Remove any block templates that are no longer valid
         // NOTE: this needs to be called BEFORE applying the state update or we might remove
         // constraints for which we need to get the receipts.
-        for template in self.remove_block_templates_until(slot) {
-            debug!(%slot, "Removed block template for slot");
+        for (template_slot, template) in self.remove_block_templates_until(slot) {
+            debug!(%slot, %template_slot, "Removed block template for slot");
             let hashes = template.transaction_hashes();
             let receipts = self.client.get_receipts_unordered(hashes.as_ref()).await?;
 
@@ -466,6 +583,12 @@ impl<C: StateFetcher> ExecutionState<C> {
                 receipts_len += 1;
             }
 
+            // The templates of earlier slots are resolved at this head because their slot was
+            // missed: it has no block of its own.
+            let target_block = (template_slot == slot).then_some(head_block);
+            let found_receipts = receipts.iter().flatten().cloned().collect::<Vec<_>>();
+            self.commitment_index.on_resolved(&hashes, &found_receipts, target_block);
+
             // Sanity check with additional logs if there are any discrepancies
             if hashes.len() != receipts_len {
                 warn!(
@@ -482,15 +605,55 @@ impl<C: StateFetcher> ExecutionState<C> {
             }
         }
 
-        self.apply_state_update(update);
+        if let Some(update) = update {
+            self.apply_state_update(update);
+            self.block_hash = block.map(|(_, hash)| hash);
+        }
+
+        self.stale = false;
+        ApiMetrics::set_execution_state_stale(false);
 
         Ok(())
     }
 
+    /// Applies the state diff of a new execution block, streamed from the execution client, to
+    /// the cached account states and refreshes the block templates.
+    ///
+    /// The diff is only applied on top of the parent block: if a block was missed or reorged,
+    /// it is ignored and the state will be fetched again on the next head update. Returns
+    /// whether the diff has been applied.
+    pub fn apply_state_diff(&mut self, diff: BlockStateDiff) -> bool {
+        let is_parent_hash = self.block_hash.is_none_or(|hash| hash == diff.parent_hash);
+        if diff.block_number != self.block_number + 1 || !is_parent_hash {
+            debug!(
+                number = diff.block_number,
+                head = self.block_number,
+                "State diff doesn't apply on top of the current head, ignoring it"
+            );
+            return false;
+        }
+
+        self.block_number = diff.block_number;
+        self.block_hash = Some(diff.block_hash);
+        self.basefee = diff.basefee;
+        self.blob_basefee = diff.blob_basefee;
+
+        for (address, account) in diff.accounts {
+            if let Some(state) = self.account_states.get_mut(&address) {
+                account.apply(state);
+            }
+        }
+
+        self.refresh_templates();
+        true
+    }
+
     fn apply_state_update(&mut self, update: StateUpdate) {
-        // Update head and basefee
+        // Update head, basefee and blob basefee
         self.block_number = update.block_number;
+        self.block_hash = None;
         self.basefee = update.min_basefee;
+        self.blob_basefee = update.min_blob_basefee;
 
         for (address, state) in update.account_states {
             let Some(prev_state) = self.account_states.get_mut(&address) else {
@@ -519,7 +682,9 @@ impl<C: StateFetcher> ExecutionState<C> {
             for template in self.block_templates.values_mut() {
                 // Retain only signed constraints where transactions are still valid based on the
                 // canonical account states.
-                template.retain(address, expected_account_state);
+                for hash in template.retain(address, expected_account_state) {
+                    self.commitment_index.on_invalidated(&hash);
+                }
 
                 // Update the account state with the remaining state diff for the next iteration.
                 if let Some((nonce_diff, balance_diff)) = template.get_diff(&address) {
@@ -537,7 +702,14 @@ impl<C: StateFetcher> ExecutionState<C> {
         self.block_templates.get(&slot)
     }
 
-    /// Removes all the block templates which slot is less then or equal `slot`, and returns them.
+    /// Returns the gas that can still be committed in the given slot.
+    pub fn remaining_committed_gas(&self, slot: u64) -> u64 {
+        let committed_gas = self.block_templates.get(&slot).map_or(0, |t| t.committed_gas());
+        self.limits.max_committed_gas_per_slot.get().saturating_sub(committed_gas)
+    }
+
+    /// Removes all the block templates which slot is less then or equal `slot`, and returns them
+    /// with their slot.
     ///
     /// This should be called when we need to propose a block for the given slot, or when a new
     /// head comes in which makes an older block templates useless.
@@ -545,7 +717,7 @@ impl<C: StateFetcher> ExecutionState<C> {
     /// NOTE: We remove all previous block templates to ensure that, when a new head is received
     /// from the beacon client, all stale template are cleared. This prevents outdated templates
     /// from persisting in cases of missed slots, where such events are not emitted.
-    pub fn remove_block_templates_until(&mut self, slot: u64) -> Vec<BlockTemplate> {
+    pub fn remove_block_templates_until(&mut self, slot: u64) -> Vec<(u64, BlockTemplate)> {
         let mut slots_to_remove =
             self.block_templates.keys().filter(|s| **s <= slot).copied().collect::<Vec<_>>();
         slots_to_remove.sort();
@@ -553,7 +725,7 @@ impl<C: StateFetcher> ExecutionState<C> {
         let mut templates = Vec::with_capacity(slots_to_remove.len());
         for s in slots_to_remove {
             if let Some(template) = self.block_templates.remove(&s) {
-                templates.push(template);
+                templates.push((s, template));
             }
         }
 
@@ -612,9 +784,12 @@ mod tests {
     use std::{num::NonZero, str::FromStr, time::Duration};
 
     use alloy::{
-        consensus::constants::{ETH_TO_WEI, GWEI_TO_WEI},
+        consensus::{
+            constants::{ETH_TO_WEI, GWEI_TO_WEI},
+            SidecarBuilder, SimpleCoder,
+        },
         eips::eip2718::Encodable2718,
-        network::EthereumWallet,
+        network::{EthereumWallet, TransactionBuilder4844},
         primitives::{uint, Uint},
         providers::{network::TransactionBuilder, Provider, ProviderBuilder},
         signers::local::PrivateKeySigner,
@@ -624,8 +799,11 @@ mod tests {
 
     use crate::{
         crypto::SignableBLS,
-        primitives::{ConstraintsMessage, SignedConstraints},
-        state::fetcher,
+        primitives::{
+            misc::IntoSigned, signature::AlloySignatureWrapper, ConstraintsMessage,
+            SignedConstraints,
+        },
+        state::{commitment_index::CommitmentState, fetcher, state_diff_stream::AccountDiff},
         test_util::{create_signed_inclusion_request, default_test_transaction, launch_anvil},
     };
 
@@ -690,6 +868,39 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_stale_state_guard() -> eyre::Result<()> {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let anvil = launch_anvil();
+        let client = StateClient::new(anvil.endpoint_url());
+
+        let mut state = ExecutionState::new(client.clone(), LimitsOpts::default()).await?;
+
+        let sender = anvil.addresses().first().unwrap();
+        let sender_pk = anvil.keys().first().unwrap();
+
+        // Fail to update the head by requesting a block that doesn't exist yet
+        let slot = client.get_head().await?;
+        assert!(state.update_head(Some((slot + 100, B256::ZERO)), slot).await.is_err());
+        assert!(state.is_stale());
+
+        let tx = default_test_transaction(*sender, None);
+        let mut request = create_signed_inclusion_request(&[tx], sender_pk, 10).await?;
+
+        assert!(matches!(
+            state.validate_request(&mut request).await,
+            Err(ValidationError::StaleState)
+        ));
+
+        // A successful update makes the state fresh again
+        state.update_head(None, slot).await?;
+        assert!(!state.is_stale());
+        assert!(state.validate_request(&mut request).await.is_ok());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_invalid_inclusion_slot() -> eyre::Result<()> {
         let _ = tracing_subscriber::fmt::try_init();
@@ -1016,6 +1227,56 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_invalid_inclusion_request_min_blob_fee() -> eyre::Result<()> {
+        let anvil = launch_anvil();
+        let client = StateClient::new(anvil.endpoint_url());
+
+        // Each blob must pay 1M gwei with the priority fee, on top of the min priority fee
+        let limits =
+            LimitsOpts { min_profit_per_blob: 1_000_000 * GWEI_TO_WEI, ..Default::default() };
+
+        // The Anvil block numbers are used as slots, which are only in the Deneb fork with a
+        // schedule starting from genesis
+        let mut state = ExecutionState::new(client.clone(), limits)
+            .await?
+            .with_chain(ChainConfig::kurtosis(0, 0));
+
+        let sender = anvil.addresses().first().unwrap();
+        let sender_pk = anvil.keys().first().unwrap();
+
+        // initialize the state by updating the head once
+        let slot = client.get_head().await?;
+        state.update_head(None, slot).await?;
+
+        let blob_tx = |max_priority_fee_per_gas: u128| {
+            let sidecar = SidecarBuilder::<SimpleCoder>::from_slice(b"Blobs are fun!");
+            default_test_transaction(*sender, None)
+                .with_max_priority_fee_per_gas(max_priority_fee_per_gas)
+                .with_max_fee_per_gas(2 * max_priority_fee_per_gas)
+                .with_max_fee_per_blob_gas(3_000_000)
+                .with_blob_sidecar(sidecar.build().unwrap())
+        };
+
+        // The priority fee covers the min priority fee of the gas, but not the blob
+        let tx = blob_tx(10 * GWEI_TO_WEI as u128);
+        let mut request = create_signed_inclusion_request(&[tx], sender_pk, 10).await?;
+
+        assert!(matches!(
+            state.validate_request(&mut request).await,
+            Err(ValidationError::BlobTipTooLow(_, min_blob_fee))
+                if min_blob_fee == 1_000_000 * GWEI_TO_WEI as u128
+        ));
+
+        // 21k gas * (100 - 10) gwei covers the blob and the min priority fee of the gas
+        let tx = blob_tx(100 * GWEI_TO_WEI as u128);
+        let mut request = create_signed_inclusion_request(&[tx], sender_pk, 10).await?;
+
+        assert!(state.validate_request(&mut request).await.is_ok());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_invalid_inclusion_request_duplicate_batch() -> eyre::Result<()> {
         let anvil = launch_anvil();
@@ -1096,7 +1357,8 @@ mod tests {
         let receipt = notif.get_receipt().await?;
 
         // Update the head, which should invalidate the transaction due to a nonce conflict
-        state.update_head(receipt.block_number, receipt.block_number.unwrap()).await?;
+        let block = receipt.block_number.zip(receipt.block_hash);
+        state.update_head(block, receipt.block_number.unwrap()).await?;
 
         let transactions_len = state.get_block_template(target_slot).unwrap().transactions_len();
 
@@ -1105,6 +1367,221 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_resolve_commitments_in_target_block() -> eyre::Result<()> {
+        let anvil = launch_anvil();
+        let client = StateClient::new(anvil.endpoint_url());
+        let provider = ProviderBuilder::new().on_http(anvil.endpoint_url());
+
+        let index = Arc::new(CommitmentIndex::default());
+        let mut state = ExecutionState::new(client.clone(), LimitsOpts::default())
+            .await?
+            .with_commitment_index(index.clone());
+
+        let sender = anvil.addresses().first().unwrap();
+        let sender_pk = anvil.keys().first().unwrap();
+        let wallet: PrivateKeySigner = anvil.keys()[0].clone().into();
+        let signer: EthereumWallet = wallet.into();
+
+        // initialize the state by updating the head once
+        let slot = client.get_head().await?;
+        state.update_head(None, slot).await?;
+
+        // Commit to a transaction in each of the next three slots, and include each of them in
+        // its own block
+        let mut digests = Vec::new();
+        let mut receipts = Vec::new();
+        for nonce in 0..3 {
+            let target_slot = slot + 1 + nonce;
+            let tx = default_test_transaction(*sender, Some(nonce));
+            let request =
+                create_signed_inclusion_request(&[tx.clone()], sender_pk, target_slot).await?;
+
+            let message = ConstraintsMessage::build(Default::default(), request.clone());
+            let signature = LocalSigner::random().sign_commit_boost_root(message.digest()).unwrap();
+            state.add_constraint(target_slot, SignedConstraints { message, signature });
+
+            let commitment = request.into_signed(AlloySignatureWrapper::test_signature());
+            digests.push(commitment.digest());
+            index.on_accepted(commitment);
+
+            let signed = tx.build(&signer).await?;
+            let pending = provider.send_raw_transaction(&signed.encoded_2718()).await?;
+            receipts.push(pending.get_receipt().await?);
+        }
+
+        let commitment_state = |i: usize| index.get_status(&digests[i]).unwrap().state;
+
+        // The first slot has no block: its transaction was included in a later one
+        let block = receipts[1].block_number.zip(receipts[1].block_hash);
+        state.update_head(block, slot + 2).await?;
+        assert_eq!(commitment_state(0), CommitmentState::Missed);
+        assert_eq!(commitment_state(1), CommitmentState::Included);
+
+        // The block of the last slot is not the one that included its transaction
+        let block = receipts[2].block_number.map(|number| (number, B256::repeat_byte(1)));
+        state.update_head(block, slot + 3).await?;
+        assert_eq!(commitment_state(2), CommitmentState::Missed);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_inclusion_request_with_state_diff() -> eyre::Result<()> {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let anvil = launch_anvil();
+        let client = StateClient::new(anvil.endpoint_url());
+
+        let mut state = ExecutionState::new(client.clone(), LimitsOpts::default()).await?;
+
+        let sender = anvil.addresses().first().unwrap();
+        let sender_pk = anvil.keys().first().unwrap();
+
+        // initialize the state by updating the head once
+        let head = client.get_head().await?;
+        state.update_head(None, head).await?;
+
+        let tx = default_test_transaction(*sender, None);
+
+        let target_slot = 10;
+        let mut request = create_signed_inclusion_request(&[tx], sender_pk, target_slot).await?;
+        let inclusion_request = request.clone();
+
+        assert!(state.validate_request(&mut request).await.is_ok());
+
+        let bls_signer = LocalSigner::random();
+        let message = ConstraintsMessage::build(Default::default(), inclusion_request);
+        let signature = bls_signer.sign_commit_boost_root(message.digest()).unwrap();
+        state.add_constraint(target_slot, SignedConstraints { message, signature });
+
+        assert!(state.get_block_template(target_slot).unwrap().transactions_len() == 1);
+
+        // The sender nonce is used by another transaction in the next block
+        let sender_diff = AccountDiff { nonce: Some(1), ..Default::default() };
+        let mut diff = BlockStateDiff {
+            block_number: head + 2,
+            block_hash: B256::with_last_byte(1),
+            blob_basefee: 7,
+            accounts: HashMap::from([(*sender, sender_diff)]),
+            ..Default::default()
+        };
+
+        // A diff that doesn't apply on top of the current head is ignored
+        assert!(!state.apply_state_diff(diff.clone()));
+        assert!(state.get_block_template(target_slot).unwrap().transactions_len() == 1);
+
+        diff.block_number = head + 1;
+        assert!(state.apply_state_diff(diff));
+        assert!(state.get_block_template(target_slot).unwrap().transactions_len() == 0);
+        assert_eq!(state.blob_basefee, 7);
+
+        // The state is already at the head block, so it isn't fetched again
+        state.update_head(Some((head + 1, B256::with_last_byte(1))), head + 1).await?;
+        assert_eq!(state.account_states.get(sender).unwrap().transaction_count, 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_head_in_new_epoch() -> eyre::Result<()> {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let anvil = launch_anvil();
+        let client = StateClient::new(anvil.endpoint_url());
+        let provider = ProviderBuilder::new().on_http(anvil.endpoint_url());
+
+        let mut state = ExecutionState::new(client.clone(), LimitsOpts::default()).await?;
+
+        let sender = anvil.addresses().first().unwrap();
+        let sender_pk = anvil.keys().first().unwrap();
+
+        // initialize the state by updating the head once
+        let head = client.get_head().await?;
+        state.update_head(None, head).await?;
+
+        let tx = default_test_transaction(*sender, None);
+        let mut request = create_signed_inclusion_request(&[tx], sender_pk, 10).await?;
+        assert!(state.validate_request(&mut request).await.is_ok());
+
+        // Mine the next block without any transaction of the sender
+        let other = anvil.addresses()[1];
+        let wallet: PrivateKeySigner = anvil.keys()[1].clone().into();
+        let signer: EthereumWallet = wallet.into();
+        let signed = default_test_transaction(other, None).build(&signer).await?;
+        let receipt =
+            provider.send_raw_transaction(&signed.encoded_2718()).await?.get_receipt().await?;
+        let block = receipt.block_number.zip(receipt.block_hash);
+
+        // A streamed diff of the same block that drifted from the actual sender state
+        let sender_diff = AccountDiff { nonce: Some(1), ..Default::default() };
+        let diff = BlockStateDiff {
+            block_number: head + 1,
+            block_hash: receipt.block_hash.unwrap(),
+            accounts: HashMap::from([(*sender, sender_diff)]),
+            ..Default::default()
+        };
+        assert!(state.apply_state_diff(diff));
+        assert_eq!(state.account_states.get(sender).unwrap().transaction_count, 1);
+
+        // The head is in a new epoch, so its state is fetched again despite the diff
+        let slot = head + state.chain.slots_per_epoch();
+        state.update_head(block, slot).await?;
+        assert_eq!(state.account_states.get(sender).unwrap().transaction_count, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_head_after_reorg() -> eyre::Result<()> {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let anvil = launch_anvil();
+        let client = StateClient::new(anvil.endpoint_url());
+        let provider = ProviderBuilder::new().on_http(anvil.endpoint_url());
+
+        let mut state = ExecutionState::new(client.clone(), LimitsOpts::default()).await?;
+
+        let sender = anvil.addresses().first().unwrap();
+        let sender_pk = anvil.keys().first().unwrap();
+
+        // initialize the state by updating the head once
+        let head = client.get_head().await?;
+        state.update_head(None, head).await?;
+
+        let tx = default_test_transaction(*sender, None);
+        let mut request = create_signed_inclusion_request(&[tx], sender_pk, 10).await?;
+        assert!(state.validate_request(&mut request).await.is_ok());
+
+        // A streamed diff moves the state to a block where the sender nonce was used
+        let sender_diff = AccountDiff { nonce: Some(1), ..Default::default() };
+        let diff = BlockStateDiff {
+            block_number: head + 1,
+            block_hash: B256::with_last_byte(1),
+            accounts: HashMap::from([(*sender, sender_diff)]),
+            ..Default::default()
+        };
+        assert!(state.apply_state_diff(diff));
+        assert_eq!(state.account_states.get(sender).unwrap().transaction_count, 1);
+
+        // The canonical block at the same height is another one, without the sender transaction
+        let other = anvil.addresses()[1];
+        let wallet: PrivateKeySigner = anvil.keys()[1].clone().into();
+        let signer: EthereumWallet = wallet.into();
+        let signed = default_test_transaction(other, None).build(&signer).await?;
+        let receipt =
+            provider.send_raw_transaction(&signed.encoded_2718()).await?.get_receipt().await?;
+        assert_eq!(receipt.block_number, Some(head + 1));
+
+        // The head has the same number but a different hash, so its state is fetched again
+        let block = receipt.block_number.zip(receipt.block_hash);
+        state.update_head(block, head + 1).await?;
+        assert_eq!(state.account_states.get(sender).unwrap().transaction_count, 0);
+        assert_eq!(state.block_hash, receipt.block_hash);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_invalidate_stale_template() -> eyre::Result<()> {
         let _ = tracing_subscriber::fmt::try_init();
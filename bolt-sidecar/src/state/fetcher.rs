@@ -1,4 +1,13 @@
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use alloy::{
     eips::BlockNumberOrTag,
@@ -8,9 +17,9 @@ use alloy::{
 };
 use futures::{stream::FuturesOrdered, StreamExt};
 use reqwest::Url;
-use tracing::error;
+use tracing::{error, info, warn};
 
-use crate::{client::ExecutionClient, primitives::AccountState};
+use crate::{client::ExecutionClient, primitives::AccountState, telemetry::ApiMetrics};
 
 use super::execution::StateUpdate;
 
@@ -225,6 +234,156 @@ impl StateFetcher for StateClient {
     }
 }
 
+/// A state fetcher over multiple execution clients, in order of priority.
+///
+/// Requests are sent to the active client. If a request fails, it is retried on the other
+/// clients in order of priority, and the first one to succeed becomes the active client.
+///
+/// State updates requested at a specific block number are only fetched from clients whose
+/// head has reached that block, so that a lagging client is failed over like an offline one.
+#[derive(Clone, Debug)]
+pub struct FailoverStateClient {
+    clients: Arc<[(Url, StateClient)]>,
+    /// The index of the active client in `clients`.
+    active: Arc<AtomicUsize>,
+}
+
+impl FailoverStateClient {
+    /// Create a new `FailoverStateClient` with the given URLs, in order of priority.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no URL is provided.
+    pub fn from_urls(urls: Vec<Url>) -> Self {
+        assert!(!urls.is_empty(), "at least one execution API URL is required");
+
+        let clients: Arc<[_]> =
+            urls.into_iter().map(|url| (url.clone(), StateClient::new(url))).collect();
+        ApiMetrics::set_active_execution_node(0, true);
+
+        Self { clients, active: Arc::new(AtomicUsize::new(0)) }
+    }
+
+    /// Returns the URL of the active execution client.
+    pub fn active_url(&self) -> &Url {
+        &self.clients[self.active.load(Ordering::Relaxed)].0
+    }
+
+    /// Send a request to the [ExecutionClient] of the active client, failing over to the other
+    /// clients like the state requests. Used by the components that need other methods of the
+    /// execution API than the ones of [StateFetcher].
+    pub async fn with_execution_client<'a, T, E, F, Fut>(&'a self, request: F) -> Result<T, E>
+    where
+        E: Debug,
+        F: Fn(&'a ExecutionClient) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        self.with_failover(|client| request(&client.client)).await
+    }
+
+    /// Send a request to the active client, failing over to the other clients in order of
+    /// priority if it fails. Returns the error of the last client if all of them fail.
+    async fn with_failover<'a, T, E, F, Fut>(&'a self, request: F) -> Result<T, E>
+    where
+        E: Debug,
+        F: Fn(&'a StateClient) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let current = self.active.load(Ordering::Relaxed);
+
+        let mut last_err = None;
+        for index in failover_order(current, self.clients.len()) {
+            let (url, client) = &self.clients[index];
+
+            match request(client).await {
+                Ok(res) => {
+                    if index != current {
+                        let from = &self.clients[current].0;
+                        warn!(%from, to = %url, "Active execution client failed, failing over");
+
+                        ApiMetrics::set_active_execution_node(current, false);
+                        ApiMetrics::set_active_execution_node(index, true);
+                        ApiMetrics::increment_execution_node_failovers();
+                        self.active.store(index, Ordering::Relaxed);
+                        info!(%url, "Switched active execution client");
+                    }
+
+                    return Ok(res);
+                }
+                Err(err) => {
+                    warn!(?err, %url, "Execution client request failed");
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        Err(last_err.expect("at least one execution client"))
+    }
+}
+
+/// The order in which the clients are tried: the current one first, then the others in order
+/// of priority.
+fn failover_order(current: usize, len: usize) -> impl Iterator<Item = usize> {
+    std::iter::once(current).chain((0..len).filter(move |index| *index != current))
+}
+
+#[async_trait::async_trait]
+impl StateFetcher for FailoverStateClient {
+    async fn get_state_update(
+        &self,
+        addresses: Vec<&Address>,
+        block_number: Option<u64>,
+    ) -> Result<StateUpdate, TransportError> {
+        self.with_failover(|client| {
+            let addresses = addresses.clone();
+            async move {
+                if let Some(block_number) = block_number {
+                    let head = client.get_head().await?;
+                    if head < block_number {
+                        return Err(TransportError::local_usage_str(&format!(
+                            "execution client head {head} is behind block {block_number}"
+                        )));
+                    }
+                }
+
+                client.get_state_update(addresses, block_number).await
+            }
+        })
+        .await
+    }
+
+    async fn get_head(&self) -> Result<u64, TransportError> {
+        self.with_failover(|client| client.get_head()).await
+    }
+
+    async fn get_basefee(&self, block_number: Option<u64>) -> Result<u128, TransportError> {
+        self.with_failover(|client| client.get_basefee(block_number)).await
+    }
+
+    async fn get_blob_basefee(&self, block_number: Option<u64>) -> Result<u128, TransportError> {
+        self.with_failover(|client| client.get_blob_basefee(block_number)).await
+    }
+
+    async fn get_account_state(
+        &self,
+        address: &Address,
+        block_number: Option<u64>,
+    ) -> Result<AccountState, TransportError> {
+        self.with_failover(|client| client.get_account_state(address, block_number)).await
+    }
+
+    async fn get_chain_id(&self) -> Result<u64, TransportError> {
+        self.with_failover(|client| client.get_chain_id()).await
+    }
+
+    async fn get_receipts_unordered(
+        &self,
+        hashes: &[TxHash],
+    ) -> Result<Vec<Option<TransactionReceipt>>, TransportError> {
+        self.with_failover(|client| client.get_receipts_unordered(hashes)).await
+    }
+}
+
 #[cfg(test)]
 impl StateClient {
     /// Return a reference to the inner `ExecutionClient`.
@@ -254,4 +413,28 @@ mod tests {
         let basefee = client.get_basefee(None).await.unwrap();
         assert_eq!(basefee, 1_000_000_000);
     }
+
+    #[tokio::test]
+    async fn test_failover_state_client() {
+        let anvil = launch_anvil();
+        let offline = Url::parse("http://127.0.0.1:1").unwrap();
+        let online = Url::parse(&anvil.endpoint()).unwrap();
+        let client = FailoverStateClient::from_urls(vec![offline, online.clone()]);
+
+        let head = client.get_head().await.unwrap();
+        assert_eq!(head, 0);
+        assert_eq!(client.active_url(), &online);
+
+        // The only client is lagging behind the requested block
+        let address = anvil.addresses().first().unwrap();
+        assert!(client.get_state_update(vec![address], Some(head + 1)).await.is_err());
+        assert!(client.get_state_update(vec![address], Some(head)).await.is_ok());
+    }
+
+    #[test]
+    fn test_failover_order() {
+        assert_eq!(failover_order(0, 3).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(failover_order(1, 3).collect::<Vec<_>>(), vec![1, 0, 2]);
+        assert_eq!(failover_order(0, 1).collect::<Vec<_>>(), vec![0]);
+    }
 }
@@ -1,9 +1,9 @@
-use alloy::rpc::types::beacon::events::HeadEvent;
-use beacon_api_client::Topic;
+use alloy::{primitives::B256, rpc::types::beacon::events::HeadEvent};
+use beacon_api_client::{BlockId, Topic};
 use futures::StreamExt;
 use std::time::Duration;
 use tokio::{sync::broadcast, task::AbortHandle, time::sleep};
-use tracing::{trace, warn};
+use tracing::{debug, info, trace, warn};
 
 use crate::client::BeaconClient;
 
@@ -13,8 +13,9 @@ const RETRY_DELAY: Duration = Duration::from_secs(1);
 /// Simple actor to keep track of the most recent head of the beacon chain
 /// and broadcast updates to its subscribers.
 ///
-/// Durability: the tracker will always attempt to reconnect to the provided
-/// beacon client URL in case of disconnection or other errors.
+/// Durability: the tracker will always attempt to reconnect to the active node of the
+/// provided beacon client in case of disconnection or other errors, and moves its
+/// subscription to the new active node on failover.
 #[derive(Debug)]
 pub struct HeadTracker {
     /// Channel to receive updates of the "Head" beacon topic
@@ -35,14 +36,20 @@ impl Topic for NewHeadsTopic {
 }
 
 impl HeadTracker {
-    /// Create a new `HeadTracker` with the given beacon client HTTP URL and
+    /// Create a new `HeadTracker` with the given beacon client and
     /// start listening for new head events in the background
-    pub fn start(beacon_client: BeaconClient) -> Self {
+    pub fn start(beacon_client: BeaconClient, slots_per_epoch: u64) -> Self {
         let (new_heads_tx, new_heads_rx) = broadcast::channel(32);
 
         let task = tokio::spawn(async move {
+            let mut active_node_rx = beacon_client.subscribe_active_node();
+            let mut latest_slot = None;
+
             loop {
-                trace!(endpoint = %beacon_client.endpoint, "Subscribing to new head events...");
+                active_node_rx.mark_unchanged();
+                let endpoint = beacon_client.active_url().clone();
+
+                trace!(%endpoint, "Subscribing to new head events...");
                 let mut event_stream = match beacon_client.get_events::<NewHeadsTopic>().await {
                     Ok(events) => events,
                     Err(err) => {
@@ -52,24 +59,67 @@ impl HeadTracker {
                     }
                 };
 
-                trace!(endpoint = %beacon_client.endpoint, "Subscribed to new head events");
+                trace!(%endpoint, "Subscribed to new head events");
 
-                let event = match event_stream.next().await {
-                    Some(Ok(event)) => event,
-                    Some(Err(err)) => {
-                        warn!(?err, "error reading new head event stream, retrying...");
-                        sleep(RETRY_DELAY).await;
-                        continue;
+                // Heads received by the node while we were (re)connecting are not part of the
+                // stream: broadcast the ones after the latest head, up to the current one.
+                if let Some(slot) = latest_slot {
+                    match fetch_missed_head_events(&beacon_client, slot, slots_per_epoch).await {
+                        Ok(events) => {
+                            for event in events {
+                                info!(
+                                    slot = event.slot,
+                                    "Recovered head missed while reconnecting"
+                                );
+                                latest_slot = Some(event.slot);
+                                if let Err(err) = new_heads_tx.send(event) {
+                                    warn!(
+                                        ?err,
+                                        "failed to broadcast new head event to subscribers"
+                                    );
+                                }
+                            }
+                        }
+                        Err(err) => warn!(?err, "failed to fetch the missed heads"),
                     }
-                    None => {
-                        warn!("new head event stream ended, retrying...");
-                        sleep(RETRY_DELAY).await;
+                }
+
+                loop {
+                    let event = tokio::select! {
+                        event = event_stream.next() => event,
+                        Ok(()) = active_node_rx.changed() => {
+                            info!(
+                                from = %endpoint,
+                                to = %beacon_client.active_url(),
+                                "Active beacon node changed, moving head subscription"
+                            );
+                            break;
+                        }
+                    };
+
+                    let event = match event {
+                        Some(Ok(event)) => event,
+                        Some(Err(err)) => {
+                            warn!(?err, "error reading new head event stream, retrying...");
+                            sleep(RETRY_DELAY).await;
+                            break;
+                        }
+                        None => {
+                            warn!("new head event stream ended, retrying...");
+                            sleep(RETRY_DELAY).await;
+                            break;
+                        }
+                    };
+
+                    // Skip heads already broadcast, e.g. when recovered after a reconnection
+                    if latest_slot >= Some(event.slot) {
                         continue;
                     }
-                };
 
-                if let Err(err) = new_heads_tx.send(event) {
-                    warn!(?err, "failed to broadcast new head event to subscribers");
+                    latest_slot = Some(event.slot);
+                    if let Err(err) = new_heads_tx.send(event) {
+                        warn!(?err, "failed to broadcast new head event to subscribers");
+                    }
                 }
             }
         });
@@ -96,13 +146,107 @@ impl HeadTracker {
     }
 }
 
+/// Build the head events of the blocks after the given slot, up to the current head of the
+/// active beacon node. Slots without a block are skipped, and at most an epoch of blocks is
+/// recovered.
+async fn fetch_missed_head_events(
+    beacon_client: &BeaconClient,
+    latest_slot: u64,
+    slots_per_epoch: u64,
+) -> Result<Vec<HeadEvent>, beacon_api_client::Error> {
+    let head = beacon_client.get_beacon_header(BlockId::Head).await?;
+    let head_slot = head.header.message.slot;
+    if head_slot <= latest_slot {
+        return Ok(Vec::new());
+    }
+
+    let first_slot = (latest_slot + 1).max(head_slot.saturating_sub(slots_per_epoch - 1));
+    let mut summaries = Vec::new();
+    for slot in first_slot..head_slot {
+        match beacon_client.get_beacon_header(BlockId::Slot(slot)).await {
+            Ok(summary) => summaries.push(summary),
+            Err(err) => debug!(slot, ?err, "No block found for missed slot"),
+        }
+    }
+    summaries.push(head);
+
+    let mut events = Vec::with_capacity(summaries.len());
+    let mut parent_slot = latest_slot;
+    let mut dependent_roots = None;
+
+    for summary in summaries {
+        let header = summary.header.message;
+        let epoch = header.slot / slots_per_epoch;
+
+        let (previous_duty_dependent_root, current_duty_dependent_root) = match dependent_roots {
+            Some((roots_epoch, roots)) if roots_epoch == epoch => roots,
+            _ => {
+                let roots = fetch_dependent_roots(beacon_client, epoch).await;
+                dependent_roots = Some((epoch, roots));
+                roots
+            }
+        };
+
+        events.push(HeadEvent {
+            slot: header.slot,
+            block: B256::from_slice(summary.root.as_slice()),
+            state: B256::from_slice(header.state_root.as_slice()),
+            epoch_transition: is_epoch_transition(parent_slot, header.slot, slots_per_epoch),
+            previous_duty_dependent_root,
+            current_duty_dependent_root,
+            execution_optimistic: false,
+        });
+        parent_slot = header.slot;
+    }
+
+    Ok(events)
+}
+
+/// Returns whether a block at the given slot is the first one of its epoch, given the slot
+/// of its parent.
+const fn is_epoch_transition(parent_slot: u64, slot: u64, slots_per_epoch: u64) -> bool {
+    parent_slot / slots_per_epoch != slot / slots_per_epoch
+}
+
+/// Fetch the duty dependent roots of a head event in the given epoch: the previous one is the
+/// dependent root of the attester duties of the epoch (the block root at the last slot of
+/// `epoch - 2`), and the current one is the dependent root of its proposer duties (the block
+/// root at the last slot of `epoch - 1`). If they can't be fetched, they are left empty.
+async fn fetch_dependent_roots(beacon_client: &BeaconClient, epoch: u64) -> (B256, B256) {
+    // The attester duties are only requested for their dependent root: validator 0 always
+    // exists, while some beacon nodes reject requests without any validator index.
+    match tokio::try_join!(
+        beacon_client.get_attester_duties(epoch, &[0]),
+        beacon_client.get_proposer_duties(epoch)
+    ) {
+        Ok(((previous, _), (current, _))) => {
+            (B256::from_slice(previous.as_slice()), B256::from_slice(current.as_slice()))
+        }
+        Err(err) => {
+            warn!(?err, epoch, "failed to fetch the duty dependent roots");
+            (B256::ZERO, B256::ZERO)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use reqwest::Url;
     use tracing::warn;
 
+    use super::is_epoch_transition;
     use crate::{client::BeaconClient, state::HeadTracker, test_util::try_get_beacon_api_url};
 
+    #[test]
+    fn test_is_epoch_transition() {
+        assert!(!is_epoch_transition(33, 34, 32));
+        assert!(is_epoch_transition(31, 32, 32));
+
+        // The first slots of the epoch were missed
+        assert!(is_epoch_transition(30, 35, 32));
+        assert!(!is_epoch_transition(32, 35, 32));
+    }
+
     #[tokio::test]
     async fn test_fetch_next_beacon_head() -> eyre::Result<()> {
         let _ = tracing_subscriber::fmt::try_init();
@@ -113,7 +257,7 @@ mod tests {
         };
 
         let beacon_client = BeaconClient::new(Url::parse(url).unwrap());
-        let mut tracker = HeadTracker::start(beacon_client);
+        let mut tracker = HeadTracker::start(beacon_client, 32);
 
         let head = tracker.next_head().await?;
 
@@ -17,7 +17,7 @@ pub use pricing::InclusionPricer;
 
 /// Module to fetch state from the Execution layer.
 pub mod fetcher;
-pub use fetcher::StateClient;
+pub use fetcher::{FailoverStateClient, StateClient};
 
 /// Module to track the consensus state.
 pub mod consensus;
@@ -27,10 +27,22 @@ pub use consensus::ConsensusState;
 pub mod head_tracker;
 pub use head_tracker::HeadTracker;
 
+/// Module to stream the state diffs of new execution blocks.
+pub mod state_diff_stream;
+pub use state_diff_stream::{BlockStateDiff, StateDiffStream};
+
 /// Module that defines the account state cache.
 pub mod account_state;
 pub use account_state::AccountStateCache;
 
+/// Module to track the lifecycle of the issued commitments.
+pub mod commitment_index;
+pub use commitment_index::CommitmentIndex;
+
+/// Module to audit the fulfillment of the issued commitments.
+pub mod auditor;
+pub use auditor::FulfillmentAuditor;
+
 /// The deadline for a which a commitment is considered valid.
 #[derive(Debug)]
 pub struct CommitmentDeadline {
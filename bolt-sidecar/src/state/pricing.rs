@@ -104,6 +104,32 @@ impl InclusionPricer {
         // Calculate the fee per gas
         Ok(inclusion_tip_wei / incoming_gas)
     }
+
+    /// Calculate the minimum fee in Wei for the blobs of a preconfirmation, to be paid with the
+    /// priority fee on top of the minimum inclusion fee of its gas.
+    ///
+    /// Blob fees are burned, so blob space is priced on its own: each blob costs the minimum
+    /// fee per blob, scaled up by the share of the blob space of the block already
+    /// preconfirmed. E.g. the last blob of a block costs almost twice as much as the first one.
+    ///
+    /// The incoming blobs are expected to fit in the remaining blob space of the block.
+    ///
+    /// # Arguments
+    /// * `incoming_blobs` - Blobs of the incoming transaction
+    /// * `preconfirmed_blobs` - Total blobs already preconfirmed
+    /// * `max_blobs` - Maximum number of blobs in a block
+    /// * `min_fee_per_blob` - The minimum fee in Wei of a blob
+    pub fn calculate_min_blob_fee(
+        &self,
+        incoming_blobs: u64,
+        preconfirmed_blobs: u64,
+        max_blobs: u64,
+        min_fee_per_blob: u64,
+    ) -> u128 {
+        (preconfirmed_blobs..preconfirmed_blobs + incoming_blobs)
+            .map(|blob| min_fee_per_blob as u128 * (max_blobs + blob) as u128 / max_blobs as u128)
+            .sum()
+    }
 }
 
 fn validate_fee_inputs(
@@ -350,4 +376,18 @@ mod tests {
         let result = pricing.calculate_min_priority_fee(incoming_gas, preconfirmed_gas);
         assert!(matches!(result, Err(PricingError::InvalidGasLimit { incoming_gas: 0 })));
     }
+
+    #[test]
+    fn test_min_blob_fee() {
+        let pricing = InclusionPricer::default();
+        let min_fee_per_blob = 1_000_000;
+
+        // The first blob of an empty block costs the min fee
+        let fee = pricing.calculate_min_blob_fee(1, 0, 6, min_fee_per_blob);
+        assert_eq!(fee, 1_000_000);
+
+        // Blobs get more expensive as the blob space fills up
+        let fee = pricing.calculate_min_blob_fee(2, 3, 6, min_fee_per_blob);
+        assert_eq!(fee, 1_500_000 + 1_666_666);
+    }
 }
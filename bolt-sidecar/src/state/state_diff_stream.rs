@@ -0,0 +1,285 @@
+use std::{collections::HashMap, time::Duration};
+
+use alloy::{
+    eips::eip1559::BaseFeeParams,
+    primitives::{Address, Bytes, B256, U256},
+    providers::{Provider, ProviderBuilder, WsConnect},
+    rpc::types::Header,
+    transports::TransportResult,
+};
+use futures::StreamExt;
+use reqwest::Url;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::{sync::mpsc, task::AbortHandle, time::sleep};
+use tracing::{debug, info, trace, warn};
+
+use crate::primitives::AccountState;
+
+/// The delay between retries when attempting to reconnect to the execution client
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// The changes to the account states made by an execution block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockStateDiff {
+    /// The number of the block.
+    pub block_number: u64,
+    /// The hash of the block.
+    pub block_hash: B256,
+    /// The hash of the parent block, on top of which the diff applies.
+    pub parent_hash: B256,
+    /// The basefee of the next block.
+    pub basefee: u128,
+    /// The blob basefee of the next block.
+    pub blob_basefee: u128,
+    /// The changes to the accounts touched by the block.
+    pub accounts: HashMap<Address, AccountDiff>,
+}
+
+/// The changes to an account made by an execution block. Fields left unchanged are `None`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AccountDiff {
+    /// The new balance of the account.
+    pub balance: Option<U256>,
+    /// The new nonce of the account.
+    pub nonce: Option<u64>,
+    /// Whether the account has code after the block.
+    pub has_code: Option<bool>,
+}
+
+impl AccountDiff {
+    /// Apply the changes to the given account state.
+    pub fn apply(&self, state: &mut AccountState) {
+        if let Some(balance) = self.balance {
+            state.balance = balance;
+        }
+        if let Some(nonce) = self.nonce {
+            state.transaction_count = nonce;
+        }
+        if let Some(has_code) = self.has_code {
+            state.has_code = has_code;
+        }
+    }
+}
+
+/// Simple actor streaming the state diffs of new execution blocks from a websocket
+/// connection to the execution client.
+///
+/// It subscribes to `newHeads` and traces every new block with the `prestateTracer` in diff
+/// mode, so that account states can be updated incrementally instead of being fetched again.
+///
+/// Durability: the stream will always attempt to reconnect to the execution client in case of
+/// disconnection or other errors. Blocks missed while reconnecting are not streamed.
+#[derive(Debug)]
+pub struct StateDiffStream {
+    /// Channel to receive the state diffs of new blocks
+    state_diffs_rx: mpsc::Receiver<BlockStateDiff>,
+    /// Handle to the background task that listens for new blocks.
+    /// Kept to allow for graceful shutdown.
+    quit: AbortHandle,
+}
+
+impl StateDiffStream {
+    /// Create a new `StateDiffStream` with the given websocket URL of the execution client and
+    /// start streaming state diffs in the background.
+    pub fn start(ws_url: Url) -> Self {
+        let (state_diffs_tx, state_diffs_rx) = mpsc::channel(32);
+
+        let task = tokio::spawn(async move {
+            loop {
+                trace!(%ws_url, "Subscribing to new execution heads...");
+                let ws = WsConnect::new(ws_url.as_str());
+                let provider = match ProviderBuilder::new().on_ws(ws).await {
+                    Ok(provider) => provider,
+                    Err(err) => {
+                        warn!(?err, "failed to connect to the execution websocket, retrying...");
+                        sleep(RETRY_DELAY).await;
+                        continue;
+                    }
+                };
+
+                let mut heads = match provider.subscribe_blocks().await {
+                    Ok(subscription) => subscription.into_stream(),
+                    Err(err) => {
+                        warn!(?err, "failed to subscribe to new execution heads, retrying...");
+                        sleep(RETRY_DELAY).await;
+                        continue;
+                    }
+                };
+
+                info!(%ws_url, "Subscribed to new execution heads");
+
+                while let Some(header) = heads.next().await {
+                    let diff = match fetch_state_diff(&provider, &header).await {
+                        Ok(diff) => diff,
+                        Err(err) => {
+                            warn!(?err, number = header.number, "failed to trace execution block");
+                            continue;
+                        }
+                    };
+
+                    debug!(
+                        number = diff.block_number,
+                        accounts = diff.accounts.len(),
+                        "Streamed execution state diff"
+                    );
+
+                    if state_diffs_tx.send(diff).await.is_err() {
+                        // The receiver has been dropped, nothing left to do.
+                        return;
+                    }
+                }
+
+                warn!("execution heads stream ended, retrying...");
+                sleep(RETRY_DELAY).await;
+            }
+        });
+
+        Self { state_diffs_rx, quit: task.abort_handle() }
+    }
+
+    /// Stop the stream and cleanup resources
+    pub fn stop(self) {
+        self.quit.abort();
+    }
+
+    /// Get the state diff of the next execution block
+    pub async fn next_diff(&mut self) -> Option<BlockStateDiff> {
+        self.state_diffs_rx.recv().await
+    }
+}
+
+/// Trace the block of the given header with the `prestateTracer` in diff mode, and fold the
+/// per-transaction diffs into the state diff of the whole block.
+async fn fetch_state_diff<P: Provider>(
+    provider: &P,
+    header: &Header,
+) -> TransportResult<BlockStateDiff> {
+    let tracer = json!({ "tracer": "prestateTracer", "tracerConfig": { "diffMode": true } });
+    let traces: Vec<TxTrace> =
+        provider.client().request("debug_traceBlockByHash", (header.hash, tracer)).await?;
+
+    // The blob basefee depends on the blob schedule of the fork, so it's left to the client.
+    let blob_basefee = provider.get_blob_base_fee().await?;
+
+    Ok(BlockStateDiff {
+        block_number: header.number,
+        block_hash: header.hash,
+        parent_hash: header.parent_hash,
+        basefee: header.next_block_base_fee(BaseFeeParams::ethereum()).unwrap_or_default() as u128,
+        blob_basefee,
+        accounts: fold_block_diff(traces),
+    })
+}
+
+/// The `prestateTracer` diff of a transaction, as returned by `debug_traceBlockByHash`.
+#[derive(Debug, Deserialize)]
+struct TxTrace {
+    result: PrestateDiff,
+}
+
+/// The accounts touched by a transaction, before and after its execution. Only the fields
+/// that changed are part of the post state, and deleted accounts are missing from it.
+#[derive(Debug, Default, Deserialize)]
+struct PrestateDiff {
+    #[serde(default)]
+    pre: HashMap<Address, TracedAccount>,
+    #[serde(default)]
+    post: HashMap<Address, TracedAccount>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TracedAccount {
+    balance: Option<U256>,
+    nonce: Option<u64>,
+    code: Option<Bytes>,
+}
+
+/// Fold the diffs of the transactions of a block, in execution order, into the changes made
+/// by the whole block.
+fn fold_block_diff(traces: Vec<TxTrace>) -> HashMap<Address, AccountDiff> {
+    let mut accounts = HashMap::<Address, AccountDiff>::new();
+
+    for TxTrace { result: PrestateDiff { pre, post } } in traces {
+        for address in pre.keys().filter(|address| !post.contains_key(address)) {
+            let deleted =
+                AccountDiff { balance: Some(U256::ZERO), nonce: Some(0), has_code: Some(false) };
+            accounts.insert(*address, deleted);
+        }
+
+        for (address, account) in post {
+            let diff = accounts.entry(address).or_default();
+            diff.balance = account.balance.or(diff.balance);
+            diff.nonce = account.nonce.or(diff.nonce);
+            diff.has_code = account.code.map(|code| !code.is_empty()).or(diff.has_code);
+        }
+    }
+
+    accounts
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::address;
+
+    use super::*;
+
+    #[test]
+    fn test_fold_block_diff() {
+        let traces = r#"[
+            {
+                "txHash": "0x0000000000000000000000000000000000000000000000000000000000000001",
+                "result": {
+                    "pre": {
+                        "0x1111111111111111111111111111111111111111": { "balance": "0x10", "nonce": 1 },
+                        "0x2222222222222222222222222222222222222222": { "balance": "0x0" },
+                        "0x3333333333333333333333333333333333333333": { "balance": "0x5", "nonce": 1 }
+                    },
+                    "post": {
+                        "0x1111111111111111111111111111111111111111": { "balance": "0x8", "nonce": 2 },
+                        "0x2222222222222222222222222222222222222222": { "balance": "0x8" }
+                    }
+                }
+            },
+            {
+                "txHash": "0x0000000000000000000000000000000000000000000000000000000000000002",
+                "result": {
+                    "pre": {
+                        "0x2222222222222222222222222222222222222222": { "balance": "0x8" }
+                    },
+                    "post": {
+                        "0x2222222222222222222222222222222222222222": { "balance": "0x4", "nonce": 1, "code": "0x6000" }
+                    }
+                }
+            }
+        ]"#;
+
+        let accounts = fold_block_diff(serde_json::from_str(traces).unwrap());
+        assert_eq!(accounts.len(), 3);
+
+        let sender = accounts[&address!("1111111111111111111111111111111111111111")];
+        assert_eq!(
+            sender,
+            AccountDiff { balance: Some(U256::from(8)), nonce: Some(2), has_code: None }
+        );
+
+        let contract = accounts[&address!("2222222222222222222222222222222222222222")];
+        assert_eq!(
+            contract,
+            AccountDiff { balance: Some(U256::from(4)), nonce: Some(1), has_code: Some(true) }
+        );
+
+        let deleted = accounts[&address!("3333333333333333333333333333333333333333")];
+        assert_eq!(
+            deleted,
+            AccountDiff { balance: Some(U256::ZERO), nonce: Some(0), has_code: Some(false) }
+        );
+
+        let mut state =
+            AccountState { balance: U256::from(16), transaction_count: 1, has_code: false };
+        sender.apply(&mut state);
+        assert_eq!(state.balance, U256::from(8));
+        assert_eq!(state.transaction_count, 2);
+        assert!(!state.has_code);
+    }
+}
@@ -12,6 +12,10 @@ const HTTP_REQUESTS_TOTAL: &str = "bolt_sidecar_http_requests_total";
 const LOCAL_BLOCKS_PROPOSED: &str = "bolt_sidecar_local_blocks_proposed";
 /// Counter for the number of remote blocks proposed.
 const REMOTE_BLOCKS_PROPOSED: &str = "bolt_sidecar_remote_blocks_proposed";
+/// Counter for the number of remote bid inclusion proof verifications, by result.
+const REMOTE_BID_PROOF_VERIFICATIONS: &str = "bolt_sidecar_remote_bid_proof_verifications";
+/// Counter for the number of headers returned to the beacon node, by decision.
+const HEADER_DECISIONS: &str = "bolt_sidecar_header_decisions";
 /// Counter for the number of inclusion commitments received.
 const INCLUSION_COMMITMENTS_RECEIVED: &str = "bolt_sidecar_inclusion_commitments_received";
 /// Counter for the number of inclusion commitments accepted.
@@ -24,16 +28,40 @@ const VALIDATION_ERRORS: &str = "bolt_sidecar_validation_errors";
 /// We call it "gross" because in the case of PBS, it doesn't mean the proposer will
 /// get all of this as revenue.
 const GROSS_TIP_REVENUE: &str = "bolt_sidecar_gross_tip_revenue";
+/// Counter for the number of failovers to another beacon node.
+const BEACON_NODE_FAILOVERS: &str = "bolt_sidecar_beacon_node_failovers";
+/// Counter for the number of failovers to another execution node.
+const EXECUTION_NODE_FAILOVERS: &str = "bolt_sidecar_execution_node_failovers";
+/// Counter for the number of reconnections to a firewall RPC, by URL.
+const FIREWALL_RPC_RECONNECTS: &str = "bolt_sidecar_firewall_rpc_reconnects";
+/// Counter for the number of inclusion requests received from a firewall RPC, by URL and result.
+const FIREWALL_RPC_REQUESTS: &str = "bolt_sidecar_firewall_rpc_requests";
+/// Counter for the number of audited commitments with all their transactions included.
+const FULFILLED_COMMITMENTS: &str = "bolt_sidecar_fulfilled_commitments";
+/// Counter for the number of audited commitments that were not fulfilled, by reason.
+const BREACHED_COMMITMENTS: &str = "bolt_sidecar_breached_commitments";
 
 //  Gauges ------------------------------------------------------------------
 /// Gauge for the latest slot number
 const LATEST_HEAD: &str = "bolt_sidecar_latest_head";
 /// Number of account states saved in cache.
 const ACCOUNT_STATES: &str = "bolt_sidecar_account_states";
+/// Gauge set to 1 for the active beacon node and 0 for the others, by index in the list of
+/// beacon nodes (URLs are not used as they may contain credentials).
+const BEACON_NODE_ACTIVE: &str = "bolt_sidecar_beacon_node_active";
+/// Gauge set to 1 for the active execution node and 0 for the others, by index in the list of
+/// execution nodes (URLs are not used as they may contain credentials).
+const EXECUTION_NODE_ACTIVE: &str = "bolt_sidecar_execution_node_active";
+/// Gauge set to 1 while the execution state is stale, i.e. the last head update failed.
+const EXECUTION_STATE_STALE: &str = "bolt_sidecar_execution_state_stale";
+/// Gauge set to 1 while a firewall RPC is connected and 0 otherwise, by URL.
+const FIREWALL_RPC_CONNECTED: &str = "bolt_sidecar_firewall_rpc_connected";
 
 //  Histograms --------------------------------------------------------------
 /// Histogram for the total duration of HTTP requests in seconds.
 const HTTP_REQUESTS_DURATION_SECONDS: &str = "bolt_sidecar_http_requests_duration_seconds";
+/// Histogram for the latency of the inclusion requests received from a firewall RPC, by URL.
+const FIREWALL_RPC_LATENCY_SECONDS: &str = "bolt_sidecar_firewall_rpc_latency_seconds";
 
 /// Metrics for the commitments API.
 #[derive(Debug, Clone, Copy)]
@@ -46,21 +74,37 @@ impl ApiMetrics {
         describe_counter!(HTTP_REQUESTS_TOTAL, "Total number of HTTP requests received");
         describe_counter!(LOCAL_BLOCKS_PROPOSED, "Local blocks proposed");
         describe_counter!(REMOTE_BLOCKS_PROPOSED, "Remote blocks proposed");
+        describe_counter!(REMOTE_BID_PROOF_VERIFICATIONS, "Remote bid proof verifications");
+        describe_counter!(HEADER_DECISIONS, "Headers returned by source and reason");
         describe_counter!(INCLUSION_COMMITMENTS_ACCEPTED, "Inclusion commitments");
         describe_counter!(INCLUSION_COMMITMENTS_ACCEPTED, "Inclusion commitments accepted");
         describe_counter!(TRANSACTIONS_PRECONFIRMED, "Transactions preconfirmed");
         describe_counter!(VALIDATION_ERRORS, "Validation errors");
         describe_counter!(GROSS_TIP_REVENUE, "Gross tip revenue");
+        describe_counter!(BEACON_NODE_FAILOVERS, "Failovers to another beacon node");
+        describe_counter!(EXECUTION_NODE_FAILOVERS, "Failovers to another execution node");
+        describe_counter!(FIREWALL_RPC_RECONNECTS, "Reconnections to a firewall RPC");
+        describe_counter!(FIREWALL_RPC_REQUESTS, "Inclusion requests received from a firewall RPC");
+        describe_counter!(FULFILLED_COMMITMENTS, "Commitments fulfilled");
+        describe_counter!(BREACHED_COMMITMENTS, "Commitments not fulfilled by reason");
 
         // Gauges
         describe_gauge!(LATEST_HEAD, "Latest slot number");
         describe_gauge!(ACCOUNT_STATES, "Number of account states saved in cache");
+        describe_gauge!(BEACON_NODE_ACTIVE, "Whether the beacon node is the active one");
+        describe_gauge!(EXECUTION_NODE_ACTIVE, "Whether the execution node is the active one");
+        describe_gauge!(EXECUTION_STATE_STALE, "Whether the execution state is stale");
+        describe_gauge!(FIREWALL_RPC_CONNECTED, "Whether the firewall RPC is connected");
 
         // Histograms
         describe_histogram!(
             HTTP_REQUESTS_DURATION_SECONDS,
             "Total duration of HTTP requests in seconds"
         );
+        describe_histogram!(
+            FIREWALL_RPC_LATENCY_SECONDS,
+            "Latency of the inclusion requests received from a firewall RPC in seconds"
+        );
     }
 
     // Counters ----------------------------------------------------------------
@@ -81,6 +125,15 @@ impl ApiMetrics {
         counter!(REMOTE_BLOCKS_PROPOSED).increment(1);
     }
 
+    pub fn increment_remote_bid_proof_verifications(valid: bool) {
+        let result = if valid { "valid" } else { "invalid" };
+        counter!(REMOTE_BID_PROOF_VERIFICATIONS, &[("result", result)]).increment(1);
+    }
+
+    pub fn increment_header_decisions(decision: &'static str) {
+        counter!(HEADER_DECISIONS, &[("decision", decision)]).increment(1);
+    }
+
     pub fn increment_inclusion_commitments_received() {
         counter!(INCLUSION_COMMITMENTS_RECEIVED).increment(1);
     }
@@ -116,6 +169,32 @@ impl ApiMetrics {
         counter!(VALIDATION_ERRORS, &[("type", err_type)]).increment(1);
     }
 
+    pub fn increment_beacon_node_failovers() {
+        counter!(BEACON_NODE_FAILOVERS).increment(1);
+    }
+
+    pub fn increment_execution_node_failovers() {
+        counter!(EXECUTION_NODE_FAILOVERS).increment(1);
+    }
+
+    pub fn increment_firewall_rpc_reconnects(url: &str) {
+        counter!(FIREWALL_RPC_RECONNECTS, &[("url", url.to_string())]).increment(1);
+    }
+
+    pub fn increment_firewall_rpc_requests(url: &str, accepted: bool) {
+        let result = if accepted { "accepted" } else { "rejected" };
+        let labels = [("url", url.to_string()), ("result", result.to_string())];
+        counter!(FIREWALL_RPC_REQUESTS, &labels).increment(1);
+    }
+
+    pub fn increment_fulfilled_commitments(count: u64) {
+        counter!(FULFILLED_COMMITMENTS).increment(count);
+    }
+
+    pub fn increment_breached_commitments(reason: &'static str) {
+        counter!(BREACHED_COMMITMENTS, &[("reason", reason)]).increment(1);
+    }
+
     /// Gauges ----------------------------------------------------------------
     pub fn set_latest_head(slot: u32) {
         gauge!(LATEST_HEAD).set(slot);
@@ -125,6 +204,25 @@ impl ApiMetrics {
         gauge!(ACCOUNT_STATES).set(count as f64);
     }
 
+    pub fn set_active_beacon_node(index: usize, active: bool) {
+        let value = if active { 1.0 } else { 0.0 };
+        gauge!(BEACON_NODE_ACTIVE, &[("index", index.to_string())]).set(value);
+    }
+
+    pub fn set_active_execution_node(index: usize, active: bool) {
+        let value = if active { 1.0 } else { 0.0 };
+        gauge!(EXECUTION_NODE_ACTIVE, &[("index", index.to_string())]).set(value);
+    }
+
+    pub fn set_execution_state_stale(stale: bool) {
+        gauge!(EXECUTION_STATE_STALE).set(if stale { 1.0 } else { 0.0 });
+    }
+
+    pub fn set_firewall_rpc_connected(url: &str, connected: bool) {
+        let value = if connected { 1.0 } else { 0.0 };
+        gauge!(FIREWALL_RPC_CONNECTED, &[("url", url.to_string())]).set(value);
+    }
+
     /// Mixed ----------------------------------------------------------------
     /// Observes the duration of an HTTP request by storing it in a histogram,
     /// and incrementing the total number of HTTP requests received.
@@ -133,4 +231,10 @@ impl ApiMetrics {
         counter!(HTTP_REQUESTS_TOTAL, &labels).increment(1);
         histogram!(HTTP_REQUESTS_DURATION_SECONDS, &labels,).record(duration.as_secs_f64());
     }
+
+    /// Histograms ----------------------------------------------------------------
+    pub fn observe_firewall_rpc_latency(url: &str, latency: Duration) {
+        histogram!(FIREWALL_RPC_LATENCY_SECONDS, &[("url", url.to_string())])
+            .record(latency.as_secs_f64());
+    }
 }
@@ -93,10 +93,10 @@ pub(crate) async fn get_test_config() -> Option<Opts> {
     };
 
     if let Some(url) = try_get_execution_api_url().await {
-        opts.execution_api_url = url.parse().expect("valid execution API URL");
+        opts.execution_api_urls = vec![url.parse().expect("valid execution API URL")];
     }
     if let Some(url) = try_get_beacon_api_url().await {
-        opts.beacon_api_url = url.parse().expect("valid beacon API URL");
+        opts.beacon_api_urls = vec![url.parse().expect("valid beacon API URL")];
     }
     if let Some(url) = try_get_engine_api_url().await {
         opts.engine_api_url = url.parse().expect("valid engine API URL");